@@ -0,0 +1,61 @@
+//! Mapping backend benchmarks
+//!
+//! Only one backend is compiled into a build, so run the benchmarks once per
+//! backend. Each run records its results under the backend's name, which
+//! places all three side by side in `target/criterion`:
+//!
+//! ```text
+//! cargo bench --bench backends
+//! cargo bench --bench backends --features phf
+//! cargo bench --bench backends --features const-table
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use mouse_codes::{Button, Platform};
+
+#[cfg(feature = "const-table")]
+const BACKEND: &str = "const-table";
+#[cfg(all(feature = "phf", not(feature = "const-table")))]
+const BACKEND: &str = "phf";
+#[cfg(not(any(feature = "phf", feature = "const-table")))]
+const BACKEND: &str = "hashmap";
+
+const PLATFORMS: [Platform; 3] = [Platform::Windows, Platform::Linux, Platform::MacOS];
+
+fn bench_to_code(c: &mut Criterion) {
+    let mut group = c.benchmark_group("to_code");
+    group.bench_function(BACKEND, |b| {
+        b.iter(|| {
+            let mut sum = 0;
+            for platform in PLATFORMS {
+                for button in Button::ALL {
+                    sum += black_box(button).to_code(black_box(platform));
+                }
+            }
+            sum
+        })
+    });
+    group.finish();
+}
+
+fn bench_from_code(c: &mut Criterion) {
+    let mut group = c.benchmark_group("from_code");
+    group.bench_function(BACKEND, |b| {
+        b.iter(|| {
+            let mut found = 0;
+            for platform in PLATFORMS {
+                // 包含无效代码，覆盖查找失败的路径
+                for code in 0..20 {
+                    if Button::from_code(black_box(code), black_box(platform)).is_some() {
+                        found += 1;
+                    }
+                }
+            }
+            found
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_to_code, bench_from_code);
+criterion_main!(benches);
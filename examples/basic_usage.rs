@@ -2,7 +2,9 @@
 //!
 //! 展示 mouse-codes 的基本功能和 API 使用
 
-use mouse_codes::{parse_button_ignore_case, parse_button_with_aliases, Button, Platform};
+use mouse_codes::{
+    parse_button_ignore_case, parse_button_with_aliases, Button, InputBackend, Platform,
+};
 use std::str::FromStr;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -11,6 +13,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 1. 平台检测
     let platform = Platform::current();
     println!("1. 当前平台: {}", platform);
+    if let Some(backend) = InputBackend::detect() {
+        println!(
+            "   输入后端: {} (代码空间: {})",
+            backend,
+            backend.code_space()
+        );
+    }
 
     // 2. 按钮到代码的转换
     println!("\n2. 按钮到代码转换:");
@@ -0,0 +1,84 @@
+//! 按钮重映射示例
+//!
+//! 从配置文本加载重映射规则，展示一对一、一对多、和弦、按钮转滚轮以及左手模式，
+//! 并演示运行时替换规则时已按下的按钮仍能正确释放
+
+use mouse_codes::remap::{Remapper, Rule};
+use mouse_codes::{parse_mouse_input, Button, MouseEvent};
+
+/// 配置文件中的规则，按钮名称支持别名
+const CONFIG: &str = "
+# 侧键当作中键
+back = middle
+# 前进键同时按下左右键
+forward = left + right
+# 右键+左键和弦发送 X2，需排在左手模式之前
+right + left = x2
+# 中键向下滚动三格
+middle = Scroll(VerticalDown, 3)
+";
+
+fn run(remapper: &mut Remapper, inputs: &[&str]) -> Result<(), Box<dyn std::error::Error>> {
+    for input in inputs {
+        let event = parse_mouse_input(input)?;
+        let output = remapper.process(&event);
+        let output: Vec<String> = output.iter().map(MouseEvent::to_string).collect();
+        println!("  {:<16} -> [{}]", input, output.join(", "));
+    }
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== 按钮重映射示例 ===");
+
+    let mut remapper = Remapper::from_config(CONFIG)?;
+    println!("\n已加载规则:\n{}", remapper);
+
+    println!("1. 一对一、一对多与按钮转滚轮:");
+    run(
+        &mut remapper,
+        &[
+            "Press(X1)",
+            "Release(X1)",
+            "Press(X2)",
+            "Release(X2)",
+            "Press(Middle)",
+            "Release(Middle)",
+        ],
+    )?;
+
+    println!("\n2. 和弦:");
+    run(
+        &mut remapper,
+        &[
+            "Press(Right)",
+            "Press(Left)",
+            "Release(Right)",
+            "Release(Left)",
+        ],
+    )?;
+
+    println!("\n3. 按住左键时切换到左手模式:");
+    run(&mut remapper, &["Press(Left)"])?;
+    remapper.set_rules(Rule::swap(Button::Left, Button::Right));
+    // 之前按下的左键仍按原映射释放，不会卡住
+    run(
+        &mut remapper,
+        &["Release(Left)", "Press(Left)", "Release(Left)"],
+    )?;
+
+    println!("\n4. 中途重置:");
+    run(&mut remapper, &["Press(Right)"])?;
+    println!("  重置 -> {:?}", remapper.reset());
+
+    println!("\n5. 无效配置:");
+    for line in ["left -> right", "left = thumb3"] {
+        println!(
+            "  {:<16} -> {}",
+            line,
+            Remapper::from_config(line).unwrap_err()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+//! 语义按钮角色示例
+//!
+//! 展示如何按主键/次键而不是左键/右键绑定操作，使左手用户无需特殊处理，
+//! 以及角色与 DOM `MouseEvent.button` 编号之间的对应关系
+
+use mouse_codes::parser::parse_button_for;
+use mouse_codes::{parse_button_role, parse_button_with_aliases, ButtonRole, Handedness};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== 语义按钮角色示例 ===");
+
+    println!("\n1. 角色在不同惯用手设置下对应的物理按钮:");
+    for role in ButtonRole::ALL {
+        println!(
+            "  {:<10} 右手: {:<7} 左手: {:<7} DOM button {} / buttons 位 {}",
+            role.as_str(),
+            role.button(Handedness::Right).as_str(),
+            role.button(Handedness::Left).as_str(),
+            role.dom_button(),
+            role.dom_buttons_bit()
+        );
+    }
+
+    println!("\n2. 配置中的角色名称:");
+    let bindings = [("选择", "primary"), ("菜单", "secondary"), ("返回", "back")];
+    for handedness in [Handedness::Right, Handedness::Left] {
+        println!("  {:?} 手:", handedness);
+        for (action, name) in bindings {
+            let button = parse_button_for(name, handedness)?;
+            println!("    {} = {:<10} -> {}", action, name, button);
+        }
+    }
+    // 角色名称不是按钮别名，必须结合惯用手解析
+    println!(
+        "  parse_button_with_aliases(\"primary\") 失败: {}",
+        parse_button_with_aliases("primary").unwrap_err()
+    );
+
+    println!("\n3. 物理按钮事件转换为角色:");
+    let handedness = Handedness::Left;
+    for name in ["left", "right", "wheel", "extra3"] {
+        let button = parse_button_with_aliases(name)?;
+        match ButtonRole::of(button, handedness) {
+            Some(role) => println!("  {} -> {}", button, role),
+            None => println!("  {} -> 无角色", button),
+        }
+    }
+
+    println!("\n4. 浏览器事件的 button 值:");
+    for value in 0..6 {
+        match ButtonRole::from_dom_button(value) {
+            Some(role) => println!(
+                "  button {} -> {} -> 左手物理按钮 {}",
+                value,
+                role,
+                role.button(Handedness::Left)
+            ),
+            None => println!("  button {} -> 未定义", value),
+        }
+    }
+
+    println!("\n解析角色名称: {:?}", parse_button_role("Aux")?);
+    Ok(())
+}
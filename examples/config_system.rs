@@ -4,8 +4,10 @@
 //! 注意：运行此示例需要启用 serde 特性：
 //! cargo run --example config_system --features serde
 
-use mouse_codes::{parse_button_ignore_case, Button};
-use std::collections::HashMap;
+#[cfg(feature = "serde")]
+use mouse_codes::binding::{Binding, BindingMap};
+#[cfg(feature = "serde")]
+use mouse_codes::{Button, MouseEvent};
 
 // 只在启用 serde 特性时编译这部分代码
 #[cfg(feature = "serde")]
@@ -14,24 +16,31 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "serde")]
 #[derive(Debug, Serialize, Deserialize)]
 struct AppConfig {
-    mouse_bindings: HashMap<String, String>,
+    /// 动作名称到输入的绑定，加载时即完成解析
+    mouse_bindings: BindingMap<String>,
     sensitivity: f32,
 }
 
-#[cfg(not(feature = "serde"))]
-#[derive(Debug)]
-struct AppConfig {
-    mouse_bindings: HashMap<String, String>,
-    sensitivity: f32,
+/// 使用 serde 适配器的配置：按钮以名称、事件以文本形式保存
+#[cfg(feature = "serde")]
+#[derive(Debug, Serialize, Deserialize)]
+struct MacroConfig {
+    #[serde(with = "mouse_codes::serde_adapters::button")]
+    trigger: Button,
+    #[serde(with = "mouse_codes::serde_adapters::windows_code")]
+    trigger_code: Button,
+    #[serde(with = "mouse_codes::serde_adapters::mouse_event")]
+    action: MouseEvent,
 }
 
+#[cfg(feature = "serde")]
 impl AppConfig {
     fn default() -> Self {
-        let mut bindings = HashMap::new();
-        bindings.insert("primary_action".to_string(), "left".to_string());
-        bindings.insert("secondary_action".to_string(), "right".to_string());
-        bindings.insert("special_action".to_string(), "x1".to_string());
-        bindings.insert("menu_action".to_string(), "middle".to_string());
+        let mut bindings = BindingMap::new();
+        bindings.bind(Button::Left, "primary_action".to_string());
+        bindings.bind(Button::Right, "secondary_action".to_string());
+        bindings.bind(Button::X1, "special_action".to_string());
+        bindings.bind(Button::Middle, "menu_action".to_string());
 
         Self {
             mouse_bindings: bindings,
@@ -39,20 +48,25 @@ impl AppConfig {
         }
     }
 
-    fn get_button_for_action(&self, action: &str) -> Option<Button> {
+    fn get_bindings_for_action(&self, action: &str) -> Vec<Binding> {
         self.mouse_bindings
-            .get(action)
-            .and_then(|btn_str| parse_button_ignore_case(btn_str).ok())
+            .bindings_for(&action.to_string())
+            .copied()
+            .collect()
     }
 
     fn validate_bindings(&self) -> Result<(), Vec<String>> {
-        let mut errors = Vec::new();
-
-        for (action, btn_str) in &self.mouse_bindings {
-            if parse_button_ignore_case(btn_str).is_err() {
-                errors.push(format!("无效的按钮绑定: {} -> {}", action, btn_str));
-            }
-        }
+        let errors: Vec<String> = self
+            .mouse_bindings
+            .conflicts()
+            .iter()
+            .map(|conflict| {
+                format!(
+                    "输入 {} 同时绑定到 {} 和 {}",
+                    conflict.binding, conflict.first, conflict.second
+                )
+            })
+            .collect();
 
         if errors.is_empty() {
             Ok(())
@@ -64,97 +78,120 @@ impl AppConfig {
     fn print_bindings(&self) {
         println!("鼠标灵敏度: {}", self.sensitivity);
         println!("按键绑定:");
-        for (action, button_str) in &self.mouse_bindings {
-            if let Ok(button) = parse_button_ignore_case(button_str) {
-                println!("  {} -> {} ({})", action, button, button_str);
-            } else {
-                println!("  {} -> {} (无效)", action, button_str);
-            }
+        for (binding, action) in self.mouse_bindings.iter() {
+            println!("  {} -> {}", action, binding);
         }
     }
 }
 
+// 检查是否启用了 serde 特性
+#[cfg(not(feature = "serde"))]
+fn main() {
+    println!("注意: 此示例需要启用 serde 特性");
+    println!("请使用以下命令运行: cargo run --example config_system --features serde");
+}
+
+#[cfg(feature = "serde")]
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // 检查是否启用了 serde 特性
-    #[cfg(not(feature = "serde"))]
-    {
-        println!("注意: 此示例需要启用 serde 特性");
-        println!("请使用以下命令运行: cargo run --example config_system --features serde");
-        return Ok(());
+    // 加载默认配置
+    let config = AppConfig::default();
+    println!("默认配置:");
+    config.print_bindings();
+
+    // 验证配置
+    match config.validate_bindings() {
+        Ok(()) => println!("\n✅ 配置验证通过"),
+        Err(errors) => {
+            println!("\n❌ 配置错误:");
+            for error in errors {
+                println!("  - {}", error);
+            }
+        }
     }
 
-    #[cfg(feature = "serde")]
+    // 演示从字符串加载配置：一个动作可以绑定多个输入，包括和弦、修饰键与滚轮方向
+    println!("\n--- 从 JSON 加载配置 ---");
+    let config_json = r#"
     {
-        use serde_json;
-
-        // 加载默认配置
-        let config = AppConfig::default();
-        println!("默认配置:");
-        config.print_bindings();
-
-        // 验证配置
-        match config.validate_bindings() {
-            Ok(()) => println!("\n✅ 配置验证通过"),
-            Err(errors) => {
-                println!("\n❌ 配置错误:");
-                for error in errors {
-                    println!("  - {}", error);
-                }
-            }
+        "mouse_bindings": {
+            "attack": "left",
+            "block": "right",
+            "dodge": ["x1", "Shift+WheelDown"],
+            "interact": "x2",
+            "map": ["middle", "Right+Left"]
+        },
+        "sensitivity": 1.5
+    }
+    "#;
+
+    let user_config: AppConfig = serde_json::from_str(config_json)?;
+    user_config.print_bindings();
+
+    // 演示查询操作对应的按钮
+    println!("\n--- 查询操作绑定 ---");
+    let actions = ["attack", "block", "dodge", "map", "jump"];
+    for action in actions {
+        let bindings = user_config.get_bindings_for_action(action);
+        if bindings.is_empty() {
+            println!("{} -> 未绑定", action);
+        } else {
+            let names: Vec<String> = bindings.iter().map(Binding::to_string).collect();
+            println!("{} -> {}", action, names.join(", "));
         }
+    }
 
-        // 演示从字符串加载配置
-        println!("\n--- 从 JSON 加载配置 ---");
-        let config_json = r#"
-        {
-            "mouse_bindings": {
-                "attack": "left",
-                "block": "right", 
-                "dodge": "x1",
-                "interact": "x2",
-                "map": "middle"
-            },
-            "sensitivity": 1.5
-        }
-        "#;
-
-        let user_config: AppConfig = serde_json::from_str(config_json)?;
-        user_config.print_bindings();
-
-        // 演示查询操作对应的按钮
-        println!("\n--- 查询操作绑定 ---");
-        let actions = ["attack", "block", "dodge", "map"];
-        for action in actions {
-            if let Some(button) = user_config.get_button_for_action(action) {
-                println!("{} -> {}", action, button);
-            } else {
-                println!("{} -> 未绑定", action);
-            }
-        }
+    // 演示错误配置：无效按钮在加载时即被拒绝
+    println!("\n--- 错误配置演示 ---");
+    let bad_config_json = r#"
+    {
+        "mouse_bindings": {
+            "valid_action": "left",
+            "invalid_action": "invalid_button"
+        },
+        "sensitivity": 1.0
+    }
+    "#;
 
-        // 演示错误配置
-        println!("\n--- 错误配置演示 ---");
-        let bad_config_json = r#"
-        {
-            "mouse_bindings": {
-                "valid_action": "left",
-                "invalid_action": "invalid_button"
-            },
-            "sensitivity": 1.0
-        }
-        "#;
-
-        let bad_config: AppConfig = serde_json::from_str(bad_config_json)?;
-        match bad_config.validate_bindings() {
-            Ok(()) => println!("✅ 配置验证通过"),
-            Err(errors) => {
-                println!("❌ 配置错误:");
-                for error in errors {
-                    println!("  - {}", error);
-                }
+    match serde_json::from_str::<AppConfig>(bad_config_json) {
+        Ok(_) => println!("✅ 配置加载成功"),
+        Err(error) => println!("❌ 配置错误: {}", error),
+    }
+
+    // 同一输入绑定到两个动作属于冲突
+    let conflicting_json = r#"
+    {
+        "mouse_bindings": { "attack": "left", "select": ["lmb", "Ctrl+Left"] },
+        "sensitivity": 1.0
+    }
+    "#;
+    let conflicting: AppConfig = serde_json::from_str(conflicting_json)?;
+    match conflicting.validate_bindings() {
+        Ok(()) => println!("✅ 配置验证通过"),
+        Err(errors) => {
+            println!("❌ 配置错误:");
+            for error in errors {
+                println!("  - {}", error);
             }
         }
     }
+    println!(
+        "保存为: {}",
+        serde_json::to_string(&conflicting.mouse_bindings)?
+    );
+
+    // 演示 serde 适配器
+    println!("\n--- serde 适配器演示 ---");
+    let macro_json = r#"
+    {
+        "trigger": "back",
+        "trigger_code": 6,
+        "action": "Scroll(VerticalUp, 3)"
+    }
+    "#;
+
+    let macro_config: MacroConfig = serde_json::from_str(macro_json)?;
+    println!("解析结果: {:?}", macro_config);
+    println!("序列化结果: {}", serde_json::to_string(&macro_config)?);
 
     Ok(())
 }
@@ -0,0 +1,73 @@
+//! 多显示器坐标示例
+//!
+//! 展示相对移动与绝对坐标的互转、虚拟桌面边界钳制、SendInput 归一化坐标以及逻辑/物理坐标换算
+
+use mouse_codes::motion::desktop::{Desktop, Monitor};
+use mouse_codes::{parse_mouse_input, MouseEvent};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== 多显示器坐标示例 ===");
+
+    // 主显示器 1920x1080；右侧 4K 显示器缩放 150%，顶端下移 200 像素，左侧留出空隙
+    let mut desktop = Desktop::new([
+        Monitor::new(0, 0, 1920, 1080),
+        Monitor::new(1920, -200, 3840, 2160).with_scale(1.5),
+    ]);
+    println!("虚拟桌面: {:?}", desktop.bounds());
+
+    println!("\n1. 相对移动转换为绝对坐标:");
+    let motions = [
+        "RelativeMove(1000, 500)",
+        "RelativeMove(2000, -800)",
+        "RelativeMove(5000, 0)",
+        "Press(Left)",
+        "RelativeMove(-4000, 1500)",
+        "Release(Left)",
+    ]
+    .into_iter()
+    .map(parse_mouse_input)
+    .collect::<Result<Vec<MouseEvent>, _>>()?;
+    for event in &motions {
+        // 超出显示器的位置会被钳制到最近的显示器边缘
+        println!(
+            "  {:<26} -> {}",
+            event.to_string(),
+            desktop.to_absolute(event)
+        );
+    }
+
+    println!("\n2. 绝对坐标转换为相对移动:");
+    for event in [MouseEvent::Move(100, 100), MouseEvent::Move(3000, -500)] {
+        println!(
+            "  {:<26} -> {}",
+            event.to_string(),
+            desktop.to_relative(&event)
+        );
+    }
+
+    println!("\n3. SendInput 归一化坐标 (0..65535):");
+    for (x, y) in [(0, 0), (1920, 900), (5759, 1959)] {
+        let normalized = desktop.normalize(x, y);
+        let primary = desktop.monitors()[0].normalize(x, y);
+        println!(
+            "  ({}, {}) -> 虚拟桌面 {:?} -> 还原 {:?}，主显示器 {:?}",
+            x,
+            y,
+            normalized,
+            desktop.denormalize(normalized.0, normalized.1),
+            primary
+        );
+    }
+
+    println!("\n4. 逻辑坐标与物理坐标:");
+    for (x, y) in [(960, 540), (1920, -200), (3840, 880)] {
+        let logical = desktop.to_logical(x, y);
+        let physical = logical.and_then(|(lx, ly)| desktop.to_physical(lx, ly));
+        println!(
+            "  物理 ({}, {}) -> 逻辑 {:?} -> 物理 {:?}",
+            x, y, logical, physical
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+//! evdev 数据流回放示例
+//!
+//! 展示如何在没有设备的情况下，用字节夹具解码 Linux `input_event` 数据流
+
+use mouse_codes::protocol::evdev::{
+    Decoder, TimeLayout, ABS_X, ABS_Y, EV_ABS, EV_KEY, EV_REL, EV_SYN, REL_WHEEL, REL_WHEEL_HI_RES,
+    REL_X, REL_Y, SYN_DROPPED, SYN_REPORT,
+};
+use mouse_codes::{Button, CodeSpace};
+
+/// 按指定布局编码一条 input_event 记录
+fn record(layout: TimeLayout, usec: i64, kind: u16, code: u16, value: i32) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(layout.record_size());
+    match layout {
+        TimeLayout::Time64 => {
+            bytes.extend_from_slice(&1i64.to_ne_bytes());
+            bytes.extend_from_slice(&usec.to_ne_bytes());
+        }
+        TimeLayout::Time32 => {
+            bytes.extend_from_slice(&1i32.to_ne_bytes());
+            bytes.extend_from_slice(&(usec as i32).to_ne_bytes());
+        }
+    }
+    bytes.extend_from_slice(&kind.to_ne_bytes());
+    bytes.extend_from_slice(&code.to_ne_bytes());
+    bytes.extend_from_slice(&value.to_ne_bytes());
+    bytes
+}
+
+/// 构造一段包含按键、移动、高精度滚轮、绝对坐标和丢包的录制数据
+fn capture(layout: TimeLayout) -> Vec<u8> {
+    let left = CodeSpace::Evdev.to_code(Button::Left).unwrap() as u16;
+    let side = CodeSpace::Evdev.to_code(Button::X1).unwrap() as u16;
+    let records = [
+        (1000, EV_KEY, left, 1),
+        (1000, EV_SYN, SYN_REPORT, 0),
+        (2000, EV_REL, REL_X, 5),
+        (2000, EV_REL, REL_Y, -3),
+        (2000, EV_SYN, SYN_REPORT, 0),
+        // 高精度滚轮：两次半格合成一格，旧式 REL_WHEEL 被忽略
+        (3000, EV_REL, REL_WHEEL_HI_RES, 60),
+        (3000, EV_SYN, SYN_REPORT, 0),
+        (4000, EV_REL, REL_WHEEL_HI_RES, 60),
+        (4000, EV_REL, REL_WHEEL, 1),
+        (4000, EV_SYN, SYN_REPORT, 0),
+        // 内核缓冲区溢出：直到下一个 SYN_REPORT 的记录都会被丢弃
+        (5000, EV_SYN, SYN_DROPPED, 0),
+        (5000, EV_KEY, side, 1),
+        (5000, EV_SYN, SYN_REPORT, 0),
+        (6000, EV_ABS, ABS_X, 640),
+        (6000, EV_ABS, ABS_Y, 480),
+        (6000, EV_SYN, SYN_REPORT, 0),
+        (7000, EV_ABS, ABS_Y, 500),
+        (7000, EV_KEY, left, 0),
+        (7000, EV_SYN, SYN_REPORT, 0),
+    ];
+    records
+        .into_iter()
+        .flat_map(|(usec, kind, code, value)| record(layout, usec, kind, code, value))
+        .collect()
+}
+
+fn main() -> std::io::Result<()> {
+    println!("=== evdev 数据流回放示例 ===");
+
+    for layout in [TimeLayout::Time64, TimeLayout::Time32] {
+        let bytes = capture(layout);
+        println!(
+            "\n{:?} 布局（每条记录 {} 字节，共 {} 字节）:",
+            layout,
+            layout.record_size(),
+            bytes.len()
+        );
+
+        // 任何实现 Read 的类型都可以作为数据源
+        for event in Decoder::with_layout(bytes.as_slice(), layout) {
+            let event = event?;
+            println!("  [{:?}] {}", event.time, event.event);
+        }
+    }
+
+    Ok(())
+}
@@ -2,58 +2,97 @@
 //!
 //! 展示如何在游戏中使用 mouse-codes 处理鼠标输入绑定
 
-use mouse_codes::{parse_button_with_aliases, Button, Platform};
-use std::collections::HashMap;
+use mouse_codes::binding::{Binding, BindingMap, Modifiers};
+use mouse_codes::{Button, ButtonSet, CodeSpace, MouseEvent, Platform, ScrollDirection};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GameAction {
+    Attack,
+    Aim,
+    Reload,
+    SwitchWeapon,
+    NextWeapon,
+    PreviousWeapon,
+    Melee,
+    Zoom,
+}
+
+impl fmt::Display for GameAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            GameAction::Attack => "攻击",
+            GameAction::Aim => "瞄准",
+            GameAction::Reload => "换弹",
+            GameAction::SwitchWeapon => "切换武器",
+            GameAction::NextWeapon => "下一把武器",
+            GameAction::PreviousWeapon => "上一把武器",
+            GameAction::Melee => "近战",
+            GameAction::Zoom => "缩放",
+        };
+        f.write_str(name)
+    }
+}
 
 struct GameInput {
-    key_bindings: HashMap<String, Button>,
+    bindings: BindingMap<GameAction>,
     platform: Platform,
+    held: ButtonSet,
 }
 
 impl GameInput {
     fn new() -> Self {
-        let mut key_bindings = HashMap::new();
-        key_bindings.insert("attack".to_string(), Button::Left);
-        key_bindings.insert("aim".to_string(), Button::Right);
-        key_bindings.insert("reload".to_string(), Button::X1);
-        key_bindings.insert("switch_weapon".to_string(), Button::X2);
+        let mut bindings = BindingMap::new();
+        bindings.bind(Button::Left, GameAction::Attack);
+        bindings.bind(Button::Right, GameAction::Aim);
+        bindings.bind(Button::X1, GameAction::Reload);
+        bindings.bind(Button::X2, GameAction::SwitchWeapon);
+        bindings.bind(ScrollDirection::VerticalUp, GameAction::NextWeapon);
+        bindings.bind(ScrollDirection::VerticalDown, GameAction::PreviousWeapon);
+        // 按住右键再按左键：和弦比单独的左键更具体，优先匹配
+        bindings.bind(
+            Binding::chord(ButtonSet::new().with(Button::Right), Button::Left),
+            GameAction::Melee,
+        );
+        bindings.bind(
+            Binding::button(Button::Middle).with_modifiers(Modifiers::SHIFT),
+            GameAction::Zoom,
+        );
 
         Self {
-            key_bindings,
+            bindings,
             platform: Platform::current(),
+            held: ButtonSet::new(),
         }
     }
 
-    fn handle_mouse_event(&self, button_code: usize) -> Option<&str> {
-        if let Some(button) = Button::from_code(button_code, self.platform) {
-            match button {
-                Button::Left => Some("攻击"),
-                Button::Right => Some("瞄准"),
-                Button::X1 => Some("换弹"),
-                Button::X2 => Some("切换武器"),
-                _ => None,
-            }
-        } else {
-            None
-        }
+    fn handle_event(&mut self, event: &MouseEvent, modifiers: Modifiers) -> Option<GameAction> {
+        let action = self.bindings.action(event, self.held, modifiers).copied();
+        // 按钮状态在查询之后更新，和弦只看此前已按住的按钮
+        self.held.apply(event);
+        action
+    }
+
+    fn handle_code(&mut self, button_code: usize) -> Option<GameAction> {
+        let button = Button::from_code(button_code, self.platform)?;
+        self.handle_event(&MouseEvent::Press(button), Modifiers::NONE)
     }
 
-    fn rebind_key(
+    fn rebind(
         &mut self,
-        action: &str,
-        button_str: &str,
+        action: GameAction,
+        input: &str,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let button = parse_button_with_aliases(button_str)?;
-        self.key_bindings.insert(action.to_string(), button);
+        let binding: Binding = input.parse()?;
+        self.bindings.rebind(action, binding);
         Ok(())
     }
 
     fn print_bindings(&self) {
         println!("当前平台: {}", self.platform);
         println!("按键绑定:");
-        for (action, button) in &self.key_bindings {
-            let code = button.to_code(self.platform);
-            println!("  {} -> {} (代码: {})", action, button, code);
+        for (binding, action) in self.bindings.iter() {
+            println!("  {} -> {}", action, binding);
         }
     }
 }
@@ -62,19 +101,74 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut game_input = GameInput::new();
     game_input.print_bindings();
 
-    // 模拟处理鼠标事件
+    // 模拟处理平台按钮代码
     println!("\n处理鼠标事件:");
-    let test_codes = [1, 2, 5, 6]; // 左键、右键、X1、X2 的 Windows 代码
+    let platform = game_input.platform;
+    let test_codes: Vec<usize> = [Button::Left, Button::Right, Button::X1, Button::X2]
+        .iter()
+        .map(|button| button.to_code(platform))
+        .collect();
     for &code in &test_codes {
-        if let Some(action) = game_input.handle_mouse_event(code) {
+        if let Some(action) = game_input.handle_code(code) {
             println!("  代码 {}: {}", code, action);
         }
+        if let Some(button) = Button::from_code(code, platform) {
+            game_input.handle_event(&MouseEvent::Release(button), Modifiers::NONE);
+        }
+    }
+
+    // 和弦、滚轮与修饰键
+    println!("\n事件序列:");
+    let events = [
+        (MouseEvent::Press(Button::Right), Modifiers::NONE),
+        (MouseEvent::Press(Button::Left), Modifiers::NONE),
+        (MouseEvent::Release(Button::Left), Modifiers::NONE),
+        (MouseEvent::Release(Button::Right), Modifiers::NONE),
+        (
+            MouseEvent::Scroll(ScrollDirection::VerticalUp, 1),
+            Modifiers::NONE,
+        ),
+        (
+            MouseEvent::Scroll(ScrollDirection::VerticalDown, 1),
+            Modifiers::NONE,
+        ),
+        (MouseEvent::Press(Button::Middle), Modifiers::SHIFT),
+        (MouseEvent::Press(Button::Middle), Modifiers::NONE),
+    ];
+    for (event, modifiers) in &events {
+        let action = game_input.handle_event(event, *modifiers);
+        let prefix = if modifiers.is_empty() {
+            String::new()
+        } else {
+            format!("{}+", modifiers)
+        };
+        match action {
+            Some(action) => println!("  {}{} -> {}", prefix, event, action),
+            None => println!("  {}{} -> (无)", prefix, event),
+        }
     }
 
-    // 重新绑定按键
-    println!("\n重新绑定 'attack' 到 'middle':");
-    game_input.rebind_key("attack", "middle")?;
+    // 重新绑定：解析失败时保留原有绑定
+    println!("\n重新绑定 攻击 到 'Ctrl+middle':");
+    game_input.rebind(GameAction::Attack, "Ctrl+middle")?;
+    if let Err(error) = game_input.rebind(GameAction::Reload, "Ctrl+thumb9") {
+        println!("  无法重新绑定 换弹: {}", error);
+    }
     game_input.print_bindings();
 
+    // 游戏引擎和窗口库各有自己的按钮编号
+    println!("\n游戏引擎按钮编号:");
+    for space in [CodeSpace::Sdl, CodeSpace::Glfw, CodeSpace::Winit] {
+        let codes: Vec<String> = [Button::Left, Button::Right, Button::Middle, Button::X1]
+            .iter()
+            .filter_map(|button| Some(format!("{}={}", button, space.to_code(*button)?)))
+            .collect();
+        println!("  {}: {}", space, codes.join(", "));
+    }
+    // 例如 SDL 事件中的 event.button.button == 2 表示中键
+    if let Some(button) = CodeSpace::Sdl.from_code(2) {
+        println!("  SDL 按钮 2 -> {}", button);
+    }
+
     Ok(())
 }
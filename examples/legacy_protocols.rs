@@ -0,0 +1,79 @@
+//! PS/2 与串口鼠标协议示例
+//!
+//! 展示如何把事件编码为 PS/2 或串口数据包，再从带噪声的字节流中解码回来
+
+use mouse_codes::protocol::ps2::{Ps2Decoder, Ps2Encoder, Ps2Protocol};
+use mouse_codes::protocol::serial::{SerialDecoder, SerialEncoder, SerialProtocol};
+use mouse_codes::{parse_mouse_input, MouseEvent};
+
+/// 以十六进制打印字节
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== PS/2 与串口鼠标协议示例 ===");
+
+    let events = [
+        "Press(Left)",
+        "RelativeMove(300, -20)",
+        "Release(Left)",
+        "Scroll(VerticalDown, 2)",
+        "Scroll(HorizontalRight, 1)",
+        "Press(X1)",
+        "Release(X1)",
+    ]
+    .into_iter()
+    .map(parse_mouse_input)
+    .collect::<Result<Vec<MouseEvent>, _>>()?;
+
+    for protocol in [
+        Ps2Protocol::Standard,
+        Ps2Protocol::IntelliMouse,
+        Ps2Protocol::Explorer,
+    ] {
+        println!(
+            "\nPS/2 {:?}（设备 ID {}，每包 {} 字节）:",
+            protocol,
+            protocol.device_id(),
+            protocol.packet_size()
+        );
+        let mut encoder = Ps2Encoder::new(protocol);
+        // 开头的两个字节模拟中途接入的数据流，解码器会跳过它们重新同步
+        let mut stream = vec![0x05, 0xff];
+        for event in &events {
+            let bytes = encoder.encode(event);
+            println!("  {:<28} -> [{}]", event.to_string(), hex(&bytes));
+            stream.extend(bytes);
+        }
+
+        let decoded = Ps2Decoder::new(protocol).decode(&stream);
+        let decoded: Vec<String> = decoded.iter().map(ToString::to_string).collect();
+        println!("  解码: {}", decoded.join(", "));
+    }
+
+    for protocol in [SerialProtocol::Microsoft, SerialProtocol::MouseSystems] {
+        println!(
+            "\n串口 {:?}（每包 {} 字节）:",
+            protocol,
+            protocol.packet_size()
+        );
+        let mut encoder = SerialEncoder::new(protocol);
+        let mut stream = vec![0x12];
+        for event in &events {
+            let bytes = encoder.encode(event);
+            println!("  {:<28} -> [{}]", event.to_string(), hex(&bytes));
+            stream.extend(bytes);
+        }
+
+        let decoded = SerialDecoder::new(protocol).decode(&stream);
+        let decoded: Vec<String> = decoded.iter().map(ToString::to_string).collect();
+        println!("  解码: {}", decoded.join(", "));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+//! 事件流滤波示例
+//!
+//! 用确定的伪随机抖动构造输入，展示平滑、抖动抑制与按键消抖滤波器及其组合
+
+use std::time::Duration;
+
+use mouse_codes::motion::filter::{
+    Debounce, EventFilter, ExponentialSmoothing, JitterFilter, OneEuroFilter,
+};
+use mouse_codes::{Button, MouseEvent, TimedEvent};
+
+/// 简单的线性同余发生器，保证每次运行得到相同的抖动
+struct Lcg(u32);
+
+impl Lcg {
+    /// 返回 -1、0 或 1
+    fn jitter(&mut self) -> i32 {
+        self.0 = self.0.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        (self.0 >> 16) as i32 % 3 - 1
+    }
+}
+
+fn at(ms: u64, event: MouseEvent) -> TimedEvent {
+    TimedEvent::new(Duration::from_millis(ms), event)
+}
+
+fn print(title: &str, events: impl IntoIterator<Item = TimedEvent>) {
+    println!("\n{}:", title);
+    for event in events {
+        println!("  {:>4}ms {}", event.time.as_millis(), event.event);
+    }
+}
+
+fn main() {
+    println!("=== 事件流滤波示例 ===");
+
+    // 静止时带 ±1 抖动的绝对坐标，随后匀速向右移动
+    let mut rng = Lcg(42);
+    let absolute: Vec<TimedEvent> = (0..12)
+        .map(|i| {
+            let x = if i < 6 { 100 } else { 100 + (i - 5) * 10 };
+            at(
+                8 * i as u64,
+                MouseEvent::Move(x + rng.jitter(), 50 + rng.jitter()),
+            )
+        })
+        .collect();
+    print("原始绝对坐标", absolute.iter().copied());
+    print(
+        "指数平滑 (alpha 0.5)",
+        ExponentialSmoothing::new(0.5).stream(absolute.iter().copied()),
+    );
+    print(
+        "One Euro (1 Hz, beta 0.05)",
+        OneEuroFilter::new(1.0, 0.05).stream(absolute.iter().copied()),
+    );
+    print(
+        "抖动抑制 (半径 2)",
+        JitterFilter::new(2.0).stream(absolute.iter().copied()),
+    );
+
+    // 磨损微动开关：一次点击中出现了 5 毫秒的释放-按下回弹，随后是一次正常双击
+    let clicks = [
+        at(0, MouseEvent::Press(Button::Left)),
+        at(40, MouseEvent::Release(Button::Left)),
+        at(45, MouseEvent::Press(Button::Left)),
+        at(50, MouseEvent::RelativeMove(1, -1)),
+        at(90, MouseEvent::Release(Button::Left)),
+        at(200, MouseEvent::Press(Button::Left)),
+        at(260, MouseEvent::Release(Button::Left)),
+    ];
+    print("原始按键", clicks);
+    print(
+        "消抖 (20ms)",
+        Debounce::new(Duration::from_millis(20)).stream(clicks),
+    );
+
+    // 相对移动：先消除抖动再平滑，两个滤波器串联
+    let mut rng = Lcg(7);
+    let relative: Vec<TimedEvent> = (0..10)
+        .map(|i| {
+            let dx = if i < 5 { rng.jitter() } else { 6 };
+            at(8 * i as u64, MouseEvent::RelativeMove(dx, rng.jitter()))
+        })
+        .collect();
+    print("原始相对移动", relative.iter().copied());
+    let chained = ExponentialSmoothing::new(0.6).stream(JitterFilter::new(3.0).stream(relative));
+    print("抖动抑制 + 指数平滑", chained);
+}
@@ -0,0 +1,89 @@
+//! 指针加速示例
+//!
+//! 把同一段带时间戳的相对移动分别交给 libinput、Windows 与 macOS 的加速曲线，
+//! 输出是确定的：相同输入总是得到相同的光标移动
+
+use std::time::Duration;
+
+use mouse_codes::motion::acceleration::{
+    AccelerationProfile, Accelerator, LibinputAdaptive, LibinputFlat, MacAcceleration,
+    WindowsEnhancedPrecision,
+};
+use mouse_codes::{parse_mouse_input, MouseEvent, TimedEvent};
+
+/// 以 8 毫秒间隔为事件加上时间戳
+fn timed(events: &[MouseEvent]) -> Vec<TimedEvent> {
+    events
+        .iter()
+        .enumerate()
+        .map(|(i, event)| TimedEvent::new(Duration::from_millis(8 * i as u64), *event))
+        .collect()
+}
+
+/// 累加所有相对移动
+fn total(events: &[TimedEvent]) -> (i32, i32) {
+    events
+        .iter()
+        .fold((0, 0), |(x, y), event| match event.event {
+            MouseEvent::RelativeMove(dx, dy) => (x + dx, y + dy),
+            _ => (x, y),
+        })
+}
+
+fn run<P: AccelerationProfile>(name: &str, profile: P, input: &[TimedEvent]) {
+    let output: Vec<TimedEvent> = Accelerator::new(profile)
+        .stream(input.iter().copied())
+        .collect();
+    println!("\n{}:", name);
+    for event in &output {
+        println!("  {:>4}ms {}", event.time.as_millis(), event.event);
+    }
+    println!("  合计 {:?}", total(&output));
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== 指针加速示例 ===");
+
+    // 先缓慢移动，按下左键快速甩动，再缓慢移动
+    let mut events = vec!["RelativeMove(1, 0)"; 6];
+    events.extend(["Press(Left)", "RelativeMove(20, 5)", "RelativeMove(40, 10)"]);
+    events.extend(["Release(Left)", "RelativeMove(1, 1)", "RelativeMove(1, 1)"]);
+    let events = events
+        .into_iter()
+        .map(parse_mouse_input)
+        .collect::<Result<Vec<MouseEvent>, _>>()?;
+    let input = timed(&events);
+    println!("输入合计 {:?}", total(&input));
+
+    println!("\n不同速度下的增益:");
+    println!("  counts/ms  flat   adaptive  windows  macos");
+    for speed in [0.0, 0.1, 0.5, 1.0, 2.5, 5.0] {
+        println!(
+            "  {:>9.1}  {:.3}  {:.3}     {:.3}    {:.3}",
+            speed,
+            LibinputFlat::new(0.0).factor(speed),
+            LibinputAdaptive::new(0.0).factor(speed),
+            WindowsEnhancedPrecision::new().factor(speed),
+            MacAcceleration::new(1.0).factor(speed),
+        );
+    }
+
+    run("libinput flat (-0.5)", LibinputFlat::new(-0.5), &input);
+    run("libinput adaptive", LibinputAdaptive::new(0.0), &input);
+    run(
+        "Windows 提高指针精确度",
+        WindowsEnhancedPrecision::new().with_resolution(800.0, 96.0),
+        &input,
+    );
+    run("macOS", MacAcceleration::new(1.0).with_dpi(800.0), &input);
+
+    // 无时间戳的事件按固定间隔处理，小数部分会累积到后续移动中
+    println!("\n余数累积（flat -0.75，每次移动 1 count）:");
+    let mut accelerator = Accelerator::new(LibinputFlat::new(-0.75));
+    for _ in 0..5 {
+        let output = accelerator.apply(&MouseEvent::RelativeMove(1, 0));
+        println!("  {:?}  余数 {:?}", output, accelerator.remainder());
+    }
+
+    Ok(())
+}
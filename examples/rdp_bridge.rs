@@ -0,0 +1,75 @@
+//! RDP 鼠标输入事件示例
+//!
+//! 展示如何把鼠标事件编码为 RDP 快速路径和慢速路径输入事件，并解码回来
+
+use mouse_codes::protocol::rdp::{PointerEvent, PointerKind, RdpDecoder, RdpEncoder};
+use mouse_codes::{parse_mouse_input, MouseEvent};
+
+/// 以十六进制打印字节
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== RDP 鼠标输入事件示例 ===");
+
+    let events = [
+        "Move(640, 360)",
+        "Press(Right)",
+        "Release(Right)",
+        "Scroll(VerticalDown, 1)",
+        "Scroll(HorizontalLeft, 1)",
+        "Press(X2)",
+        "Release(X2)",
+    ]
+    .into_iter()
+    .map(parse_mouse_input)
+    .collect::<Result<Vec<MouseEvent>, _>>()?;
+
+    let mut encoder = RdpEncoder::new();
+    let mut fast_path = Vec::new();
+    println!("\n1. 编码（pointerFlags / 快速路径 / 慢速路径）:");
+    for event in &events {
+        for pointer in encoder.encode(event) {
+            let fast = pointer.to_fast_path();
+            println!(
+                "  {:<26} {:?} {:#06x}  [{}]  [{}]",
+                event.to_string(),
+                pointer.kind,
+                pointer.flags,
+                hex(&fast),
+                hex(&pointer.to_slow_path(1000))
+            );
+            fast_path.extend(fast);
+        }
+    }
+
+    println!("\n2. 解码快速路径数据:");
+    let mut decoder = RdpDecoder::new();
+    for chunk in fast_path.chunks(PointerEvent::FAST_PATH_SIZE) {
+        if let Some(pointer) = PointerEvent::from_fast_path(chunk) {
+            for event in decoder.decode(&pointer) {
+                println!("  {}", event);
+            }
+        }
+    }
+
+    // 高精度滚轮每次只发送部分刻度，解码器会累积余数
+    println!("\n3. 高精度滚轮（每次 40 单位）:");
+    let mut decoder = RdpDecoder::new();
+    for _ in 0..3 {
+        let pointer = PointerEvent::from_bytes(PointerKind::Mouse, &[0x28, 0x02, 0, 0, 0, 0])
+            .expect("6 字节事件体");
+        println!(
+            "  rotation {:?} -> {:?}",
+            pointer.wheel_rotation(),
+            decoder.decode(&pointer)
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+//! 灵敏度换算示例
+//!
+//! 展示如何在不同游戏与 DPI 之间换算灵敏度，以及把 400 DPI 录制的移动重放到 1600 DPI 设备上
+
+use mouse_codes::motion::sensitivity::{edpi, rescale_sensitivity, DpiScaler, Game};
+use mouse_codes::{parse_mouse_input, MouseEvent};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== 灵敏度换算示例 ===");
+
+    // 选手配置：CS2 灵敏度 2.0，800 DPI
+    let (sensitivity, dpi) = (2.0, 800.0);
+    let cm = Game::Source.cm_per_360(sensitivity, dpi);
+    println!(
+        "\n{} 灵敏度 {} @ {} DPI: eDPI {}，{:.2} cm/360",
+        Game::Source,
+        sensitivity,
+        dpi,
+        edpi(sensitivity, dpi),
+        cm
+    );
+
+    println!("\n1. 换算到其他游戏（保持 cm/360 不变）:");
+    for game in Game::ALL {
+        let converted = Game::Source.convert(sensitivity, game);
+        println!(
+            "  {:<14} 偏航 {:<7} 灵敏度 {:>8.4}  {:.2} cm/360",
+            game.as_str(),
+            game.yaw(),
+            converted,
+            game.cm_per_360(converted, dpi)
+        );
+    }
+
+    println!("\n2. 更换鼠标 DPI:");
+    for target in [400.0, 1600.0, 3200.0] {
+        let rescaled = rescale_sensitivity(sensitivity, dpi, target);
+        println!("  {:>6} DPI -> 灵敏度 {:.3}", target, rescaled);
+    }
+
+    println!("\n3. 目标 30 cm/360 时各游戏的灵敏度:");
+    for game in Game::ALL {
+        println!(
+            "  {:<14} {:.4}",
+            game.as_str(),
+            game.sensitivity_for(30.0, dpi)
+        );
+    }
+
+    println!("\n4. 把 1600 DPI 录制的移动重放到 400 DPI 设备:");
+    let recorded = [
+        "RelativeMove(3, 1)",
+        "RelativeMove(3, 1)",
+        "Press(Left)",
+        "RelativeMove(-9, 2)",
+        "RelativeMove(1, 0)",
+        "Release(Left)",
+        "RelativeMove(2, 1)",
+    ]
+    .into_iter()
+    .map(parse_mouse_input)
+    .collect::<Result<Vec<MouseEvent>, _>>()?;
+
+    // 不足一个 count 的部分会累积到后续移动中
+    let mut scaler = DpiScaler::new(1600.0, 400.0);
+    for event in &recorded {
+        let scaled = scaler.apply(event);
+        println!(
+            "  {:<20} -> {:?}  余数 {:?}",
+            event.to_string(),
+            scaled,
+            scaler.remainder()
+        );
+    }
+
+    Ok(())
+}
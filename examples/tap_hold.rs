@@ -0,0 +1,87 @@
+//! 点击、长按与重复识别示例
+//!
+//! 展示如何从带时间戳的按下/释放事件中区分点击（200 ms 内释放）、
+//! 长按（按住超过 500 ms，只触发一次）以及按住时的重复触发
+
+use mouse_codes::gesture::{Gesture, GestureEvent, GestureRecognizer, Thresholds};
+use mouse_codes::{Button, MouseEvent, TimedEvent};
+use std::time::Duration;
+
+fn at(ms: u64, event: MouseEvent) -> TimedEvent {
+    TimedEvent::new(Duration::from_millis(ms), event)
+}
+
+fn print_gestures(gestures: &[GestureEvent]) {
+    for gesture in gestures {
+        println!("  {:>5} ms  {}", gesture.time.as_millis(), gesture);
+    }
+}
+
+fn main() {
+    // 默认阈值适用于所有按钮；X1 单独配置为按住后每 100 ms 重复一次
+    let recognizer = GestureRecognizer::new(Thresholds::default()).with_thresholds(
+        Button::X1,
+        Thresholds::new(Duration::from_millis(150), Duration::from_millis(300))
+            .with_repeat(Duration::from_millis(100)),
+    );
+    for button in [Button::Left, Button::X1] {
+        let thresholds = recognizer.thresholds(button);
+        println!(
+            "{}: 点击 < {:?}, 长按 >= {:?}, 重复 {:?}",
+            button, thresholds.tap, thresholds.hold, thresholds.repeat
+        );
+    }
+
+    // 一段录制的输入
+    let events = [
+        at(0, MouseEvent::Press(Button::Left)),
+        at(120, MouseEvent::Release(Button::Left)), // 点击
+        at(1000, MouseEvent::Press(Button::Left)),
+        at(1350, MouseEvent::Release(Button::Left)), // 介于两个阈值之间：无手势
+        at(2000, MouseEvent::Press(Button::Right)),
+        at(2300, MouseEvent::RelativeMove(4, 0)),
+        at(2900, MouseEvent::Release(Button::Right)), // 长按，释放时结束
+        at(4000, MouseEvent::Press(Button::X1)),
+        at(4000, MouseEvent::Press(Button::Left)),
+        at(4620, MouseEvent::Release(Button::X1)), // 长按后重复
+        at(4700, MouseEvent::Release(Button::Left)),
+    ];
+
+    println!("\n识别结果:");
+    let gestures: Vec<GestureEvent> = recognizer.clone().stream(events).collect();
+    print_gestures(&gestures);
+
+    // 把手势映射到游戏动作
+    println!("\n游戏动作:");
+    for gesture in &gestures {
+        let action = match (gesture.button, gesture.gesture) {
+            (Button::Left, Gesture::Tap) => "开火",
+            (Button::Left, Gesture::Hold) => "开始蓄力",
+            (Button::Left, Gesture::HoldEnd) => "释放蓄力攻击",
+            (Button::Right, Gesture::Hold) => "打开轮盘",
+            (Button::Right, Gesture::HoldEnd) => "关闭轮盘",
+            (Button::X1, Gesture::Hold | Gesture::Repeat(_)) => "切换下一件物品",
+            _ => continue,
+        };
+        println!(
+            "  {:>5} ms  {} -> {}",
+            gesture.time.as_millis(),
+            gesture,
+            action
+        );
+    }
+
+    // 实时使用：每帧调用 advance，按住期间没有新事件也能触发长按
+    println!("\n逐帧推进 (每帧 16 ms):");
+    let mut live = recognizer;
+    print_gestures(&live.process(&at(0, MouseEvent::Press(Button::Middle))));
+    for frame in 1..=40 {
+        let gestures = live.advance(Duration::from_millis(frame * 16));
+        if !gestures.is_empty() {
+            println!("  第 {} 帧:", frame);
+            print_gestures(&gestures);
+        }
+    }
+    println!("  按住的按钮: {:?}", live.held());
+    print_gestures(&live.process(&at(700, MouseEvent::Release(Button::Middle))));
+}
@@ -0,0 +1,51 @@
+//! 终端鼠标事件互转示例
+//!
+//! 展示如何把 crossterm 鼠标事件转换为通用鼠标事件，再转换为 termion 鼠标事件
+
+use crossterm::event::{
+    KeyModifiers, MouseButton as CrosstermButton, MouseEvent as CrosstermEvent, MouseEventKind,
+};
+use mouse_codes::terminal::TerminalPointer;
+
+/// 构造一个位于指定单元格的 crossterm 事件
+fn at(kind: MouseEventKind, column: u16, row: u16) -> CrosstermEvent {
+    CrosstermEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    }
+}
+
+fn main() {
+    println!("=== 终端鼠标事件互转示例 ===");
+
+    // 终端应用从 crossterm 收到的一段拖动选择操作
+    let received = [
+        at(MouseEventKind::Moved, 10, 4),
+        at(MouseEventKind::Down(CrosstermButton::Left), 10, 4),
+        at(MouseEventKind::Drag(CrosstermButton::Left), 11, 4),
+        at(MouseEventKind::Drag(CrosstermButton::Left), 14, 5),
+        at(MouseEventKind::Up(CrosstermButton::Left), 14, 5),
+        at(MouseEventKind::ScrollDown, 14, 5),
+    ];
+
+    let mut input = TerminalPointer::new();
+    let mut output = TerminalPointer::new();
+    for event in &received {
+        println!(
+            "\ncrossterm: {:?} @ ({}, {})",
+            event.kind, event.column, event.row
+        );
+        // Drag / Moved 会合成带单元格坐标的 Move 事件
+        for event in input.from_crossterm(event) {
+            println!("  -> {}", event);
+            // termion 坐标从 1 开始，没有单独的移动事件
+            for termion in output.to_termion(&event) {
+                println!("     termion: {:?}", termion);
+            }
+        }
+    }
+
+    println!("\n当前按住的按钮: {:?}", input.buttons());
+}
@@ -0,0 +1,60 @@
+//! uinput 事件编码示例
+//!
+//! 展示如何把鼠标事件脚本编码为 uinput 虚拟鼠标所需的 input_event 记录，
+//! 并列出创建设备前需要启用的能力位
+
+use mouse_codes::parse_mouse_input;
+use mouse_codes::protocol::evdev::{Decoder, Encoder, TimeLayout, EV_SYN};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== uinput 事件编码示例 ===\n");
+
+    let script = [
+        "Press(X1)",
+        "Release(X1)",
+        "RelativeMove(50, -20)",
+        "Scroll(VerticalDown, 2)",
+    ];
+    let events = script
+        .iter()
+        .map(|line| parse_mouse_input(line))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // 实际应用中这里是打开的 /dev/uinput 文件，这里写入内存以便检查
+    let mut encoder = Encoder::with_layout(Vec::new(), TimeLayout::Time64);
+    encoder.set_hi_res_wheel(true);
+
+    println!("1. 创建设备前需要启用的能力位:");
+    for capability in encoder.capabilities(&events) {
+        println!(
+            "   ioctl({:#010x}, {:#x})  // {:?}",
+            capability.request(),
+            capability.code(),
+            capability
+        );
+    }
+
+    println!("\n2. 每个事件对应的 input_event 记录:");
+    for event in &events {
+        println!("   {}", event);
+        for record in encoder.records(event) {
+            if record.kind == EV_SYN {
+                println!("     EV_SYN  SYN_REPORT");
+            } else {
+                println!(
+                    "     type {:#04x} code {:#05x} value {}",
+                    record.kind, record.code, record.value
+                );
+            }
+        }
+    }
+
+    encoder.write_events(&events)?;
+    let bytes = encoder.into_inner();
+    println!("\n3. 共写入 {} 字节，解码回放:", bytes.len());
+    for event in Decoder::with_layout(bytes.as_slice(), TimeLayout::Time64) {
+        println!("   {}", event?.event);
+    }
+
+    Ok(())
+}
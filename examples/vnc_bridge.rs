@@ -0,0 +1,58 @@
+//! VNC 指针事件桥接示例
+//!
+//! 展示如何把 RFB PointerEvent 消息转换为鼠标事件，以及如何反向生成消息
+
+use mouse_codes::protocol::rfb::{PointerEvent, RfbDecoder, RfbEncoder};
+use mouse_codes::{parse_mouse_input, MouseEvent};
+
+/// 以十六进制打印字节
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== VNC 指针事件桥接示例 ===");
+
+    let events = [
+        "Move(100, 200)",
+        "Press(Left)",
+        "RelativeMove(5, -3)",
+        "Release(Left)",
+        "Scroll(VerticalDown, 2)",
+        "Press(X1)",
+        "Release(X1)",
+    ]
+    .into_iter()
+    .map(parse_mouse_input)
+    .collect::<Result<Vec<MouseEvent>, _>>()?;
+
+    // 服务器是否宣告了 ExtendedMouseButtons 伪编码
+    for extended in [false, true] {
+        println!("\nExtendedMouseButtons: {}", extended);
+
+        let mut encoder = RfbEncoder::new(extended);
+        let mut stream = Vec::new();
+        for event in &events {
+            let bytes = encoder.encode_bytes(event);
+            println!("  {:<26} -> [{}]", event.to_string(), hex(&bytes));
+            stream.extend(bytes);
+        }
+
+        // 网关端：逐条解析消息，并通过状态比较得到按下/释放边沿
+        let mut decoder = RfbDecoder::new();
+        let mut decoded = Vec::new();
+        let mut rest = stream.as_slice();
+        while let Some((message, size)) = PointerEvent::from_bytes(rest, extended) {
+            decoded.extend(decoder.decode(&message));
+            rest = &rest[size..];
+        }
+        let decoded: Vec<String> = decoded.iter().map(ToString::to_string).collect();
+        println!("  解码: {}", decoded.join(", "));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+//! xterm 鼠标报告转义序列示例
+//!
+//! 展示如何直接解析终端发送的鼠标转义序列，以及终端模拟器如何从鼠标事件生成这些序列
+
+use mouse_codes::terminal::xterm::{Encoding, MouseReport, SequenceError};
+use mouse_codes::terminal::TerminalPointer;
+use mouse_codes::{parse_mouse_input, MouseEvent};
+
+/// 以可读形式打印转义序列
+fn escape(bytes: &[u8]) -> String {
+    bytes.escape_ascii().to_string()
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== xterm 鼠标报告示例 ===");
+
+    // 终端输入流：SGR 按下与拖动、SGR 释放、URXVT 滚轮、X10 侧键，末尾是被截断的序列
+    let mut input = Vec::new();
+    input.extend_from_slice(b"\x1b[<0;5;3M\x1b[<32;7;3M\x1b[<0;7;3m");
+    input.extend_from_slice(b"\x1b[97;7;3M");
+    input.extend_from_slice(b"\x1b[M\xa0\x28\x23\x1b[M\x23\x28\x23");
+    input.extend_from_slice(b"\x1b[<2;1");
+
+    println!("\n1. 解析终端输入:");
+    let mut pointer = TerminalPointer::new();
+    let mut rest = input.as_slice();
+    loop {
+        match MouseReport::parse(rest) {
+            Ok((report, encoding, len)) => {
+                println!(
+                    "  {:<22} {:?} {:?} @ ({}, {})",
+                    escape(&rest[..len]),
+                    encoding,
+                    report.kind,
+                    report.x,
+                    report.y
+                );
+                for event in pointer.from_xterm(&report) {
+                    println!("    -> {}", event);
+                }
+                rest = &rest[len..];
+            }
+            Err(SequenceError::Incomplete) => {
+                println!("  {:<22} 不完整，等待更多字节", escape(rest));
+                break;
+            }
+            Err(SequenceError::Invalid) => {
+                println!("  {:<22} 无效，跳过一个字节", escape(&rest[..1]));
+                rest = &rest[1..];
+            }
+        }
+        if rest.is_empty() {
+            break;
+        }
+    }
+
+    println!("\n2. 终端模拟器生成报告:");
+    let events = [
+        "Move(9, 4)",
+        "Press(Right)",
+        "RelativeMove(2, 0)",
+        "Release(Right)",
+        "Scroll(HorizontalLeft, 1)",
+        "Press(X2)",
+    ]
+    .into_iter()
+    .map(parse_mouse_input)
+    .collect::<Result<Vec<MouseEvent>, _>>()?;
+
+    let mut pointer = TerminalPointer::new();
+    for event in &events {
+        println!("  {}", event);
+        for report in pointer.to_xterm(event) {
+            for encoding in [Encoding::X10, Encoding::Urxvt, Encoding::Sgr] {
+                println!(
+                    "    {:<6} {}",
+                    format!("{:?}", encoding),
+                    escape(&report.encode(encoding))
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,231 @@
+//! TOML format for custom button maps
+//!
+//! ```toml
+//! name = "Gaming mouse"
+//!
+//! [[buttons]]
+//! name = "Sniper"
+//! codes = { windows = 16, linux = 18, evdev = 0x120 }
+//! ```
+//!
+//! Code space names are resolved through `PlatformRegistry`, so every
+//! built-in platform and code space name is accepted.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use mouse_codes::{
+    parse_button_with_aliases, CustomButton, CustomButtonMap, PlatformId, PlatformRegistry,
+};
+use serde::Deserialize;
+
+/// Contents of a custom map file
+#[derive(Debug, Deserialize)]
+struct MapFile {
+    name: String,
+    #[serde(default)]
+    buttons: Vec<ButtonEntry>,
+}
+
+/// One `[[buttons]]` entry
+#[derive(Debug, Deserialize)]
+struct ButtonEntry {
+    name: String,
+    #[serde(default)]
+    codes: BTreeMap<String, usize>,
+}
+
+/// Result of loading a custom map file
+pub struct Report {
+    /// Map built from every valid entry
+    pub map: CustomButtonMap,
+    /// Number of buttons added to the map
+    pub buttons: usize,
+    /// Problems that make the file invalid
+    pub errors: Vec<String>,
+    /// Suspicious but accepted entries
+    pub warnings: Vec<String>,
+}
+
+/// Load and check a custom map file
+///
+/// I/O and TOML syntax errors are returned as `Err`; semantic problems are
+/// collected in the report so that all of them can be shown at once.
+pub fn load(path: &Path, registry: &PlatformRegistry) -> Result<Report, Box<dyn Error>> {
+    parse(&fs::read_to_string(path)?, registry)
+}
+
+/// Check the contents of a custom map file
+pub fn parse(source: &str, registry: &PlatformRegistry) -> Result<Report, Box<dyn Error>> {
+    let file: MapFile = toml::from_str(source)?;
+
+    let mut report = Report {
+        map: CustomButtonMap::new(&file.name),
+        buttons: 0,
+        errors: Vec::new(),
+        warnings: Vec::new(),
+    };
+    // 记录每个代码空间中已占用的代码，用于检测冲突
+    let mut used: BTreeMap<(PlatformId, usize), String> = BTreeMap::new();
+
+    for entry in file.buttons {
+        if parse_button_with_aliases(&entry.name).is_ok() {
+            report
+                .warnings
+                .push(format!("{}: name shadows a standard button", entry.name));
+        }
+        if entry.codes.is_empty() {
+            report
+                .warnings
+                .push(format!("{}: no codes defined", entry.name));
+        }
+
+        let mut codes = Vec::new();
+        // 别名（如 linux 与 x11）指向同一代码空间，只能出现一次
+        let mut spaces: BTreeMap<PlatformId, &str> = BTreeMap::new();
+        for (space, code) in &entry.codes {
+            let Some(id) = registry.id(space) else {
+                report
+                    .errors
+                    .push(format!("{}: unknown code space '{}'", entry.name, space));
+                continue;
+            };
+            if let Some(other) = spaces.insert(id, space) {
+                report.errors.push(format!(
+                    "{}: '{}' and '{}' name the same code space",
+                    entry.name, other, space
+                ));
+                continue;
+            }
+            if let Some(other) = used.get(&(id, *code)) {
+                report.errors.push(format!(
+                    "{}: code {} in {} is already used by {}",
+                    entry.name, code, space, other
+                ));
+                continue;
+            }
+            if let Some(standard) = registry.from_code(*code, id) {
+                report.warnings.push(format!(
+                    "{}: code {} in {} overrides standard button {}",
+                    entry.name, code, space, standard
+                ));
+            }
+            used.insert((id, *code), entry.name.clone());
+            codes.push((id, *code));
+        }
+
+        match report
+            .map
+            .add_button_with_codes(CustomButton::custom_string(entry.name), codes)
+        {
+            Ok(()) => report.buttons += 1,
+            Err(err) => report.errors.push(err.to_string()),
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mouse_codes::Platform;
+
+    fn check(source: &str) -> Report {
+        parse(source, &PlatformRegistry::new()).unwrap()
+    }
+
+    #[test]
+    fn valid_maps_load_every_button() {
+        let report = check(
+            r#"
+            name = "Gaming mouse"
+
+            [[buttons]]
+            name = "Sniper"
+            codes = { windows = 16, linux = 18, evdev = 0x120 }
+
+            [[buttons]]
+            name = "Thumb"
+            codes = { macos = 20 }
+            "#,
+        );
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+        assert_eq!(report.buttons, 2);
+        assert_eq!(report.map.name(), "Gaming mouse");
+        assert_eq!(
+            report.map.from_code(18, Platform::Linux),
+            Some(CustomButton::custom_static("Sniper"))
+        );
+    }
+
+    #[test]
+    fn aliases_of_one_code_space_are_duplicates() {
+        let report = check(
+            r#"
+            name = "Aliases"
+
+            [[buttons]]
+            name = "Sniper"
+            codes = { linux = 18, x11 = 19 }
+            "#,
+        );
+        assert_eq!(
+            report.errors,
+            ["Sniper: 'linux' and 'x11' name the same code space"]
+        );
+        assert_eq!(
+            report.map.from_code(18, Platform::Linux),
+            Some(CustomButton::custom_static("Sniper"))
+        );
+        assert_eq!(report.map.from_code(19, Platform::Linux), None);
+    }
+
+    #[test]
+    fn problems_are_collected() {
+        let report = check(
+            r#"
+            name = "Broken"
+
+            [[buttons]]
+            name = "Sniper"
+            codes = { windows = 16, amiga = 3 }
+
+            [[buttons]]
+            name = "Thumb"
+            codes = { windows = 16 }
+
+            [[buttons]]
+            name = "Back"
+            codes = { linux = 1 }
+
+            [[buttons]]
+            name = "Empty"
+            "#,
+        );
+        assert_eq!(
+            report.errors,
+            [
+                "Sniper: unknown code space 'amiga'",
+                "Thumb: code 16 in windows is already used by Sniper",
+            ]
+        );
+        assert_eq!(
+            report.warnings,
+            [
+                "Back: name shadows a standard button",
+                "Back: code 1 in linux overrides standard button Left",
+                "Empty: no codes defined",
+            ]
+        );
+    }
+
+    #[test]
+    fn syntax_errors_are_returned() {
+        assert!(parse("name = ", &PlatformRegistry::new()).is_err());
+        assert!(parse("buttons = []", &PlatformRegistry::new()).is_err());
+    }
+}
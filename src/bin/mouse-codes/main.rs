@@ -0,0 +1,346 @@
+//! `mouse-codes` command line tool
+//!
+//! Looks up and translates mouse button codes between code spaces, prints the
+//! standard tables, parses event strings, validates custom map files and
+//! monitors evdev devices.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use mouse_codes::{
+    parse_button_with_aliases, parse_mouse_input, utils::platform_button_mapping, Button,
+    CodeSpace, InputBackend, Platform, PlatformRegistry,
+};
+
+mod custom_map;
+mod monitor;
+
+#[derive(Parser)]
+#[command(
+    name = "mouse-codes",
+    version,
+    about = "Mouse button code lookup and conversion"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Look up the code of a button, or the button of a code
+    Lookup {
+        /// Button name or alias, or a decimal / 0x-prefixed code
+        input: String,
+        /// Code space to look in; all code spaces when omitted
+        #[arg(short, long, value_parser = parse_code_space)]
+        platform: Option<CodeSpace>,
+    },
+    /// Translate a code from one code space to another
+    Translate {
+        /// Decimal or 0x-prefixed code
+        #[arg(value_parser = parse_code)]
+        code: usize,
+        /// Source code space
+        #[arg(long, value_parser = parse_code_space)]
+        from: CodeSpace,
+        /// Target code space
+        #[arg(long, value_parser = parse_code_space)]
+        to: CodeSpace,
+    },
+    /// Print the standard button table
+    Table {
+        /// Platform to print; all platforms when omitted
+        #[arg(short, long, value_parser = parse_platform)]
+        platform: Option<Platform>,
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = Format::Md)]
+        format: Format,
+    },
+    /// Parse a mouse event string such as "Press(Left)"
+    Parse {
+        /// Event string
+        event: String,
+    },
+    /// Check a custom map TOML file
+    Validate {
+        /// Path to the custom map file
+        file: PathBuf,
+    },
+    /// Print decoded events of an evdev device or a raw input_event stream
+    Monitor {
+        /// Device or file of raw input_event records, `-` for standard input;
+        /// the first mouse in /dev/input/by-id when omitted
+        source: Option<PathBuf>,
+        /// Custom map TOML file naming extra buttons
+        #[arg(short, long)]
+        map: Option<PathBuf>,
+    },
+}
+
+/// Output format of the `table` subcommand
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// Markdown table
+    Md,
+    /// Comma-separated values
+    Csv,
+    /// JSON object keyed by platform
+    Json,
+}
+
+fn parse_code(s: &str) -> Result<usize, String> {
+    let parsed = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => usize::from_str_radix(hex, 16),
+        None => s.parse(),
+    };
+    parsed.map_err(|_| format!("invalid code: {}", s))
+}
+
+fn parse_code_space(s: &str) -> Result<CodeSpace, String> {
+    s.parse().map_err(|err| format!("{}: {}", err, s))
+}
+
+fn parse_platform(s: &str) -> Result<Platform, String> {
+    s.parse().map_err(|err| format!("{}: {}", err, s))
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<ExitCode, Box<dyn Error>> {
+    match cli.command {
+        Command::Lookup { input, platform } => lookup(&input, platform),
+        Command::Translate { code, from, to } => {
+            let button = from
+                .try_from_code(code)
+                .map_err(|err| format!("{} in {}", err, from))?;
+            let code = to
+                .try_to_code(button)
+                .map_err(|err| format!("{} {}", err, to))?;
+            println!("{}", code);
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Table { platform, format } => {
+            let platforms = match platform {
+                Some(platform) => vec![platform],
+                None => Platform::ALL.to_vec(),
+            };
+            print!("{}", table(&platforms, format)?);
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Parse { event } => {
+            let event = parse_mouse_input(&event)?;
+            println!("{}", event);
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Validate { file } => validate(&file),
+        Command::Monitor { source, map } => {
+            monitor::run(source.as_deref(), map.as_deref())?;
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
+
+fn lookup(input: &str, platform: Option<CodeSpace>) -> Result<ExitCode, Box<dyn Error>> {
+    let spaces = match platform {
+        Some(space) => vec![space],
+        None => CodeSpace::ALL.to_vec(),
+    };
+
+    if let Ok(code) = parse_code(input) {
+        // 按代码查询：列出各代码空间中该代码对应的按钮
+        let mut found = false;
+        for space in spaces {
+            if let Some(button) = space.from_code(code) {
+                println!("{}: {}", space, button);
+                found = true;
+            }
+        }
+        if !found {
+            return Err(format!("no button has code {}", code).into());
+        }
+    } else {
+        let button = parse_button_with_aliases(input)?;
+        // 没有代码的代码空间（如 GLFW 的额外按钮）不列出
+        for space in spaces {
+            if let Some(code) = space.to_code(button) {
+                println!("{}: {}", space, code);
+            }
+        }
+    }
+
+    if platform.is_none() {
+        if let Some(backend) = InputBackend::detect() {
+            println!(
+                "(current backend: {}, code space {})",
+                backend,
+                backend.code_space()
+            );
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn table(platforms: &[Platform], format: Format) -> Result<String, Box<dyn Error>> {
+    let mappings: Vec<_> = platforms
+        .iter()
+        .map(|platform| platform_button_mapping(*platform))
+        .collect();
+
+    let mut out = String::new();
+    match format {
+        Format::Md => {
+            out.push_str("| Button |");
+            for platform in platforms {
+                out.push_str(&format!(" {} |", platform));
+            }
+            out.push_str("\n|--------|");
+            out.push_str(&"------|".repeat(platforms.len()));
+            out.push('\n');
+            for button in Button::iter() {
+                out.push_str(&format!("| {} |", button));
+                for mapping in &mappings {
+                    out.push_str(&format!(" {} |", mapping[&button]));
+                }
+                out.push('\n');
+            }
+        }
+        Format::Csv => {
+            out.push_str("button");
+            for platform in platforms {
+                out.push_str(&format!(",{}", platform));
+            }
+            out.push('\n');
+            for button in Button::iter() {
+                out.push_str(button.as_str());
+                for mapping in &mappings {
+                    out.push_str(&format!(",{}", mapping[&button]));
+                }
+                out.push('\n');
+            }
+        }
+        Format::Json => {
+            // 使用 BTreeMap 保证输出顺序稳定
+            let json: BTreeMap<&str, BTreeMap<Button, usize>> = platforms
+                .iter()
+                .zip(mappings)
+                .map(|(platform, mapping)| (platform.as_str(), mapping.into_iter().collect()))
+                .collect();
+            out.push_str(&serde_json::to_string_pretty(&json)?);
+            out.push('\n');
+        }
+    }
+    Ok(out)
+}
+
+fn validate(file: &std::path::Path) -> Result<ExitCode, Box<dyn Error>> {
+    let report = custom_map::load(file, &PlatformRegistry::new())?;
+
+    for warning in &report.warnings {
+        println!("warning: {}", warning);
+    }
+    for error in &report.errors {
+        println!("error: {}", error);
+    }
+
+    if report.errors.is_empty() {
+        println!(
+            "{}: map '{}' with {} custom buttons is valid",
+            file.display(),
+            report.map.name(),
+            report.buttons
+        );
+        Ok(ExitCode::SUCCESS)
+    } else {
+        println!("{}: {} errors", file.display(), report.errors.len());
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn cli_definition_is_consistent() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn codes_accept_decimal_and_hex() {
+        assert_eq!(parse_code("8"), Ok(8));
+        assert_eq!(parse_code("0x110"), Ok(0x110));
+        assert_eq!(parse_code("0X11a"), Ok(0x11a));
+        assert_eq!(parse_code("left"), Err("invalid code: left".to_string()));
+    }
+
+    #[test]
+    fn arguments_resolve_code_space_aliases() {
+        let cli = Cli::try_parse_from([
+            "mouse-codes",
+            "translate",
+            "0x113",
+            "--from",
+            "evdev",
+            "--to",
+            "x11",
+        ])
+        .unwrap();
+        let Command::Translate { code, from, to } = cli.command else {
+            panic!("expected translate");
+        };
+        assert_eq!(
+            (code, from, to),
+            (0x113, CodeSpace::Evdev, CodeSpace::Linux)
+        );
+
+        let cli = Cli::try_parse_from(["mouse-codes", "lookup", "back", "-p", "sdl2"]).unwrap();
+        let Command::Lookup { input, platform } = cli.command else {
+            panic!("expected lookup");
+        };
+        assert_eq!((input.as_str(), platform), ("back", Some(CodeSpace::Sdl)));
+
+        assert!(Cli::try_parse_from([
+            "mouse-codes",
+            "translate",
+            "1",
+            "--from",
+            "amiga",
+            "--to",
+            "x11"
+        ])
+        .is_err());
+        assert!(Cli::try_parse_from(["mouse-codes", "table", "-p", "evdev"]).is_err());
+        assert!(Cli::try_parse_from(["mouse-codes", "table", "-f", "xml"]).is_err());
+    }
+
+    #[test]
+    fn tables_list_every_button() {
+        let md = table(&[Platform::Windows, Platform::Linux], Format::Md).unwrap();
+        let mut lines = md.lines();
+        assert_eq!(lines.next(), Some("| Button | Windows | Linux |"));
+        assert_eq!(lines.next(), Some("|--------|------|------|"));
+        assert_eq!(lines.next(), Some("| Left | 1 | 1 |"));
+        assert_eq!(md.lines().count(), 2 + Button::iter().count());
+
+        let csv = table(&[Platform::MacOS], Format::Csv).unwrap();
+        assert!(csv.starts_with("button,macOS\nLeft,0\nRight,1\n"));
+
+        let json = table(&[Platform::Linux], Format::Json).unwrap();
+        let parsed: BTreeMap<String, BTreeMap<Button, usize>> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed["Linux"][&Button::X1], 8);
+    }
+}
@@ -0,0 +1,100 @@
+//! `monitor` subcommand: print decoded events of an evdev device
+//!
+//! Reads raw `struct input_event` records from a device node, a file or
+//! standard input, so recorded streams can be replayed without hardware.
+
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use mouse_codes::protocol::evdev::{FrameDecoder, FrameReader, EV_KEY};
+use mouse_codes::{CodeSpace, CustomButtonMap, MouseEvent, PlatformId, PlatformRegistry};
+
+use crate::custom_map;
+
+/// Directory holding persistent device names maintained by udev
+const BY_ID: &str = "/dev/input/by-id";
+
+/// Print events from `source` until the stream ends
+///
+/// `source` of `-` reads standard input; without a source the first mouse in
+/// `/dev/input/by-id` is opened. Button names come from `map` when given.
+pub fn run(source: Option<&Path>, map: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    let registry = PlatformRegistry::new();
+    let map = match map {
+        Some(path) => {
+            let report = custom_map::load(path, &registry)?;
+            if !report.errors.is_empty() {
+                return Err(format!(
+                    "{}: invalid custom map, see `mouse-codes validate`",
+                    path.display()
+                )
+                .into());
+            }
+            report.map
+        }
+        None => CustomButtonMap::new("standard"),
+    };
+
+    let reader: Box<dyn Read> = match source {
+        Some(path) if path == Path::new("-") => Box::new(io::stdin().lock()),
+        Some(path) => Box::new(File::open(path)?),
+        None => {
+            let device = default_device()?;
+            eprintln!("monitoring {}", device.display());
+            Box::new(File::open(device)?)
+        }
+    };
+
+    let evdev = PlatformId::from(CodeSpace::Evdev);
+    let mut decoder = FrameDecoder::new();
+    for frame in FrameReader::new(BufReader::new(reader)) {
+        let frame = frame?;
+        if frame.dropped {
+            println!("{} (events dropped)", timestamp(frame.time));
+        }
+
+        // 按键记录通过自定义映射解析，以便显示自定义按钮名称
+        for record in frame.events.iter().filter(|record| record.kind == EV_KEY) {
+            let name = map
+                .from_code_in(record.code.into(), evdev, &registry)
+                .map(|button| button.to_string())
+                .unwrap_or_else(|| format!("{:#x}", record.code));
+            let action = match record.value {
+                1 => "Press",
+                0 => "Release",
+                _ => continue,
+            };
+            println!("{} {}({})", timestamp(frame.time), action, name);
+        }
+
+        for event in decoder.decode(&frame) {
+            if !matches!(event, MouseEvent::Press(_) | MouseEvent::Release(_)) {
+                println!("{} {}", timestamp(frame.time), event);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn timestamp(time: Duration) -> String {
+    format!("[{:>6}.{:06}]", time.as_secs(), time.subsec_micros())
+}
+
+fn default_device() -> Result<PathBuf, Box<dyn Error>> {
+    let mut mice: Vec<PathBuf> = fs::read_dir(BY_ID)
+        .map_err(|err| format!("{}: {}; pass a device path", BY_ID, err))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with("-event-mouse"))
+        })
+        .collect();
+    mice.sort();
+    mice.into_iter()
+        .next()
+        .ok_or_else(|| format!("no mouse found in {}; pass a device path", BY_ID).into())
+}
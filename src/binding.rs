@@ -0,0 +1,721 @@
+//! Bindings from mouse input to application actions
+//!
+//! A `Binding` describes one input: a button press or a wheel direction,
+//! optionally with keyboard modifiers and with other buttons that must be
+//! held down for a chord. A `BindingMap` binds any number of inputs to each
+//! action of an application-defined type, answers which action an event
+//! triggers and, in reverse, what is bound to an action.
+//!
+//! Bindings are written as `+`-separated tokens: modifiers (`Ctrl`, `Alt`,
+//! `Shift`, `Meta`), then held buttons, then the button or wheel direction
+//! that triggers the binding, e.g. `Ctrl+X1`, `Right+Left` or `Shift+WheelUp`.
+//! Buttons are named as `parse_button_with_aliases` accepts. A button or
+//! modifier may appear only once.
+
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::error::MouseParseError;
+use crate::parser::borrowed::{parse_button_with_aliases, parse_scroll_direction};
+use crate::types::{Button, ButtonSet, MouseEvent, ScrollDirection};
+
+/// Keyboard modifiers held with a mouse input
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Modifiers(u8);
+
+impl Modifiers {
+    /// No modifiers
+    pub const NONE: Modifiers = Modifiers(0);
+    /// Control key
+    pub const CONTROL: Modifiers = Modifiers(1);
+    /// Alt or Option key
+    pub const ALT: Modifiers = Modifiers(1 << 1);
+    /// Shift key
+    pub const SHIFT: Modifiers = Modifiers(1 << 2);
+    /// Meta key: Windows, Super or Command
+    pub const META: Modifiers = Modifiers(1 << 3);
+
+    // 名称与别名，按显示顺序排列
+    const NAMES: [(Modifiers, &'static str, &'static [&'static str]); 4] = [
+        (Modifiers::CONTROL, "Ctrl", &["control"]),
+        (Modifiers::ALT, "Alt", &["option"]),
+        (Modifiers::SHIFT, "Shift", &[]),
+        (Modifiers::META, "Meta", &["super", "cmd", "command", "win"]),
+    ];
+
+    /// Check whether all of `other` are held
+    pub const fn contains(&self, other: Modifiers) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Get the union of two sets of modifiers
+    pub const fn with(self, other: Modifiers) -> Self {
+        Modifiers(self.0 | other.0)
+    }
+
+    /// Check whether no modifier is held
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Number of modifiers held
+    pub const fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Parse a single modifier name, ignoring case
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::NAMES
+            .iter()
+            .find(|(_, name, aliases)| {
+                name.eq_ignore_ascii_case(s) || aliases.iter().any(|a| a.eq_ignore_ascii_case(s))
+            })
+            .map(|(modifier, _, _)| *modifier)
+    }
+}
+
+impl core::ops::BitOr for Modifiers {
+    type Output = Modifiers;
+
+    fn bitor(self, other: Modifiers) -> Modifiers {
+        self.with(other)
+    }
+}
+
+impl fmt::Display for Modifiers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names = Self::NAMES
+            .iter()
+            .filter(|(modifier, _, _)| self.contains(*modifier))
+            .map(|(_, name, _)| name);
+        if let Some(first) = names.next() {
+            f.write_str(first)?;
+        }
+        for name in names {
+            write!(f, "+{}", name)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Modifiers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Modifiers({})", self)
+    }
+}
+
+/// Input that fires a binding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+    /// A button press
+    Button(Button),
+    /// A scroll in a direction
+    Wheel(ScrollDirection),
+}
+
+impl Trigger {
+    /// Get the trigger of an event: presses and scrolls
+    pub fn of(event: &MouseEvent) -> Option<Self> {
+        match *event {
+            MouseEvent::Press(button) => Some(Trigger::Button(button)),
+            MouseEvent::Scroll(direction, _) => Some(Trigger::Wheel(direction)),
+            _ => None,
+        }
+    }
+
+    /// Parse a wheel token such as `WheelUp` or `ScrollHorizontalLeft`
+    fn parse_wheel(s: &str) -> Option<ScrollDirection> {
+        ["wheel", "scroll"].iter().find_map(|prefix| {
+            let rest = s.get(prefix.len()..).filter(|rest| !rest.is_empty())?;
+            if !s[..prefix.len()].eq_ignore_ascii_case(prefix) {
+                return None;
+            }
+            parse_scroll_direction(rest).ok()
+        })
+    }
+}
+
+impl fmt::Display for Trigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Trigger::Button(button) => write!(f, "{}", button),
+            Trigger::Wheel(direction) => f.write_str(match direction {
+                ScrollDirection::VerticalUp => "WheelUp",
+                ScrollDirection::VerticalDown => "WheelDown",
+                ScrollDirection::HorizontalLeft => "WheelLeft",
+                ScrollDirection::HorizontalRight => "WheelRight",
+            }),
+        }
+    }
+}
+
+/// A mouse input with its modifiers and chord buttons
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Binding {
+    /// Keyboard modifiers that must be held, and no others
+    pub modifiers: Modifiers,
+    /// Buttons that must already be held
+    pub held: ButtonSet,
+    /// Input that fires the binding
+    pub trigger: Trigger,
+}
+
+impl Binding {
+    /// Bind a button press
+    pub const fn button(button: Button) -> Self {
+        Self {
+            modifiers: Modifiers::NONE,
+            held: ButtonSet::EMPTY,
+            trigger: Trigger::Button(button),
+        }
+    }
+
+    /// Bind a scroll direction
+    pub const fn wheel(direction: ScrollDirection) -> Self {
+        Self {
+            modifiers: Modifiers::NONE,
+            held: ButtonSet::EMPTY,
+            trigger: Trigger::Wheel(direction),
+        }
+    }
+
+    /// Bind a press of `button` while all of `held` are held
+    pub const fn chord(held: ButtonSet, button: Button) -> Self {
+        Self {
+            modifiers: Modifiers::NONE,
+            held,
+            trigger: Trigger::Button(button),
+        }
+    }
+
+    /// Require keyboard modifiers
+    pub const fn with_modifiers(mut self, modifiers: Modifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    /// Check whether an event fires this binding
+    ///
+    /// `held` are the buttons held before the event and `modifiers` the
+    /// keyboard modifiers held with it.
+    pub fn matches(&self, event: &MouseEvent, held: ButtonSet, modifiers: Modifiers) -> bool {
+        Trigger::of(event) == Some(self.trigger)
+            && self.modifiers == modifiers
+            && self.held.iter().all(|button| held.contains(button))
+    }
+
+    /// Number of modifiers and held buttons: the more, the more specific
+    fn specificity(&self) -> usize {
+        self.modifiers.len() + self.held.len()
+    }
+}
+
+impl From<Button> for Binding {
+    fn from(button: Button) -> Self {
+        Self::button(button)
+    }
+}
+
+impl From<ScrollDirection> for Binding {
+    fn from(direction: ScrollDirection) -> Self {
+        Self::wheel(direction)
+    }
+}
+
+impl FromStr for Binding {
+    type Err = MouseParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || MouseParseError::InvalidBinding(s.trim().to_string());
+        if s.trim().is_empty() {
+            return Err(MouseParseError::EmptyInput);
+        }
+
+        let mut modifiers = Modifiers::NONE;
+        let mut buttons = Vec::new();
+        let mut wheel = None;
+        for token in s.split('+').map(str::trim) {
+            // 滚轮方向只能作为最后一个触发输入；空记号来自多余的 `+`
+            if wheel.is_some() || token.is_empty() {
+                return Err(invalid());
+            }
+            if let Some(modifier) = Modifiers::parse(token) {
+                // 修饰键必须在按钮之前，且不能重复（如 `Ctrl+Control`）
+                if !buttons.is_empty() || modifiers.contains(modifier) {
+                    return Err(invalid());
+                }
+                modifiers = modifiers.with(modifier);
+            } else if let Some(direction) = Trigger::parse_wheel(token) {
+                wheel = Some(direction);
+            } else {
+                let button = parse_button_with_aliases(token)
+                    .map_err(|_| MouseParseError::UnknownButton(token.to_lowercase()))?;
+                // 同一按钮不能既按住又触发，也不能按住两次
+                if buttons.contains(&button) {
+                    return Err(invalid());
+                }
+                buttons.push(button);
+            }
+        }
+
+        let trigger = match wheel {
+            Some(direction) => Trigger::Wheel(direction),
+            None => Trigger::Button(buttons.pop().ok_or_else(invalid)?),
+        };
+        Ok(Self {
+            modifiers,
+            held: buttons.into_iter().collect(),
+            trigger,
+        })
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.modifiers.is_empty() {
+            write!(f, "{}+", self.modifiers)?;
+        }
+        for button in self.held.iter() {
+            write!(f, "{}+", button)?;
+        }
+        write!(f, "{}", self.trigger)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Binding {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Binding {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = alloc::string::String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Two actions bound to the same input
+///
+/// Only identical bindings conflict. Bindings that merely overlap, such as
+/// `Left` and `Right+Left`, are resolved by specificity when looking up an
+/// action, and bindings of equal specificity that can match the same event,
+/// such as `Right+X1` and `Middle+X1`, go to the one bound first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Conflict<'a, A> {
+    /// The input bound twice
+    pub binding: &'a Binding,
+    /// Action bound first
+    pub first: &'a A,
+    /// Action bound later
+    pub second: &'a A,
+}
+
+/// Bindings from inputs to actions of type `A`
+///
+/// An action can have any number of inputs. When several bindings match an
+/// event, the most specific one wins: the one with the most modifiers and
+/// held buttons, then the one bound first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindingMap<A> {
+    entries: Vec<(Binding, A)>,
+}
+
+impl<A> Default for BindingMap<A> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<A> BindingMap<A> {
+    /// Create an empty map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bind an input to an action
+    pub fn bind(&mut self, binding: impl Into<Binding>, action: A) {
+        self.entries.push((binding.into(), action));
+    }
+
+    /// Remove every action bound to an input, returning whether there was one
+    pub fn unbind(&mut self, binding: &Binding) -> bool {
+        let len = self.entries.len();
+        self.entries.retain(|(bound, _)| bound != binding);
+        self.entries.len() != len
+    }
+
+    /// Iterate over the bindings in the order they were bound
+    pub fn iter(&self) -> impl Iterator<Item = (&Binding, &A)> {
+        self.entries
+            .iter()
+            .map(|(binding, action)| (binding, action))
+    }
+
+    /// Number of bindings
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check whether nothing is bound
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the action an event triggers
+    ///
+    /// `held` are the buttons held before the event, such as the `ButtonSet`
+    /// of the caller's pointer state, and `modifiers` the keyboard modifiers.
+    pub fn action(&self, event: &MouseEvent, held: ButtonSet, modifiers: Modifiers) -> Option<&A> {
+        self.entries
+            .iter()
+            .filter(|(binding, _)| binding.matches(event, held, modifiers))
+            .min_by_key(|(binding, _)| core::cmp::Reverse(binding.specificity()))
+            .map(|(_, action)| action)
+    }
+
+    /// Get the action an event triggers with no buttons or modifiers held
+    pub fn action_for(&self, event: &MouseEvent) -> Option<&A> {
+        self.action(event, ButtonSet::EMPTY, Modifiers::NONE)
+    }
+}
+
+impl<A: PartialEq> BindingMap<A> {
+    /// Bind an input to an action unless it is bound to another action
+    ///
+    /// On conflict the action already bound is returned.
+    pub fn try_bind(&mut self, binding: impl Into<Binding>, action: A) -> Result<(), &A> {
+        let binding = binding.into();
+        match self
+            .entries
+            .iter()
+            .position(|(bound, other)| *bound == binding && *other != action)
+        {
+            Some(index) => Err(&self.entries[index].1),
+            None => {
+                let bound = self
+                    .entries
+                    .iter()
+                    .any(|(bound, other)| *bound == binding && *other == action);
+                if !bound {
+                    self.entries.push((binding, action));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Remove every input bound to an action, returning whether there was one
+    pub fn unbind_action(&mut self, action: &A) -> bool {
+        let len = self.entries.len();
+        self.entries.retain(|(_, bound)| bound != action);
+        self.entries.len() != len
+    }
+
+    /// Replace the inputs of an action with a single one
+    pub fn rebind(&mut self, action: A, binding: impl Into<Binding>) {
+        self.unbind_action(&action);
+        self.bind(binding, action);
+    }
+
+    /// Get the inputs bound to an action
+    pub fn bindings_for<'a>(&'a self, action: &'a A) -> impl Iterator<Item = &'a Binding> + 'a {
+        self.entries
+            .iter()
+            .filter(move |(_, bound)| bound == action)
+            .map(|(binding, _)| binding)
+    }
+
+    /// Find inputs bound to more than one action
+    ///
+    /// Only identical bindings are reported; see [`Conflict`].
+    pub fn conflicts(&self) -> Vec<Conflict<'_, A>> {
+        let mut conflicts = Vec::new();
+        for (i, (binding, first)) in self.entries.iter().enumerate() {
+            for (other, second) in &self.entries[i + 1..] {
+                if binding == other && first != second {
+                    conflicts.push(Conflict {
+                        binding,
+                        first,
+                        second,
+                    });
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+impl<A> FromIterator<(Binding, A)> for BindingMap<A> {
+    fn from_iter<I: IntoIterator<Item = (Binding, A)>>(iter: I) -> Self {
+        Self {
+            entries: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<A> Extend<(Binding, A)> for BindingMap<A> {
+    fn extend<I: IntoIterator<Item = (Binding, A)>>(&mut self, iter: I) {
+        self.entries.extend(iter);
+    }
+}
+
+/// Serialized as a map from each action to the list of its inputs
+#[cfg(feature = "serde")]
+impl<A: serde::Serialize + PartialEq> serde::Serialize for BindingMap<A> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        // 按动作首次出现的顺序分组
+        let mut actions: Vec<&A> = Vec::new();
+        for (_, action) in &self.entries {
+            if !actions.contains(&action) {
+                actions.push(action);
+            }
+        }
+        let mut map = serializer.serialize_map(Some(actions.len()))?;
+        for action in actions {
+            let inputs: Vec<&Binding> = self.bindings_for(action).collect();
+            map.serialize_entry(action, &inputs)?;
+        }
+        map.end()
+    }
+}
+
+/// Deserialized from a map from each action to one input or a list of inputs
+#[cfg(feature = "serde")]
+impl<'de, A: serde::Deserialize<'de> + Clone> serde::Deserialize<'de> for BindingMap<A> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use core::marker::PhantomData;
+        use serde::de::{MapAccess, SeqAccess, Visitor};
+
+        /// One input or a list of inputs
+        struct Inputs(Vec<Binding>);
+
+        impl<'de> serde::Deserialize<'de> for Inputs {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct InputsVisitor;
+
+                impl<'de> Visitor<'de> for InputsVisitor {
+                    type Value = Inputs;
+
+                    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                        f.write_str("an input or a list of inputs")
+                    }
+
+                    fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<Inputs, E> {
+                        s.parse()
+                            .map(|binding| Inputs([binding].into()))
+                            .map_err(E::custom)
+                    }
+
+                    fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<Inputs, S::Error> {
+                        let mut bindings = Vec::new();
+                        while let Some(binding) = seq.next_element()? {
+                            bindings.push(binding);
+                        }
+                        Ok(Inputs(bindings))
+                    }
+                }
+
+                deserializer.deserialize_any(InputsVisitor)
+            }
+        }
+
+        struct MapVisitor<A>(PhantomData<A>);
+
+        impl<'de, A: serde::Deserialize<'de> + Clone> Visitor<'de> for MapVisitor<A> {
+            type Value = BindingMap<A>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a map from actions to inputs")
+            }
+
+            fn visit_map<M: MapAccess<'de>>(self, mut access: M) -> Result<Self::Value, M::Error> {
+                let mut map = BindingMap::new();
+                while let Some((action, Inputs(bindings))) = access.next_entry::<A, Inputs>()? {
+                    for binding in bindings {
+                        map.bind(binding, action.clone());
+                    }
+                }
+                Ok(map)
+            }
+        }
+
+        deserializer.deserialize_map(MapVisitor(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Binding {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for s in [
+            "X1",
+            "Ctrl+Shift+Right+X1",
+            "Alt+WheelUp",
+            "Meta+WheelLeft",
+            "Right+Left",
+        ] {
+            assert_eq!(parse(s).to_string(), s);
+        }
+        // 别名与大小写规范化为标准名称
+        assert_eq!(parse("control + shift + back").to_string(), "Ctrl+Shift+X1");
+        assert_eq!(parse("cmd+ScrollDown").to_string(), "Meta+WheelDown");
+        assert_eq!(
+            parse("Ctrl+Right+X1"),
+            Binding::chord(ButtonSet::new().with(Button::Right), Button::X1)
+                .with_modifiers(Modifiers::CONTROL)
+        );
+    }
+
+    #[test]
+    fn malformed_bindings_are_rejected() {
+        assert_eq!("".parse::<Binding>(), Err(MouseParseError::EmptyInput));
+        assert_eq!(
+            "Ctrl+Nope".parse::<Binding>(),
+            Err(MouseParseError::UnknownButton("nope".to_string()))
+        );
+        for s in [
+            "Left+Left",
+            "Right+Left+Right",
+            "Ctrl+Control+Left",
+            "Shift+Shift+WheelUp",
+            "WheelUp+Left",
+            "WheelUp+WheelDown",
+            "Left+Ctrl",
+            "Ctrl",
+            "Ctrl+",
+        ] {
+            assert_eq!(
+                s.parse::<Binding>(),
+                Err(MouseParseError::InvalidBinding(s.to_string())),
+                "{}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn most_specific_binding_wins() {
+        let mut map = BindingMap::new();
+        map.bind(Button::X1, "plain");
+        map.bind(parse("Ctrl+X1"), "ctrl");
+        map.bind(parse("Right+X1"), "right");
+        map.bind(parse("Middle+X1"), "middle");
+        map.bind(parse("Ctrl+Right+X1"), "ctrl_right");
+
+        let press = MouseEvent::Press(Button::X1);
+        let right = ButtonSet::new().with(Button::Right);
+        let both = right.with(Button::Middle);
+        assert_eq!(map.action_for(&press), Some(&"plain"));
+        assert_eq!(map.action(&press, right, Modifiers::NONE), Some(&"right"));
+        assert_eq!(
+            map.action(&press, ButtonSet::EMPTY, Modifiers::CONTROL),
+            Some(&"ctrl")
+        );
+        assert_eq!(
+            map.action(&press, right, Modifiers::CONTROL),
+            Some(&"ctrl_right")
+        );
+        // 同样具体时先绑定的优先
+        assert_eq!(map.action(&press, both, Modifiers::NONE), Some(&"right"));
+        // 修饰键必须完全一致
+        assert_eq!(map.action(&press, ButtonSet::EMPTY, Modifiers::SHIFT), None);
+        assert_eq!(map.action_for(&MouseEvent::Release(Button::X1)), None);
+    }
+
+    #[test]
+    fn conflicts_report_identical_bindings_only() {
+        let mut map = BindingMap::new();
+        map.bind(Button::Left, "attack");
+        map.bind(parse("Right+Left"), "melee");
+        map.bind(parse("Left"), "select");
+        map.bind(parse("Left"), "attack");
+
+        let conflicts = map.conflicts();
+        assert_eq!(conflicts.len(), 2);
+        assert!(conflicts
+            .iter()
+            .all(|conflict| *conflict.binding == Binding::button(Button::Left)));
+        assert_eq!(
+            (conflicts[0].first, conflicts[0].second),
+            (&"attack", &"select")
+        );
+        assert_eq!(
+            (conflicts[1].first, conflicts[1].second),
+            (&"select", &"attack")
+        );
+    }
+
+    #[test]
+    fn try_bind_keeps_the_first_action() {
+        let mut map = BindingMap::new();
+        assert_eq!(map.try_bind(Button::Left, "attack"), Ok(()));
+        assert_eq!(map.try_bind(Button::Left, "attack"), Ok(()));
+        assert_eq!(map.try_bind(Button::Left, "select"), Err(&"attack"));
+        assert_eq!(map.try_bind(parse("Shift+Left"), "select"), Ok(()));
+        assert_eq!(map.len(), 2);
+
+        map.bind(ScrollDirection::VerticalUp, "attack");
+        let inputs: Vec<String> = map
+            .bindings_for(&"attack")
+            .map(Binding::to_string)
+            .collect();
+        assert_eq!(inputs, ["Left", "WheelUp"]);
+
+        map.rebind("attack", Button::Middle);
+        let inputs: Vec<&Binding> = map.bindings_for(&"attack").collect();
+        assert_eq!(inputs, [&Binding::button(Button::Middle)]);
+        assert!(map.unbind(&parse("Shift+Left")));
+        assert!(!map.unbind(&parse("Shift+Left")));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trips() {
+        use alloc::string::String;
+
+        let binding = parse("Ctrl+Right+X1");
+        let json = serde_json::to_string(&binding).unwrap();
+        assert_eq!(json, r#""Ctrl+Right+X1""#);
+        assert_eq!(serde_json::from_str::<Binding>(&json).unwrap(), binding);
+
+        let mut map: BindingMap<String> = BindingMap::new();
+        map.bind(Button::Left, "attack".to_string());
+        map.bind(parse("Shift+WheelDown"), "zoom_out".to_string());
+        map.bind(parse("Right+Left"), "attack".to_string());
+        let json = serde_json::to_string(&map).unwrap();
+        assert_eq!(
+            json,
+            r#"{"attack":["Left","Right+Left"],"zoom_out":["Shift+WheelDown"]}"#
+        );
+        let decoded: BindingMap<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.len(), 3);
+        for (binding, action) in map.iter() {
+            assert!(decoded.bindings_for(action).any(|bound| bound == binding));
+        }
+
+        // 单个字符串与列表都可以
+        let decoded: BindingMap<String> =
+            serde_json::from_str(r#"{"attack":"back","zoom_out":["Shift+WheelDown"]}"#).unwrap();
+        assert_eq!(
+            decoded.action_for(&MouseEvent::Press(Button::X1)),
+            Some(&"attack".to_string())
+        );
+        assert!(serde_json::from_str::<BindingMap<String>>(r#"{"attack":"Left+Left"}"#).is_err());
+        assert!(serde_json::from_str::<BindingMap<String>>(r#"{"attack":"nope"}"#).is_err());
+    }
+}
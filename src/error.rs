@@ -1,6 +1,10 @@
 use thiserror::Error;
 
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+
 /// Error type for mouse code parsing and mapping operations
+#[cfg(feature = "alloc")]
 #[derive(Debug, Error, PartialEq)]
 pub enum MouseParseError {
     /// Unknown mouse button
@@ -15,11 +19,83 @@ pub enum MouseParseError {
     #[error("Duplicate custom button: {0}")]
     DuplicateCustomButton(String),
 
+    /// Duplicate platform name in a registry
+    #[error("Duplicate platform: {0}")]
+    DuplicatePlatform(String),
+
+    /// No platform ids left in a registry
+    #[error("Too many platforms registered")]
+    TooManyPlatforms,
+
     /// Invalid button code for platform
     #[error("Invalid button code {0} for platform")]
     InvalidButtonCode(usize),
 
+    /// Button without a code in the requested code space
+    #[error("No code for button {0} on platform")]
+    UnmappedButton(String),
+
     /// Empty input string
     #[error("Empty input string")]
     EmptyInput,
+
+    /// Malformed remapping rule
+    #[error("Invalid remapping rule: {0}")]
+    InvalidRule(String),
+
+    /// Malformed input binding
+    #[error("Invalid binding: {0}")]
+    InvalidBinding(String),
+}
+
+/// Borrowed error type for allocation-free parsing
+///
+/// Mirrors `MouseParseError` but refers to the offending input instead of
+/// copying it, so it is available without the `alloc` feature.
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError<'a> {
+    /// Unknown mouse button
+    #[error("Unknown mouse button: {0}")]
+    UnknownButton(&'a str),
+
+    /// Mouse event that does not follow the `Kind(params)` syntax
+    #[error("Invalid mouse event: {0}")]
+    InvalidEvent(&'a str),
+
+    /// Unknown platform
+    #[error("Unknown platform")]
+    UnknownPlatform,
+
+    /// Invalid button code for platform
+    #[error("Invalid button code {0} for platform")]
+    InvalidButtonCode(usize),
+
+    /// Empty input string
+    #[error("Empty input string")]
+    EmptyInput,
+}
+
+#[cfg(feature = "alloc")]
+impl ParseError<'_> {
+    /// Convert into an owned `MouseParseError`
+    ///
+    /// `InvalidEvent` becomes `MouseParseError::UnknownButton` with the whole
+    /// input, as the owned parsers have always reported it.
+    pub fn into_owned(self) -> MouseParseError {
+        match self {
+            ParseError::UnknownButton(s) | ParseError::InvalidEvent(s) => {
+                MouseParseError::UnknownButton(s.to_string())
+            }
+            ParseError::UnknownPlatform => MouseParseError::UnknownPlatform,
+            ParseError::InvalidButtonCode(code) => MouseParseError::InvalidButtonCode(code),
+            ParseError::EmptyInput => MouseParseError::EmptyInput,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<ParseError<'_>> for MouseParseError {
+    fn from(err: ParseError<'_>) -> Self {
+        err.into_owned()
+    }
 }
@@ -0,0 +1,446 @@
+//! Tap, hold and repeat recognition
+//!
+//! A `GestureRecognizer` turns timestamped button presses and releases into
+//! gestures: a tap when a button is released quickly, a hold once it has been
+//! down long enough, and optionally repeats for as long as it stays down. A
+//! press released between the tap and hold thresholds produces nothing.
+//!
+//! Holds and repeats are due at times derived from the press, not from the
+//! moment they are noticed: the recognizer only sees time pass through the
+//! events it is given and `advance`, which an application calls every frame
+//! so that holds fire while no event arrives. Each gesture carries the time it
+//! was due, and gestures due at the same time come out in press order, so the
+//! output depends only on the input events. Thresholds are exclusive: a hold
+//! or repeat due at some time fires once time has passed it, not at that
+//! exact time. When time jumps past several repeats, only the latest is
+//! produced, carrying its number.
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::time::Duration;
+
+use crate::types::{Button, ButtonSet, MouseEvent, TimedEvent};
+
+/// Timing thresholds of a button
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Thresholds {
+    /// A press released before this long is a tap
+    pub tap: Duration,
+    /// A press lasting longer than this is a hold
+    pub hold: Duration,
+    /// Interval of repeats after a hold, `None` (or zero) for no repeats
+    pub repeat: Option<Duration>,
+}
+
+impl Thresholds {
+    /// Taps under 200 ms, holds from 500 ms, no repeats
+    pub const DEFAULT: Thresholds =
+        Thresholds::new(Duration::from_millis(200), Duration::from_millis(500));
+
+    /// Create thresholds without repeats
+    pub const fn new(tap: Duration, hold: Duration) -> Self {
+        Self {
+            tap,
+            hold,
+            repeat: None,
+        }
+    }
+
+    /// Repeat every `interval` after a hold
+    pub const fn with_repeat(mut self, interval: Duration) -> Self {
+        self.repeat = Some(interval);
+        self
+    }
+
+    /// Get the hold, or the latest repeat, of a press due before `now`
+    ///
+    /// Returns the time it was due and, for a repeat, its number.
+    fn due(&self, pressed: &Pressed, now: Duration) -> Option<(Duration, u32)> {
+        let hold = pressed.time.checked_add(self.hold)?;
+        let elapsed = now.checked_sub(hold).filter(|elapsed| !elapsed.is_zero())?;
+        if !pressed.held {
+            return Some((hold, 0));
+        }
+        let interval = self.repeat.filter(|interval| !interval.is_zero())?;
+        // 严格早于 now 的重复次数；跳过期间错过的重复，只取最新一次
+        let count = (elapsed.as_nanos() - 1) / interval.as_nanos();
+        let count = u32::try_from(count).unwrap_or(u32::MAX);
+        if count <= pressed.repeats {
+            return None;
+        }
+        Some((hold.checked_add(interval.checked_mul(count)?)?, count))
+    }
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Gesture recognized on a button
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Gesture {
+    /// Pressed and released quickly
+    Tap,
+    /// Held long enough, once per press
+    Hold,
+    /// Still held, numbered from 1; repeats skipped by a time jump are not reported
+    Repeat(u32),
+    /// Released after a hold
+    HoldEnd,
+}
+
+impl fmt::Display for Gesture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Gesture::Tap => write!(f, "Tap"),
+            Gesture::Hold => write!(f, "Hold"),
+            Gesture::Repeat(count) => write!(f, "Repeat({})", count),
+            Gesture::HoldEnd => write!(f, "HoldEnd"),
+        }
+    }
+}
+
+/// Gesture with the button and the time it happened at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GestureEvent {
+    /// Time of the gesture, on the clock of the input events
+    pub time: Duration,
+    /// Button the gesture was made with
+    pub button: Button,
+    /// The gesture itself
+    pub gesture: Gesture,
+}
+
+impl fmt::Display for GestureEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.gesture {
+            Gesture::Repeat(count) => write!(f, "Repeat({}, {})", self.button, count),
+            gesture => write!(f, "{}({})", gesture, self.button),
+        }
+    }
+}
+
+/// Button held down
+#[derive(Debug, Clone, Copy)]
+struct Pressed {
+    button: Button,
+    time: Duration,
+    held: bool,
+    repeats: u32,
+}
+
+/// Recognizes taps, holds and repeats in a stream of events
+#[derive(Debug, Clone, Default)]
+pub struct GestureRecognizer {
+    thresholds: Thresholds,
+    overrides: Vec<(Button, Thresholds)>,
+    // 按下顺序排列，同一时刻到期的手势按此顺序输出
+    pressed: Vec<Pressed>,
+}
+
+impl GestureRecognizer {
+    /// Create a recognizer using `thresholds` for every button
+    pub fn new(thresholds: Thresholds) -> Self {
+        Self {
+            thresholds,
+            ..Self::default()
+        }
+    }
+
+    /// Use different thresholds for `button`
+    pub fn with_thresholds(mut self, button: Button, thresholds: Thresholds) -> Self {
+        self.set_thresholds(button, thresholds);
+        self
+    }
+
+    /// Set the thresholds of `button`
+    ///
+    /// A button already held keeps its press time, so its hold may become
+    /// due at once with the new thresholds.
+    pub fn set_thresholds(&mut self, button: Button, thresholds: Thresholds) {
+        match self.overrides.iter_mut().find(|(b, _)| *b == button) {
+            Some((_, existing)) => *existing = thresholds,
+            None => self.overrides.push((button, thresholds)),
+        }
+    }
+
+    /// Get the thresholds of `button`
+    pub fn thresholds(&self, button: Button) -> Thresholds {
+        self.overrides
+            .iter()
+            .find(|(b, _)| *b == button)
+            .map_or(self.thresholds, |(_, thresholds)| *thresholds)
+    }
+
+    /// Get the buttons held
+    pub fn held(&self) -> ButtonSet {
+        self.pressed.iter().map(|pressed| pressed.button).collect()
+    }
+
+    /// Forget the buttons held, without producing gestures
+    pub fn reset(&mut self) {
+        self.pressed.clear();
+    }
+
+    /// Produce the holds and repeats due by `now`
+    pub fn advance(&mut self, now: Duration) -> Vec<GestureEvent> {
+        let mut gestures = Vec::new();
+        self.advance_into(now, &mut gestures);
+        gestures
+    }
+
+    /// Process an event, producing the gestures due by its time
+    ///
+    /// Events other than presses and releases only advance time. A repeated
+    /// press of a held button and a release of a button not seen pressed are
+    /// ignored.
+    pub fn process(&mut self, event: &TimedEvent) -> Vec<GestureEvent> {
+        let mut gestures = Vec::new();
+        match event.event {
+            MouseEvent::Press(button) => {
+                if !self.pressed.iter().any(|pressed| pressed.button == button) {
+                    self.pressed.push(Pressed {
+                        button,
+                        time: event.time,
+                        held: false,
+                        repeats: 0,
+                    });
+                }
+                self.advance_into(event.time, &mut gestures);
+            }
+            MouseEvent::Release(button) => {
+                // 先输出释放之前到期的长按与重复
+                self.advance_into(event.time, &mut gestures);
+                let Some(index) = self.pressed.iter().position(|p| p.button == button) else {
+                    return gestures;
+                };
+                let pressed = self.pressed.remove(index);
+                let gesture = if pressed.held {
+                    Some(Gesture::HoldEnd)
+                } else if event.time.saturating_sub(pressed.time) < self.thresholds(button).tap {
+                    Some(Gesture::Tap)
+                } else {
+                    None
+                };
+                gestures.extend(gesture.map(|gesture| GestureEvent {
+                    time: event.time,
+                    button,
+                    gesture,
+                }));
+            }
+            _ => self.advance_into(event.time, &mut gestures),
+        }
+        gestures
+    }
+
+    /// Recognize gestures in a stream of events
+    pub fn stream<I>(self, events: I) -> Gestures<I::IntoIter>
+    where
+        I: IntoIterator<Item = TimedEvent>,
+    {
+        Gestures {
+            recognizer: self,
+            events: events.into_iter(),
+            pending: Vec::new().into_iter(),
+        }
+    }
+
+    fn advance_into(&mut self, now: Duration, gestures: &mut Vec<GestureEvent>) {
+        loop {
+            // 最早到期的按钮；同一时刻取先按下的
+            let mut next: Option<(usize, Duration, u32)> = None;
+            for (index, pressed) in self.pressed.iter().enumerate() {
+                let due = self.thresholds(pressed.button).due(pressed, now);
+                if let Some((time, count)) = due {
+                    if next.map_or(true, |(_, earliest, _)| time < earliest) {
+                        next = Some((index, time, count));
+                    }
+                }
+            }
+            let Some((index, time, count)) = next else {
+                return;
+            };
+
+            let pressed = &mut self.pressed[index];
+            let gesture = if pressed.held {
+                pressed.repeats = count;
+                Gesture::Repeat(count)
+            } else {
+                pressed.held = true;
+                Gesture::Hold
+            };
+            gestures.push(GestureEvent {
+                time,
+                button: pressed.button,
+                gesture,
+            });
+        }
+    }
+}
+
+/// Iterator over the gestures of a stream of events
+#[derive(Debug, Clone)]
+pub struct Gestures<I> {
+    recognizer: GestureRecognizer,
+    events: I,
+    pending: alloc::vec::IntoIter<GestureEvent>,
+}
+
+impl<I> Gestures<I> {
+    /// Get the recognizer, e.g. to `advance` it past the last event
+    pub fn recognizer(&mut self) -> &mut GestureRecognizer {
+        &mut self.recognizer
+    }
+}
+
+impl<I: Iterator<Item = TimedEvent>> Iterator for Gestures<I> {
+    type Item = GestureEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(gesture) = self.pending.next() {
+                return Some(gesture);
+            }
+            let event = self.events.next()?;
+            self.pending = self.recognizer.process(&event).into_iter();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    fn at(time: u64, event: MouseEvent) -> TimedEvent {
+        TimedEvent::new(ms(time), event)
+    }
+
+    fn gesture(time: Duration, button: Button, gesture: Gesture) -> GestureEvent {
+        GestureEvent {
+            time,
+            button,
+            gesture,
+        }
+    }
+
+    #[test]
+    fn taps_and_holds() {
+        let events = [
+            at(0, MouseEvent::Press(Button::Left)),
+            at(150, MouseEvent::Release(Button::Left)),
+            // 200 ms 既不是单击也不是长按
+            at(1000, MouseEvent::Press(Button::Left)),
+            at(1200, MouseEvent::Release(Button::Left)),
+            at(2000, MouseEvent::Press(Button::Left)),
+            at(2700, MouseEvent::Release(Button::Left)),
+        ];
+        let gestures: Vec<GestureEvent> = GestureRecognizer::default().stream(events).collect();
+        assert_eq!(
+            gestures,
+            [
+                gesture(ms(150), Button::Left, Gesture::Tap),
+                gesture(ms(2500), Button::Left, Gesture::Hold),
+                gesture(ms(2700), Button::Left, Gesture::HoldEnd),
+            ]
+        );
+    }
+
+    #[test]
+    fn hold_needs_more_than_the_threshold() {
+        let mut recognizer = GestureRecognizer::default();
+        recognizer.process(&at(0, MouseEvent::Press(Button::Left)));
+        assert_eq!(recognizer.advance(ms(500)), []);
+        assert_eq!(
+            recognizer.advance(ms(501)),
+            [gesture(ms(500), Button::Left, Gesture::Hold)]
+        );
+
+        // 恰好 500 ms 时释放不算长按
+        recognizer.process(&at(1000, MouseEvent::Press(Button::Right)));
+        assert_eq!(
+            recognizer.process(&at(1500, MouseEvent::Release(Button::Right))),
+            []
+        );
+    }
+
+    #[test]
+    fn repeats_catch_up_with_a_single_event() {
+        let thresholds = Thresholds::DEFAULT.with_repeat(ms(1));
+        let mut recognizer = GestureRecognizer::new(thresholds);
+        recognizer.process(&at(0, MouseEvent::Press(Button::X1)));
+
+        // 一小时未调用 advance，只输出最新一次重复
+        let hour = Duration::from_secs(3600);
+        assert_eq!(
+            recognizer.advance(hour),
+            [
+                gesture(ms(500), Button::X1, Gesture::Hold),
+                gesture(hour - ms(1), Button::X1, Gesture::Repeat(3_599_499)),
+            ]
+        );
+        assert_eq!(recognizer.advance(hour), []);
+        assert_eq!(
+            recognizer.advance(hour + ms(2)),
+            [gesture(
+                hour + ms(1),
+                Button::X1,
+                Gesture::Repeat(3_599_501)
+            )]
+        );
+        assert_eq!(
+            recognizer.process(&TimedEvent::new(
+                hour + ms(2),
+                MouseEvent::Release(Button::X1)
+            )),
+            [gesture(hour + ms(2), Button::X1, Gesture::HoldEnd)]
+        );
+    }
+
+    #[test]
+    fn repeats_follow_the_interval() {
+        let mut recognizer = GestureRecognizer::default().with_thresholds(
+            Button::X1,
+            Thresholds::new(ms(150), ms(300)).with_repeat(ms(100)),
+        );
+        recognizer.process(&at(0, MouseEvent::Press(Button::X1)));
+        let mut gestures = Vec::new();
+        for frame in (16..=560).step_by(16) {
+            gestures.extend(recognizer.advance(ms(frame)));
+        }
+        assert_eq!(
+            gestures,
+            [
+                gesture(ms(300), Button::X1, Gesture::Hold),
+                gesture(ms(400), Button::X1, Gesture::Repeat(1)),
+                gesture(ms(500), Button::X1, Gesture::Repeat(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn simultaneous_gestures_come_out_in_press_order() {
+        let mut recognizer = GestureRecognizer::default()
+            .with_thresholds(Button::Right, Thresholds::new(ms(200), ms(400)));
+        recognizer.process(&at(0, MouseEvent::Press(Button::Middle)));
+        recognizer.process(&at(0, MouseEvent::Press(Button::Left)));
+        recognizer.process(&at(100, MouseEvent::Press(Button::Right)));
+        assert_eq!(recognizer.held().len(), 3);
+        assert_eq!(
+            recognizer.advance(ms(1000)),
+            [
+                gesture(ms(500), Button::Middle, Gesture::Hold),
+                gesture(ms(500), Button::Left, Gesture::Hold),
+                gesture(ms(500), Button::Right, Gesture::Hold),
+            ]
+        );
+    }
+}
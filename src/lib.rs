@@ -3,39 +3,72 @@
 //! This crate provides comprehensive mouse button definitions and cross-platform
 //! code mapping for Windows, Linux, and macOS.
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![deny(missing_docs)]
 #![warn(clippy::all)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 #[cfg(feature = "serde")]
 extern crate serde;
 
 #[cfg(feature = "phf")]
 extern crate phf;
 
-#[cfg(feature = "phf")]
-extern crate lazy_static;
-
+/// Bindings from mouse input to application actions
+#[cfg(feature = "alloc")]
+pub mod binding;
 /// Error types for mouse parsing and mapping
 pub mod error;
+/// Tap, hold and repeat recognition over timed button events
+#[cfg(feature = "alloc")]
+pub mod gesture;
 /// Mouse code mapping implementations
 pub mod mapping;
+/// Pointer motion processing: acceleration, sensitivity, filtering and desktop geometry
+#[cfg(feature = "std")]
+pub mod motion;
 /// Advanced mouse input parsing with alias support
 pub mod parser;
+/// Wire formats of mouse input protocols
+#[cfg(feature = "alloc")]
+pub mod protocol;
+/// Runtime button remapping rules
+#[cfg(feature = "alloc")]
+pub mod remap;
+/// Serde adapters for human-readable field representations
+#[cfg(all(feature = "serde", feature = "alloc"))]
+pub mod serde_adapters;
+/// Terminal mouse reporting and terminal library adapters
+#[cfg(feature = "alloc")]
+pub mod terminal;
 /// Core type definitions for mouse buttons and platforms
 pub mod types;
 /// Utility functions and helpers
 pub mod utils;
 
 // Re-export main types for convenient access
+#[cfg(feature = "alloc")]
 pub use error::MouseParseError;
+pub use error::ParseError;
+#[cfg(feature = "alloc")]
 pub use mapping::custom::{CustomButton, CustomButtonMap};
-pub use types::{Button, CodeMapper, MouseEvent, Platform, ScrollDirection};
+#[cfg(feature = "alloc")]
+pub use mapping::registry::{PlatformId, PlatformRegistry};
+pub use mapping::CodeSpace;
+pub use types::{
+    Button, ButtonRole, ButtonSet, CodeMapper, Handedness, InputBackend, MouseEvent, Platform,
+    ScrollDirection, TimedEvent,
+};
 
 // Re-export core parsing functions
+#[cfg(feature = "alloc")]
 pub use mapping::standard::parse_button_ignore_case;
 
 // Re-export advanced parser functionality
-pub use parser::{parse_button_with_aliases, parse_mouse_input};
+#[cfg(feature = "alloc")]
+pub use parser::{parse_button_role, parse_button_with_aliases, parse_mouse_input};
 
 // 保持向后兼容性，但标记为已弃用
 #[deprecated(since = "0.1.0", note = "Use Platform::current() instead")]
@@ -0,0 +1,119 @@
+//! Built-in button code spaces
+//!
+//! A `Platform` names an operating system, but one operating system can expose
+//! several numbering schemes: on Linux, X11 reports core protocol button
+//! numbers while Wayland and evdev devices report kernel `BTN_*` codes.
+//! Toolkits such as SDL, GLFW and winit number buttons their own way again.
+//! Each column of the standard button table is a `CodeSpace`.
+
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use crate::error::MouseParseError;
+use crate::mapping::standard;
+use crate::mapping::standard::table::standard_button_table;
+use crate::types::{Button, Platform};
+
+macro_rules! define_code_space {
+    (
+        code_spaces: [$(
+            $(#[doc = $doc:literal])*
+            $space:ident($module:ident) => ($str:literal, [$($alias:literal),*], [$($platform:ident)?]);
+        )*];
+        buttons: [$($rows:tt)*];
+    ) => {
+        /// Numbering scheme used to encode mouse buttons
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub enum CodeSpace {
+            $(
+                $(#[doc = $doc])*
+                $space,
+            )*
+        }
+
+        impl CodeSpace {
+            /// All built-in code spaces, in table column order
+            pub const ALL: [CodeSpace; [$(CodeSpace::$space),*].len()] = [$(CodeSpace::$space),*];
+
+            /// Get the display name of the code space
+            pub const fn as_str(&self) -> &'static str {
+                match self {
+                    $(CodeSpace::$space => $str,)*
+                }
+            }
+
+            /// Get the lowercase aliases accepted for this code space besides its name
+            pub const fn aliases(&self) -> &'static [&'static str] {
+                match self {
+                    $(CodeSpace::$space => &[$($alias),*],)*
+                }
+            }
+
+            /// Get the platform whose standard codes this space holds, if any
+            pub const fn platform(&self) -> Option<Platform> {
+                match self {
+                    $(CodeSpace::$space => define_code_space!(@platform $($platform)?),)*
+                }
+            }
+        }
+    };
+    (@platform $platform:ident) => {
+        Some(Platform::$platform)
+    };
+    (@platform) => {
+        None
+    };
+}
+
+standard_button_table!(define_code_space);
+
+impl CodeSpace {
+    /// Convert a button to its code in this space, if it has one
+    ///
+    /// Every button has a code in the spaces of a `Platform`; toolkits with
+    /// fewer buttons, such as GLFW, leave the extra ones out.
+    pub fn to_code(&self, button: Button) -> Option<usize> {
+        standard::code_in(button, *self)
+    }
+
+    /// Convert a button to its code in this space, reporting buttons without a code as an error
+    #[cfg(feature = "alloc")]
+    pub fn try_to_code(&self, button: Button) -> Result<usize, MouseParseError> {
+        self.to_code(button)
+            .ok_or_else(|| MouseParseError::UnmappedButton(button.as_str().into()))
+    }
+
+    /// Parse a button from a code in this space
+    pub fn from_code(&self, code: usize) -> Option<Button> {
+        standard::button_in(code, *self)
+    }
+
+    /// Parse a button from a code in this space, reporting unknown codes as an error
+    #[cfg(feature = "alloc")]
+    pub fn try_from_code(&self, code: usize) -> Result<Button, MouseParseError> {
+        self.from_code(code)
+            .ok_or(MouseParseError::InvalidButtonCode(code))
+    }
+}
+
+impl From<Platform> for CodeSpace {
+    fn from(platform: Platform) -> Self {
+        platform.code_space()
+    }
+}
+
+impl fmt::Display for CodeSpace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::str::FromStr for CodeSpace {
+    type Err = MouseParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        crate::parser::borrowed::parse_code_space(s).map_err(Into::into)
+    }
+}
@@ -1,16 +1,18 @@
 //! Custom mouse button mapping support
 
-use std::borrow::Cow;
-use std::collections::HashMap;
-use std::fmt;
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use core::fmt;
 
 use crate::{
     error::MouseParseError,
+    mapping::registry::{PlatformId, PlatformRegistry},
     types::{Button, CodeMapper, Platform},
 };
 
 /// A custom mouse button that extends the standard button set
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CustomButton {
     /// A standard mouse button
@@ -45,8 +47,8 @@ impl fmt::Display for CustomButton {
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CustomButtonMap {
     name: String,
-    mappings: HashMap<CustomButton, [Option<usize>; 3]>, // [Windows, Linux, macOS]
-    reverse_mappings: [HashMap<usize, CustomButton>; 3], // [Windows, Linux, macOS]
+    mappings: BTreeMap<CustomButton, BTreeMap<PlatformId, usize>>,
+    reverse_mappings: BTreeMap<PlatformId, BTreeMap<usize, CustomButton>>,
 }
 
 impl CustomButtonMap {
@@ -54,8 +56,8 @@ impl CustomButtonMap {
     pub fn new(name: &str) -> Self {
         Self {
             name: name.to_string(),
-            mappings: HashMap::new(),
-            reverse_mappings: [HashMap::new(), HashMap::new(), HashMap::new()],
+            mappings: BTreeMap::new(),
+            reverse_mappings: BTreeMap::new(),
         }
     }
 
@@ -67,28 +69,57 @@ impl CustomButtonMap {
         linux_code: Option<usize>,
         macos_code: Option<usize>,
     ) -> Result<(), MouseParseError> {
+        let codes = [
+            (Platform::Windows, windows_code),
+            (Platform::Linux, linux_code),
+            (Platform::MacOS, macos_code),
+        ];
+        self.add_button_with_codes(
+            button,
+            codes
+                .into_iter()
+                .filter_map(|(platform, code)| Some((platform.into(), code?))),
+        )
+    }
+
+    /// Add a custom button with codes for any registered code space
+    ///
+    /// Fails without changing the map if the button already exists or if
+    /// one of its codes is taken by another custom button in the same code
+    /// space.
+    pub fn add_button_with_codes<I>(
+        &mut self,
+        button: CustomButton,
+        codes: I,
+    ) -> Result<(), MouseParseError>
+    where
+        I: IntoIterator<Item = (PlatformId, usize)>,
+    {
         if self.mappings.contains_key(&button) {
             return Err(MouseParseError::DuplicateCustomButton(button.to_string()));
         }
 
-        // 先克隆按钮用于反向映射
-        let button_clone = button.clone();
+        let codes: BTreeMap<PlatformId, usize> = codes.into_iter().collect();
 
-        // Store forward mapping
-        self.mappings
-            .insert(button, [windows_code, linux_code, macos_code]);
+        // 同一代码空间中代码必须唯一，保证正反向表互逆
+        if let Some((_, code)) = codes
+            .iter()
+            .find(|(id, code)| self.custom_button(**code, **id).is_some())
+        {
+            return Err(MouseParseError::InvalidButtonCode(*code));
+        }
 
         // Store reverse mappings for each platform
-        if let Some(code) = windows_code {
-            self.reverse_mappings[0].insert(code, button_clone.clone());
-        }
-        if let Some(code) = linux_code {
-            self.reverse_mappings[1].insert(code, button_clone.clone());
-        }
-        if let Some(code) = macos_code {
-            self.reverse_mappings[2].insert(code, button_clone);
+        for (id, code) in &codes {
+            self.reverse_mappings
+                .entry(*id)
+                .or_default()
+                .insert(*code, button.clone());
         }
 
+        // Store forward mapping
+        self.mappings.insert(button, codes);
+
         Ok(())
     }
 
@@ -101,46 +132,148 @@ impl CustomButtonMap {
 // 明确使用 CodeMapper trait 的方法
 impl CodeMapper for CustomButton {
     fn to_code(&self, platform: Platform) -> usize {
-        match self {
-            CustomButton::Standard(btn) => <Button as CodeMapper>::to_code(btn, platform),
-            CustomButton::Custom(_) => {
-                panic!("Custom buttons require a CustomButtonMap for conversion")
-            }
+        match self.try_to_code(platform) {
+            Ok(code) => code,
+            Err(_) => panic!("Custom buttons require a CustomButtonMap for conversion"),
         }
     }
 
     fn from_code(code: usize, platform: Platform) -> Option<Self> {
         <Button as CodeMapper>::from_code(code, platform).map(CustomButton::Standard)
     }
+
+    fn try_to_code(&self, platform: Platform) -> Result<usize, MouseParseError> {
+        match self {
+            CustomButton::Standard(btn) => <Button as CodeMapper>::try_to_code(btn, platform),
+            // 自定义按钮的代码只能通过 CustomButtonMap 查询
+            CustomButton::Custom(name) => Err(MouseParseError::UnmappedButton(name.to_string())),
+        }
+    }
 }
 
 impl CustomButtonMap {
     /// Get the platform-specific code for a custom button
     pub fn get_code_for_button(&self, button: &CustomButton, platform: Platform) -> Option<usize> {
+        self.try_get_code_for_button(button, platform).ok()
+    }
+
+    /// Get the platform-specific code for a custom button, reporting unmapped buttons as an error
+    pub fn try_get_code_for_button(
+        &self,
+        button: &CustomButton,
+        platform: Platform,
+    ) -> Result<usize, MouseParseError> {
         match button {
-            CustomButton::Standard(btn) => Some(<Button as CodeMapper>::to_code(btn, platform)),
-            CustomButton::Custom(_) => {
-                let idx = match platform {
-                    Platform::Windows => 0,
-                    Platform::Linux => 1,
-                    Platform::MacOS => 2,
-                };
-                self.mappings.get(button).and_then(|codes| codes[idx])
-            }
+            CustomButton::Standard(btn) => <Button as CodeMapper>::try_to_code(btn, platform),
+            CustomButton::Custom(_) => self
+                .custom_code(button, platform.into())
+                .ok_or_else(|| MouseParseError::UnmappedButton(button.to_string())),
+        }
+    }
+
+    /// Get the code for a custom button in any code space of `registry`
+    pub fn get_code_in(
+        &self,
+        button: &CustomButton,
+        id: PlatformId,
+        registry: &PlatformRegistry,
+    ) -> Option<usize> {
+        match button {
+            CustomButton::Standard(btn) => registry.to_code(*btn, id),
+            CustomButton::Custom(_) => self.custom_code(button, id),
         }
     }
 
     /// Get button from platform-specific code using custom mappings
     pub fn from_code(&self, code: usize, platform: Platform) -> Option<CustomButton> {
-        let idx = match platform {
-            Platform::Windows => 0,
-            Platform::Linux => 1,
-            Platform::MacOS => 2,
-        };
-
         // Check custom mappings first, then fall back to standard buttons
-        self.reverse_mappings[idx].get(&code).cloned().or_else(|| {
+        self.custom_button(code, platform.into()).or_else(|| {
             <Button as CodeMapper>::from_code(code, platform).map(CustomButton::Standard)
         })
     }
+
+    /// Get button from a code in any code space of `registry`
+    pub fn from_code_in(
+        &self,
+        code: usize,
+        id: PlatformId,
+        registry: &PlatformRegistry,
+    ) -> Option<CustomButton> {
+        self.custom_button(code, id)
+            .or_else(|| registry.from_code(code, id).map(CustomButton::Standard))
+    }
+
+    /// Get button from platform-specific code, reporting unknown codes as an error
+    pub fn try_from_code(
+        &self,
+        code: usize,
+        platform: Platform,
+    ) -> Result<CustomButton, MouseParseError> {
+        self.from_code(code, platform)
+            .ok_or(MouseParseError::InvalidButtonCode(code))
+    }
+
+    fn custom_code(&self, button: &CustomButton, id: PlatformId) -> Option<usize> {
+        self.mappings.get(button)?.get(&id).copied()
+    }
+
+    fn custom_button(&self, code: usize, id: PlatformId) -> Option<CustomButton> {
+        self.reverse_mappings.get(&id)?.get(&code).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_to_code_reports_unmapped_buttons() {
+        let extra = CustomButton::custom_static("Thumb");
+        assert_eq!(
+            extra.try_to_code(Platform::Windows),
+            Err(MouseParseError::UnmappedButton("Thumb".to_string()))
+        );
+        assert_eq!(
+            CustomButton::Standard(Button::X1).try_to_code(Platform::Linux),
+            Ok(8)
+        );
+
+        let map = CustomButtonMap::new("gaming");
+        assert_eq!(
+            map.try_get_code_for_button(&extra, Platform::MacOS),
+            Err(MouseParseError::UnmappedButton("Thumb".to_string()))
+        );
+        assert_eq!(
+            map.try_from_code(99, Platform::MacOS),
+            Err(MouseParseError::InvalidButtonCode(99))
+        );
+    }
+
+    #[test]
+    fn codes_are_unique_per_platform() {
+        let mut map = CustomButtonMap::new("gaming");
+        let thumb = CustomButton::custom_static("Thumb");
+        let sniper = CustomButton::custom_static("Sniper");
+        map.add_button(thumb.clone(), Some(20), Some(30), None)
+            .unwrap();
+
+        // Linux 代码 30 已被占用，整个按钮都不应加入
+        assert_eq!(
+            map.add_button(sniper.clone(), Some(21), Some(30), None),
+            Err(MouseParseError::InvalidButtonCode(30))
+        );
+        assert_eq!(map.get_code_for_button(&sniper, Platform::Windows), None);
+        assert_eq!(map.from_code(21, Platform::Windows), None);
+        assert_eq!(map.from_code(30, Platform::Linux), Some(thumb.clone()));
+
+        // 不同平台上可以复用同一个代码
+        map.add_button(sniper.clone(), Some(30), None, Some(20))
+            .unwrap();
+        assert_eq!(map.from_code(30, Platform::Windows), Some(sniper));
+        assert_eq!(map.from_code(20, Platform::Windows), Some(thumb.clone()));
+        assert_eq!(
+            map.add_button(thumb, None, None, None),
+            Err(MouseParseError::DuplicateCustomButton("Thumb".to_string()))
+        );
+    }
 }
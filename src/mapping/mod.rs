@@ -3,15 +3,29 @@
 //! This module provides standard and custom mapping functionality for
 //! converting between mouse buttons and platform-specific codes.
 
+/// Built-in button code spaces
+pub mod code_space;
 /// Custom mouse button mapping support
+#[cfg(feature = "alloc")]
 pub mod custom;
+/// Runtime-extensible registry of platform code spaces
+#[cfg(feature = "alloc")]
+pub mod registry;
 /// Standard mouse button code mappings for cross-platform compatibility
 pub mod standard;
+/// Conversions to and from winit's `MouseButton`
+#[cfg(feature = "winit")]
+mod winit_impl;
 
 use crate::CodeMapper;
 
 /// Re-export key types and traits from submodules
+pub use code_space::CodeSpace;
+#[cfg(feature = "alloc")]
 pub use custom::{CustomButton, CustomButtonMap};
+#[cfg(feature = "alloc")]
+pub use registry::{PlatformId, PlatformRegistry};
+#[cfg(feature = "alloc")]
 pub use standard::parse_button_from_str;
 
 /// Helper function to get platform-specific code for a button
@@ -29,6 +43,7 @@ pub fn get_button(code: usize, platform: crate::types::Platform) -> Option<crate
 }
 
 /// Get all standard button mappings for a platform as a hashmap
+#[cfg(feature = "std")]
 pub fn standard_mapping(
     platform: crate::types::Platform,
 ) -> std::collections::HashMap<crate::types::Button, usize> {
@@ -36,6 +51,7 @@ pub fn standard_mapping(
 }
 
 /// Get reverse standard mapping (code to button) for a platform
+#[cfg(feature = "std")]
 pub fn reverse_standard_mapping(
     platform: crate::types::Platform,
 ) -> std::collections::HashMap<usize, crate::types::Button> {
@@ -0,0 +1,296 @@
+//! Runtime-extensible platform registry
+//!
+//! `CodeSpace` is a closed set of built-in code spaces. A `PlatformRegistry`
+//! starts with those and lets further code spaces (FreeBSD, Android, a VNC
+//! backend, ...) be registered at runtime with their own forward and reverse
+//! tables. Every code space is addressed by a `PlatformId`.
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{
+    error::MouseParseError,
+    mapping::standard::{code_space_index, CODE_SPACE_COUNT},
+    mapping::CodeSpace,
+    parser::borrowed::parse_code_space,
+    types::{Button, Platform},
+};
+
+/// Identifier of a code space in a `PlatformRegistry`
+///
+/// The built-in code spaces have fixed ids, so `PlatformId::from(Platform::Linux)`
+/// and `PlatformId::from(CodeSpace::Evdev)` are valid for every registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct PlatformId(u16);
+
+impl PlatformId {
+    /// Get the numeric value of this id
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    /// Get the built-in code space this id refers to, if any
+    pub fn builtin(self) -> Option<CodeSpace> {
+        CodeSpace::ALL
+            .into_iter()
+            .find(|space| PlatformId::from(*space) == self)
+    }
+}
+
+impl From<CodeSpace> for PlatformId {
+    fn from(space: CodeSpace) -> Self {
+        PlatformId(code_space_index(space) as u16)
+    }
+}
+
+impl From<Platform> for PlatformId {
+    fn from(platform: Platform) -> Self {
+        platform.code_space().into()
+    }
+}
+
+impl fmt::Display for PlatformId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// A code space registered at runtime
+#[derive(Debug, Clone)]
+struct RegisteredPlatform {
+    name: String,
+    codes: BTreeMap<Button, usize>,
+    buttons: BTreeMap<usize, Button>,
+}
+
+/// Registry of built-in and runtime-registered code spaces
+#[derive(Debug, Clone, Default)]
+pub struct PlatformRegistry {
+    platforms: Vec<RegisteredPlatform>,
+}
+
+impl PlatformRegistry {
+    /// Create a registry containing only the built-in code spaces
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a new code space with its button codes
+    ///
+    /// Fails if the name is already taken, if two buttons share a code or if
+    /// the registry has run out of ids. A `(button, code)` pair listed twice is
+    /// accepted.
+    pub fn register<I>(&mut self, name: &str, codes: I) -> Result<PlatformId, MouseParseError>
+    where
+        I: IntoIterator<Item = (Button, usize)>,
+    {
+        if self.id(name).is_some() {
+            return Err(MouseParseError::DuplicatePlatform(name.to_string()));
+        }
+        let id = u16::try_from(CODE_SPACE_COUNT + self.platforms.len())
+            .map(PlatformId)
+            .map_err(|_| MouseParseError::TooManyPlatforms)?;
+
+        let mut platform = RegisteredPlatform {
+            name: name.to_string(),
+            codes: BTreeMap::new(),
+            buttons: BTreeMap::new(),
+        };
+        for (button, code) in codes {
+            if platform.codes.get(&button) == Some(&code) {
+                continue;
+            }
+            // 同一代码空间中代码必须唯一，保证正反向表互逆
+            if platform.buttons.insert(code, button).is_some() {
+                return Err(MouseParseError::InvalidButtonCode(code));
+            }
+            if let Some(previous) = platform.codes.insert(button, code) {
+                platform.buttons.remove(&previous);
+            }
+        }
+
+        self.platforms.push(platform);
+        Ok(id)
+    }
+
+    /// Look up a code space by name (case-insensitive)
+    pub fn id(&self, name: &str) -> Option<PlatformId> {
+        if let Ok(space) = parse_code_space(name) {
+            return Some(space.into());
+        }
+        self.platforms
+            .iter()
+            .position(|p| p.name.eq_ignore_ascii_case(name))
+            .map(|i| PlatformId((CODE_SPACE_COUNT + i) as u16))
+    }
+
+    /// Get the name of a code space
+    pub fn name(&self, id: PlatformId) -> Option<&str> {
+        match id.builtin() {
+            Some(space) => Some(space.as_str()),
+            None => self.registered(id).map(|p| p.name.as_str()),
+        }
+    }
+
+    /// Iterate over the ids of all known code spaces
+    pub fn ids(&self) -> impl Iterator<Item = PlatformId> + '_ {
+        (0..CODE_SPACE_COUNT + self.platforms.len()).map(|i| PlatformId(i as u16))
+    }
+
+    /// Check whether `id` refers to a code space in this registry
+    pub fn contains(&self, id: PlatformId) -> bool {
+        id.index() < CODE_SPACE_COUNT + self.platforms.len()
+    }
+
+    /// Convert a button to a code in the given code space
+    pub fn to_code(&self, button: Button, id: PlatformId) -> Option<usize> {
+        match id.builtin() {
+            Some(space) => space.to_code(button),
+            None => self.registered(id)?.codes.get(&button).copied(),
+        }
+    }
+
+    /// Parse a button from a code in the given code space
+    pub fn from_code(&self, code: usize, id: PlatformId) -> Option<Button> {
+        match id.builtin() {
+            Some(space) => space.from_code(code),
+            None => self.registered(id)?.buttons.get(&code).copied(),
+        }
+    }
+
+    /// Convert a button to a code in the given code space without panicking
+    pub fn try_to_code(&self, button: Button, id: PlatformId) -> Result<usize, MouseParseError> {
+        match id.builtin() {
+            Some(space) => space.try_to_code(button),
+            None if self.contains(id) => self
+                .to_code(button, id)
+                .ok_or_else(|| MouseParseError::UnmappedButton(button.to_string())),
+            None => Err(MouseParseError::UnknownPlatform),
+        }
+    }
+
+    /// Parse a button from a code in the given code space, reporting unknown codes as an error
+    pub fn try_from_code(&self, code: usize, id: PlatformId) -> Result<Button, MouseParseError> {
+        if !self.contains(id) {
+            return Err(MouseParseError::UnknownPlatform);
+        }
+        self.from_code(code, id)
+            .ok_or(MouseParseError::InvalidButtonCode(code))
+    }
+
+    /// Translate a code from one code space to another
+    pub fn translate(&self, code: usize, from: PlatformId, to: PlatformId) -> Option<usize> {
+        self.to_code(self.from_code(code, from)?, to)
+    }
+
+    fn registered(&self, id: PlatformId) -> Option<&RegisteredPlatform> {
+        id.index()
+            .checked_sub(CODE_SPACE_COUNT)
+            .and_then(|i| self.platforms.get(i))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registered_platforms_translate_codes() {
+        let mut registry = PlatformRegistry::new();
+        let freebsd = registry
+            .register(
+                "FreeBSD",
+                [(Button::Left, 1), (Button::Right, 3), (Button::Middle, 2)],
+            )
+            .unwrap();
+
+        assert_eq!(freebsd.index(), CODE_SPACE_COUNT);
+        assert_eq!(freebsd.builtin(), None);
+        assert!(registry.contains(freebsd));
+        assert_eq!(registry.name(freebsd), Some("FreeBSD"));
+        assert_eq!(registry.ids().count(), CODE_SPACE_COUNT + 1);
+
+        assert_eq!(registry.to_code(Button::Right, freebsd), Some(3));
+        assert_eq!(registry.from_code(2, freebsd), Some(Button::Middle));
+        assert_eq!(
+            registry.try_to_code(Button::X1, freebsd),
+            Err(MouseParseError::UnmappedButton("X1".to_string()))
+        );
+        assert_eq!(
+            registry.try_from_code(9, freebsd),
+            Err(MouseParseError::InvalidButtonCode(9))
+        );
+        assert_eq!(
+            registry.translate(3, freebsd, Platform::Windows.into()),
+            Some(2)
+        );
+        assert_eq!(
+            registry.translate(0x111, CodeSpace::Evdev.into(), freebsd),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn lookup_by_name_ignores_case_and_covers_builtins() {
+        let mut registry = PlatformRegistry::new();
+        let android = registry.register("Android", []).unwrap();
+
+        assert_eq!(registry.id("android"), Some(android));
+        assert_eq!(registry.id("x11"), Some(Platform::Linux.into()));
+        assert_eq!(registry.id("EVDEV"), Some(CodeSpace::Evdev.into()));
+        assert_eq!(registry.id("Amiga"), None);
+        assert_eq!(
+            registry.name(CodeSpace::Sdl.into()),
+            Some(CodeSpace::Sdl.as_str())
+        );
+
+        let unknown = PlatformId(u16::MAX);
+        assert!(!registry.contains(unknown));
+        assert_eq!(registry.name(unknown), None);
+        assert_eq!(
+            registry.try_to_code(Button::Left, unknown),
+            Err(MouseParseError::UnknownPlatform)
+        );
+    }
+
+    #[test]
+    fn duplicate_names_are_rejected() {
+        let mut registry = PlatformRegistry::new();
+        registry.register("Android", []).unwrap();
+
+        for name in ["ANDROID", "Linux", "evdev"] {
+            assert_eq!(
+                registry.register(name, []),
+                Err(MouseParseError::DuplicatePlatform(name.to_string()))
+            );
+        }
+        assert_eq!(registry.ids().count(), CODE_SPACE_COUNT + 1);
+    }
+
+    #[test]
+    fn code_collisions_are_rejected() {
+        let mut registry = PlatformRegistry::new();
+        assert_eq!(
+            registry.register("Broken", [(Button::Left, 1), (Button::Right, 1)]),
+            Err(MouseParseError::InvalidButtonCode(1))
+        );
+        assert_eq!(registry.id("Broken"), None);
+
+        // 重复出现的同一对 (按钮, 代码) 是一致的
+        let repeated = registry
+            .register("Repeated", [(Button::Left, 1), (Button::Left, 1)])
+            .unwrap();
+        assert_eq!(registry.from_code(1, repeated), Some(Button::Left));
+
+        // 后出现的代码替换先前的代码，旧代码不再指向该按钮
+        let moved = registry
+            .register("Moved", [(Button::Left, 1), (Button::Left, 5)])
+            .unwrap();
+        assert_eq!(registry.to_code(Button::Left, moved), Some(5));
+        assert_eq!(registry.from_code(1, moved), None);
+    }
+}
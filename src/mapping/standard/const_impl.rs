@@ -0,0 +1,117 @@
+//! Standard mapping implementation using `const fn` match tables
+
+use super::table::standard_button_table;
+#[cfg(feature = "alloc")]
+use crate::error::MouseParseError;
+use crate::mapping::CodeSpace;
+use crate::types::{Button, Platform};
+
+macro_rules! define_const_column {
+    ($space:ident($module:ident); $($(#[cfg($cfg:meta)])* $name:ident => $str:literal, $code:tt;)*) => {
+        mod $module {
+            use super::*;
+
+            #[allow(unreachable_patterns)]
+            pub(super) const fn to_code(button: Button) -> Option<usize> {
+                match button {
+                    $($(#[cfg($cfg)])* Button::$name => Some($code),)*
+                    _ => None,
+                }
+            }
+
+            pub(super) const fn from_code(code: usize) -> Option<Button> {
+                match code {
+                    $($(#[cfg($cfg)])* $code => Some(Button::$name),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+standard_button_table!(@columns define_const_column);
+
+macro_rules! define_const_dispatch {
+    (code_spaces: [$($(#[$space_attr:meta])* $space:ident($module:ident) => $space_meta:tt;)*]; buttons: [$($rows:tt)*];) => {
+        /// Convert a button to its code in `space` in a const context
+        pub(crate) const fn code_in(button: Button, space: CodeSpace) -> Option<usize> {
+            match space {
+                $(CodeSpace::$space => $module::to_code(button),)*
+            }
+        }
+
+        /// Parse a button from a code in `space` in a const context
+        pub(crate) const fn button_in(code: usize, space: CodeSpace) -> Option<Button> {
+            match space {
+                $(CodeSpace::$space => $module::from_code(code),)*
+            }
+        }
+    };
+}
+
+standard_button_table!(define_const_dispatch);
+
+/// Const-fn implementation of the CodeMapper trait
+pub trait CodeMapperImpl {
+    /// Convert the button to a platform-specific code
+    fn to_code(&self, platform: Platform) -> usize;
+
+    /// Parse a button from a platform-specific code
+    fn from_code(code: usize, platform: Platform) -> Option<Self>
+    where
+        Self: Sized;
+
+    /// Convert the button to a platform-specific code without panicking
+    #[cfg(feature = "alloc")]
+    fn try_to_code(&self, platform: Platform) -> Result<usize, MouseParseError>;
+}
+
+/// Convert a button to its code on `platform` in a const context
+///
+/// Platform columns of the table are complete, which the table checks at
+/// compile time, so this never panics.
+pub(crate) const fn platform_code(button: Button, platform: Platform) -> usize {
+    match code_in(button, platform.code_space()) {
+        Some(code) => code,
+        None => panic!("Invalid button for platform"),
+    }
+}
+
+impl CodeMapperImpl for Button {
+    fn to_code(&self, platform: Platform) -> usize {
+        platform_code(*self, platform)
+    }
+
+    fn from_code(code: usize, platform: Platform) -> Option<Self> {
+        button_in(code, platform.code_space())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn try_to_code(&self, platform: Platform) -> Result<usize, MouseParseError> {
+        code_in(*self, platform.code_space())
+            .ok_or_else(|| MouseParseError::UnmappedButton(self.as_str().into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tables_are_inverse() {
+        super::super::tests::assert_inverse(code_in, button_in);
+    }
+
+    #[test]
+    fn platform_tables_are_inverse() {
+        for platform in [Platform::Windows, Platform::Linux, Platform::MacOS] {
+            for button in Button::iter() {
+                let code = CodeMapperImpl::to_code(&button, platform);
+                assert_eq!(
+                    <Button as CodeMapperImpl>::from_code(code, platform),
+                    Some(button)
+                );
+            }
+        }
+    }
+}
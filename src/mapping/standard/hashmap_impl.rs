@@ -1,128 +1,41 @@
 //! Standard mapping implementation using std::collections::HashMap
 
+use super::{code_space_index, CODE_SPACE_COUNT, STANDARD_CODES};
+use crate::error::MouseParseError;
+use crate::mapping::CodeSpace;
 use crate::types::{Button, Platform};
 use std::collections::HashMap;
 use std::sync::LazyLock;
 
-static WINDOWS_CODE_MAP: LazyLock<HashMap<Button, usize>> = LazyLock::new(|| {
-    HashMap::from([
-        (Button::Left, 1),
-        (Button::Right, 2),
-        (Button::Middle, 4),
-        (Button::X1, 5),
-        (Button::X2, 6),
-        (Button::Extra3, 7),
-        (Button::Extra4, 8),
-        (Button::Extra5, 9),
-        (Button::Extra6, 10),
-        (Button::Extra7, 11),
-        (Button::Extra8, 12),
-        #[cfg(feature = "extended")]
-        (Button::Extra9, 13),
-        #[cfg(feature = "extended")]
-        (Button::Extra10, 14),
-    ])
+/// Forward maps (button to code), one per code space column
+static CODE_MAPS: LazyLock<[HashMap<Button, usize>; CODE_SPACE_COUNT]> = LazyLock::new(|| {
+    std::array::from_fn(|space| {
+        STANDARD_CODES
+            .iter()
+            .filter_map(|(button, codes)| Some((*button, codes[space]?)))
+            .collect()
+    })
 });
 
-static LINUX_CODE_MAP: LazyLock<HashMap<Button, usize>> = LazyLock::new(|| {
-    HashMap::from([
-        (Button::Left, 1),
-        (Button::Right, 3),
-        (Button::Middle, 2),
-        (Button::X1, 8),
-        (Button::X2, 9),
-        (Button::Extra3, 10),
-        (Button::Extra4, 11),
-        (Button::Extra5, 12),
-        (Button::Extra6, 13),
-        (Button::Extra7, 14),
-        (Button::Extra8, 15),
-        #[cfg(feature = "extended")]
-        (Button::Extra9, 16),
-        #[cfg(feature = "extended")]
-        (Button::Extra10, 17),
-    ])
+/// Reverse maps (code to button), one per code space column
+static REVERSE_MAPS: LazyLock<[HashMap<usize, Button>; CODE_SPACE_COUNT]> = LazyLock::new(|| {
+    std::array::from_fn(|space| {
+        STANDARD_CODES
+            .iter()
+            .filter_map(|(button, codes)| Some((codes[space]?, *button)))
+            .collect()
+    })
 });
 
-static MACOS_CODE_MAP: LazyLock<HashMap<Button, usize>> = LazyLock::new(|| {
-    HashMap::from([
-        (Button::Left, 0),
-        (Button::Right, 1),
-        (Button::Middle, 2),
-        (Button::X1, 3),
-        (Button::X2, 4),
-        (Button::Extra3, 5),
-        (Button::Extra4, 6),
-        (Button::Extra5, 7),
-        (Button::Extra6, 8),
-        (Button::Extra7, 9),
-        (Button::Extra8, 10),
-        #[cfg(feature = "extended")]
-        (Button::Extra9, 11),
-        #[cfg(feature = "extended")]
-        (Button::Extra10, 12),
-    ])
-});
-
-static WINDOWS_REVERSE_MAP: LazyLock<HashMap<usize, Button>> = LazyLock::new(|| {
-    HashMap::from([
-        (1, Button::Left),
-        (2, Button::Right),
-        (4, Button::Middle),
-        (5, Button::X1),
-        (6, Button::X2),
-        (7, Button::Extra3),
-        (8, Button::Extra4),
-        (9, Button::Extra5),
-        (10, Button::Extra6),
-        (11, Button::Extra7),
-        (12, Button::Extra8),
-        #[cfg(feature = "extended")]
-        (13, Button::Extra9),
-        #[cfg(feature = "extended")]
-        (14, Button::Extra10),
-    ])
-});
-
-static LINUX_REVERSE_MAP: LazyLock<HashMap<usize, Button>> = LazyLock::new(|| {
-    HashMap::from([
-        (1, Button::Left),
-        (3, Button::Right),
-        (2, Button::Middle),
-        (8, Button::X1),
-        (9, Button::X2),
-        (10, Button::Extra3),
-        (11, Button::Extra4),
-        (12, Button::Extra5),
-        (13, Button::Extra6),
-        (14, Button::Extra7),
-        (15, Button::Extra8),
-        #[cfg(feature = "extended")]
-        (16, Button::Extra9),
-        #[cfg(feature = "extended")]
-        (17, Button::Extra10),
-    ])
-});
+/// Convert a button to its code in `space`
+pub(crate) fn code_in(button: Button, space: CodeSpace) -> Option<usize> {
+    CODE_MAPS[code_space_index(space)].get(&button).copied()
+}
 
-static MACOS_REVERSE_MAP: LazyLock<HashMap<usize, Button>> = LazyLock::new(|| {
-    HashMap::from([
-        (0, Button::Left),
-        (1, Button::Right),
-        (2, Button::Middle),
-        (3, Button::X1),
-        (4, Button::X2),
-        (5, Button::Extra3),
-        (6, Button::Extra4),
-        (7, Button::Extra5),
-        (8, Button::Extra6),
-        (9, Button::Extra7),
-        (10, Button::Extra8),
-        #[cfg(feature = "extended")]
-        (11, Button::Extra9),
-        #[cfg(feature = "extended")]
-        (12, Button::Extra10),
-    ])
-});
+/// Parse a button from a code in `space`
+pub(crate) fn button_in(code: usize, space: CodeSpace) -> Option<Button> {
+    REVERSE_MAPS[code_space_index(space)].get(&code).copied()
+}
 
 /// HashMap implementation of the CodeMapper trait
 pub trait CodeMapperImpl {
@@ -133,28 +46,45 @@ pub trait CodeMapperImpl {
     fn from_code(code: usize, platform: Platform) -> Option<Self>
     where
         Self: Sized;
+
+    /// Convert the button to a platform-specific code without panicking
+    fn try_to_code(&self, platform: Platform) -> Result<usize, MouseParseError>;
 }
 
 impl CodeMapperImpl for Button {
     fn to_code(&self, platform: Platform) -> usize {
-        match platform {
-            Platform::Windows => *WINDOWS_CODE_MAP
-                .get(self)
-                .expect("Invalid button for Windows platform"),
-            Platform::Linux => *LINUX_CODE_MAP
-                .get(self)
-                .expect("Invalid button for Linux platform"),
-            Platform::MacOS => *MACOS_CODE_MAP
-                .get(self)
-                .expect("Invalid button for macOS platform"),
-        }
+        CodeMapperImpl::try_to_code(self, platform).expect("Invalid button for platform")
+    }
+
+    fn try_to_code(&self, platform: Platform) -> Result<usize, MouseParseError> {
+        code_in(*self, platform.code_space())
+            .ok_or_else(|| MouseParseError::UnmappedButton(self.to_string()))
     }
 
     fn from_code(code: usize, platform: Platform) -> Option<Self> {
-        match platform {
-            Platform::Windows => WINDOWS_REVERSE_MAP.get(&code).copied(),
-            Platform::Linux => LINUX_REVERSE_MAP.get(&code).copied(),
-            Platform::MacOS => MACOS_REVERSE_MAP.get(&code).copied(),
+        button_in(code, platform.code_space())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tables_are_inverse() {
+        super::super::tests::assert_inverse(code_in, button_in);
+    }
+
+    #[test]
+    fn platform_tables_are_inverse() {
+        for platform in [Platform::Windows, Platform::Linux, Platform::MacOS] {
+            for button in Button::iter() {
+                let code = CodeMapperImpl::to_code(&button, platform);
+                assert_eq!(
+                    <Button as CodeMapperImpl>::from_code(code, platform),
+                    Some(button)
+                );
+            }
         }
     }
 }
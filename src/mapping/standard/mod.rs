@@ -1,62 +1,111 @@
 //! Standard mouse button code mappings for cross-platform compatibility
 
+use crate::mapping::CodeSpace;
+#[cfg(feature = "alloc")]
+use crate::parser::borrowed;
 use crate::types::{Button, Platform};
 
+/// Declarative button table shared by every backend
+pub(crate) mod table;
+
+macro_rules! define_codes {
+    (
+        code_spaces: [$($(#[$space_attr:meta])* $space:ident($module:ident) => $space_meta:tt;)*];
+        buttons: [$(
+            $(#[cfg($cfg:meta)])*
+            #[doc = $doc:literal]
+            $name:ident => $str:literal, [$($alias:literal),*], [$($code:tt),*];
+        )*];
+    ) => {
+        /// Number of code space columns in the standard table
+        pub(crate) const CODE_SPACE_COUNT: usize = [$(stringify!($space)),*].len();
+
+        /// Standard buttons with their code in each space, indexed by `code_space_index`
+        pub(crate) const STANDARD_CODES: &[(Button, [Option<usize>; CODE_SPACE_COUNT])] = &[$(
+            $(#[cfg($cfg)])*
+            (Button::$name, [$(table::standard_button_table!(@cell $code)),*]),
+        )*];
+
+        /// Column of the standard table holding the codes for `space`
+        #[cfg(feature = "alloc")]
+        pub(crate) const fn code_space_index(space: CodeSpace) -> usize {
+            let mut index = 0;
+            $(
+                if matches!(space, CodeSpace::$space) {
+                    return index;
+                }
+                index += 1;
+            )*
+            let _ = index;
+            unreachable!()
+        }
+    };
+}
+
+table::standard_button_table!(define_codes);
+
+// Every code must be unique within its code space column, otherwise the reverse
+// tables would not be inverses of the forward tables. Code spaces of a
+// `Platform` must give every button a code, so that `Button::to_code` cannot fail.
+const _: () = {
+    let mut space = 0;
+    while space < CODE_SPACE_COUNT {
+        let complete = CodeSpace::ALL[space].platform().is_some();
+        let mut i = 0;
+        while i < STANDARD_CODES.len() {
+            let code = STANDARD_CODES[i].1[space];
+            assert!(
+                code.is_some() || !complete,
+                "missing code in a platform column of the standard button table"
+            );
+            let mut j = i + 1;
+            while j < STANDARD_CODES.len() {
+                if let (Some(a), Some(b)) = (code, STANDARD_CODES[j].1[space]) {
+                    assert!(a != b, "duplicate code in standard button table");
+                }
+                j += 1;
+            }
+            i += 1;
+        }
+        space += 1;
+    }
+};
+
 /// Parse a button from a string with exact matching
+#[cfg(feature = "alloc")]
 pub fn parse_button_from_str(s: &str) -> Result<Button, crate::error::MouseParseError> {
-    match s {
-        "Left" => Ok(Button::Left),
-        "Right" => Ok(Button::Right),
-        "Middle" => Ok(Button::Middle),
-        "X1" => Ok(Button::X1),
-        "X2" => Ok(Button::X2),
-        "Extra3" => Ok(Button::Extra3),
-        "Extra4" => Ok(Button::Extra4),
-        "Extra5" => Ok(Button::Extra5),
-        "Extra6" => Ok(Button::Extra6),
-        "Extra7" => Ok(Button::Extra7),
-        "Extra8" => Ok(Button::Extra8),
-        #[cfg(feature = "extended")]
-        "Extra9" => Ok(Button::Extra9),
-        #[cfg(feature = "extended")]
-        "Extra10" => Ok(Button::Extra10),
-        _ => Err(crate::error::MouseParseError::UnknownButton(s.to_string())),
-    }
+    borrowed::parse_button_from_str(s).map_err(Into::into)
 }
 
 /// Parse a button from a string with case-insensitive matching
+#[cfg(feature = "alloc")]
 pub fn parse_button_ignore_case(s: &str) -> Result<Button, crate::error::MouseParseError> {
-    let s = s.to_ascii_lowercase();
-    match s.as_str() {
-        "left" => Ok(Button::Left),
-        "right" => Ok(Button::Right),
-        "middle" => Ok(Button::Middle),
-        "x1" => Ok(Button::X1),
-        "x2" => Ok(Button::X2),
-        "extra3" => Ok(Button::Extra3),
-        "extra4" => Ok(Button::Extra4),
-        "extra5" => Ok(Button::Extra5),
-        "extra6" => Ok(Button::Extra6),
-        "extra7" => Ok(Button::Extra7),
-        "extra8" => Ok(Button::Extra8),
-        #[cfg(feature = "extended")]
-        "extra9" => Ok(Button::Extra9),
-        #[cfg(feature = "extended")]
-        "extra10" => Ok(Button::Extra10),
-        _ => Err(crate::error::MouseParseError::UnknownButton(s)),
-    }
+    borrowed::parse_button_ignore_case(s)
+        .map_err(|_| crate::error::MouseParseError::UnknownButton(s.to_ascii_lowercase()))
 }
 
-// 根据特性选择不同的实现
-#[cfg(feature = "phf")]
+// 根据特性选择不同的实现（优先级：const-table > phf > HashMap）
+// 未启用 std 且未指定后端时，回退到 const 实现
+#[cfg(any(feature = "const-table", not(any(feature = "phf", feature = "std"))))]
+pub(crate) mod const_impl;
+#[cfg(any(feature = "const-table", not(any(feature = "phf", feature = "std"))))]
+pub use const_impl::CodeMapperImpl as CodeMapper;
+#[cfg(any(feature = "const-table", not(any(feature = "phf", feature = "std"))))]
+pub(crate) use const_impl::{button_in, code_in};
+
+#[cfg(all(feature = "phf", not(feature = "const-table")))]
 mod phf_impl;
-#[cfg(feature = "phf")]
+#[cfg(all(feature = "phf", not(feature = "const-table")))]
 pub use phf_impl::CodeMapperImpl as CodeMapper;
+#[cfg(all(feature = "phf", not(feature = "const-table")))]
+pub(crate) use phf_impl::{button_in, code_in};
 
-#[cfg(not(feature = "phf"))]
+#[cfg(all(feature = "std", not(any(feature = "phf", feature = "const-table"))))]
 mod hashmap_impl;
-#[cfg(not(feature = "phf"))]
+#[cfg(all(feature = "std", not(any(feature = "phf", feature = "const-table"))))]
 pub use hashmap_impl::CodeMapperImpl as CodeMapper;
+#[cfg(all(feature = "std", not(any(feature = "phf", feature = "const-table"))))]
+pub(crate) use hashmap_impl::{button_in, code_in};
 
 // 为 Button 实现 CodeMapper  trait（转发到具体实现）
 impl crate::types::CodeMapper for Button {
@@ -67,4 +116,87 @@ impl crate::types::CodeMapper for Button {
     fn from_code(code: usize, platform: Platform) -> Option<Self> {
         <Self as CodeMapper>::from_code(code, platform)
     }
+
+    #[cfg(feature = "alloc")]
+    fn try_to_code(&self, platform: Platform) -> Result<usize, crate::error::MouseParseError> {
+        <Self as CodeMapper>::try_to_code(self, platform)
+    }
+}
+
+/// Checks shared by the tests of every mapping backend
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::STANDARD_CODES;
+    use crate::mapping::CodeSpace;
+    use crate::parser::borrowed::parse_code_space;
+    use crate::types::Button;
+
+    /// Check that a backend's forward and reverse tables are mutual inverses
+    ///
+    /// Every button must have its table code in every code space and
+    /// round-trip through it, and every code up to past the largest one in
+    /// the table that no button maps to must have no button.
+    pub(crate) fn assert_inverse(
+        to_code: impl Fn(Button, CodeSpace) -> Option<usize>,
+        from_code: impl Fn(usize, CodeSpace) -> Option<Button>,
+    ) {
+        for (index, space) in CodeSpace::ALL.into_iter().enumerate() {
+            for button in Button::iter() {
+                let (_, codes) = STANDARD_CODES.iter().find(|(b, _)| *b == button).unwrap();
+                assert_eq!(
+                    to_code(button, space),
+                    codes[index],
+                    "{} in {}",
+                    button,
+                    space
+                );
+                if let Some(code) = codes[index] {
+                    assert_eq!(
+                        from_code(code, space),
+                        Some(button),
+                        "{} in {}",
+                        button,
+                        space
+                    );
+                }
+            }
+
+            let codes: Vec<usize> = STANDARD_CODES
+                .iter()
+                .filter_map(|(_, codes)| codes[index])
+                .collect();
+            let max = codes.iter().copied().max().unwrap_or(0);
+            for code in (0..=max + 16).filter(|code| !codes.contains(code)) {
+                assert_eq!(from_code(code, space), None, "code {} in {}", code, space);
+            }
+        }
+    }
+
+    #[test]
+    fn glfw_has_no_code_for_extra_buttons() {
+        // GLFW 只定义了 0 到 7 号按钮
+        assert_eq!(CodeSpace::Glfw.to_code(Button::Extra5), Some(7));
+        assert_eq!(CodeSpace::Glfw.to_code(Button::Extra6), None);
+        assert_eq!(CodeSpace::Glfw.from_code(8), None);
+        #[cfg(feature = "alloc")]
+        assert_eq!(
+            CodeSpace::Glfw.try_to_code(Button::Extra6),
+            Err(crate::error::MouseParseError::UnmappedButton(
+                "Extra6".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn code_spaces_match_their_platforms() {
+        for space in CodeSpace::ALL {
+            if let Some(platform) = space.platform() {
+                assert_eq!(platform.code_space(), space);
+            }
+            assert_eq!(parse_code_space(space.as_str()), Ok(space));
+            for alias in space.aliases() {
+                assert_eq!(parse_code_space(alias), Ok(space));
+            }
+        }
+    }
 }
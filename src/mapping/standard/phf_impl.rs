@@ -1,136 +1,44 @@
 //! Standard mapping implementation using phf hashmaps
 
+use super::table::standard_button_table;
+#[cfg(feature = "alloc")]
+use crate::error::MouseParseError;
+use crate::mapping::CodeSpace;
 use crate::types::{Button, Platform};
+#[cfg(feature = "alloc")]
+use alloc::string::ToString;
 use phf::phf_map;
 
-static WINDOWS_CODE_MAP: phf::Map<u8, usize> = phf_map! {
-    0u8 => 1,   // Button::Left
-    1u8 => 2,   // Button::Right
-    2u8 => 4,   // Button::Middle
-    3u8 => 5,   // Button::X1
-    4u8 => 6,   // Button::X2
-    5u8 => 7,   // Button::Extra3
-    6u8 => 8,   // Button::Extra4
-    7u8 => 9,   // Button::Extra5
-    8u8 => 10,  // Button::Extra6
-    9u8 => 11,  // Button::Extra7
-    10u8 => 12, // Button::Extra8
-    #[cfg(feature = "extended")]
-    11u8 => 13, // Button::Extra9
-    #[cfg(feature = "extended")]
-    12u8 => 14, // Button::Extra10
-};
-
-static LINUX_CODE_MAP: phf::Map<u8, usize> = phf_map! {
-    0u8 => 1,   // Button::Left
-    1u8 => 3,   // Button::Right
-    2u8 => 2,   // Button::Middle
-    3u8 => 8,   // Button::X1
-    4u8 => 9,   // Button::X2
-    5u8 => 10,  // Button::Extra3
-    6u8 => 11,  // Button::Extra4
-    7u8 => 12,  // Button::Extra5
-    8u8 => 13,  // Button::Extra6
-    9u8 => 14,  // Button::Extra7
-    10u8 => 15, // Button::Extra8
-    #[cfg(feature = "extended")]
-    11u8 => 16, // Button::Extra9
-    #[cfg(feature = "extended")]
-    12u8 => 17, // Button::Extra10
-};
-
-static MACOS_CODE_MAP: phf::Map<u8, usize> = phf_map! {
-    0u8 => 0,   // Button::Left
-    1u8 => 1,   // Button::Right
-    2u8 => 2,   // Button::Middle
-    3u8 => 3,   // Button::X1
-    4u8 => 4,   // Button::X2
-    5u8 => 5,   // Button::Extra3
-    6u8 => 6,   // Button::Extra4
-    7u8 => 7,   // Button::Extra5
-    8u8 => 8,   // Button::Extra6
-    9u8 => 9,   // Button::Extra7
-    10u8 => 10, // Button::Extra8
-    #[cfg(feature = "extended")]
-    11u8 => 11, // Button::Extra9
-    #[cfg(feature = "extended")]
-    12u8 => 12, // Button::Extra10
-};
-
-static WINDOWS_REVERSE_MAP: phf::Map<usize, Button> = phf_map! {
-    1usize => Button::Left,
-    2usize => Button::Right,
-    4usize => Button::Middle,
-    5usize => Button::X1,
-    6usize => Button::X2,
-    7usize => Button::Extra3,
-    8usize => Button::Extra4,
-    9usize => Button::Extra5,
-    10usize => Button::Extra6,
-    11usize => Button::Extra7,
-    12usize => Button::Extra8,
-    #[cfg(feature = "extended")]
-    13usize => Button::Extra9,
-    #[cfg(feature = "extended")]
-    14usize => Button::Extra10,
-};
-
-static LINUX_REVERSE_MAP: phf::Map<usize, Button> = phf_map! {
-    1usize => Button::Left,
-    3usize => Button::Right,
-    2usize => Button::Middle,
-    8usize => Button::X1,
-    9usize => Button::X2,
-    10usize => Button::Extra3,
-    11usize => Button::Extra4,
-    12usize => Button::Extra5,
-    13usize => Button::Extra6,
-    14usize => Button::Extra7,
-    15usize => Button::Extra8,
-    #[cfg(feature = "extended")]
-    16usize => Button::Extra9,
-    #[cfg(feature = "extended")]
-    17usize => Button::Extra10,
-};
-
-static MACOS_REVERSE_MAP: phf::Map<usize, Button> = phf_map! {
-    0usize => Button::Left,
-    1usize => Button::Right,
-    2usize => Button::Middle,
-    3usize => Button::X1,
-    4usize => Button::X2,
-    5usize => Button::Extra3,
-    6usize => Button::Extra4,
-    7usize => Button::Extra5,
-    8usize => Button::Extra6,
-    9usize => Button::Extra7,
-    10usize => Button::Extra8,
-    #[cfg(feature = "extended")]
-    11usize => Button::Extra9,
-    #[cfg(feature = "extended")]
-    12usize => Button::Extra10,
-};
-
-// 为 Button 实现 u8 转换（用于 phf 键）
+// phf_map! 不支持条目上的 #[cfg]，因此受特性控制的行始终存在，
+// 在特性未启用时反向映射的值为 None。
+macro_rules! define_phf_column {
+    ($space:ident($module:ident); $($(#[cfg($cfg:meta)])* $name:ident => $str:literal, $code:tt;)*) => {
+        mod $module {
+            use super::*;
+
+            pub(super) static CODE_MAP: phf::Map<&'static str, usize> = phf_map! {
+                $($str => $code,)*
+            };
+
+            pub(super) static REVERSE_MAP: phf::Map<usize, Option<Button>> = phf_map! {
+                $($code => {
+                    $(#[cfg($cfg)])*
+                    let button = Some(Button::$name);
+                    #[cfg(not(all($($cfg),*)))]
+                    let button = None;
+                    button
+                },)*
+            };
+        }
+    };
+}
+
+standard_button_table!(@columns define_phf_column);
+
+// 为 Button 实现 u8 转换（按声明顺序编号）
 impl From<Button> for u8 {
     fn from(btn: Button) -> Self {
-        match btn {
-            Button::Left => 0,
-            Button::Right => 1,
-            Button::Middle => 2,
-            Button::X1 => 3,
-            Button::X2 => 4,
-            Button::Extra3 => 5,
-            Button::Extra4 => 6,
-            Button::Extra5 => 7,
-            Button::Extra6 => 8,
-            Button::Extra7 => 9,
-            Button::Extra8 => 10,
-            #[cfg(feature = "extended")]
-            Button::Extra9 => 11,
-            #[cfg(feature = "extended")]
-            Button::Extra10 => 12,
-        }
+        btn as u8
     }
 }
 
@@ -143,22 +51,67 @@ pub trait CodeMapperImpl {
     fn from_code(code: usize, platform: Platform) -> Option<Self>
     where
         Self: Sized;
+
+    /// Convert the button to a platform-specific code without panicking
+    #[cfg(feature = "alloc")]
+    fn try_to_code(&self, platform: Platform) -> Result<usize, MouseParseError>;
 }
 
-impl CodeMapperImpl for Button {
-    fn to_code(&self, platform: Platform) -> usize {
-        match platform {
-            Platform::Windows => WINDOWS_CODE_MAP[&u8::from(*self)],
-            Platform::Linux => LINUX_CODE_MAP[&u8::from(*self)],
-            Platform::MacOS => MACOS_CODE_MAP[&u8::from(*self)],
+macro_rules! define_phf_dispatch {
+    (code_spaces: [$($(#[$space_attr:meta])* $space:ident($module:ident) => $space_meta:tt;)*]; buttons: [$($rows:tt)*];) => {
+        /// Convert a button to its code in `space`
+        pub(crate) fn code_in(button: Button, space: CodeSpace) -> Option<usize> {
+            match space {
+                $(CodeSpace::$space => $module::CODE_MAP.get(button.as_str()).copied(),)*
+            }
+        }
+
+        /// Parse a button from a code in `space`
+        pub(crate) fn button_in(code: usize, space: CodeSpace) -> Option<Button> {
+            match space {
+                $(CodeSpace::$space => $module::REVERSE_MAP.get(&code).copied().flatten(),)*
+            }
+        }
+
+        impl CodeMapperImpl for Button {
+            fn to_code(&self, platform: Platform) -> usize {
+                code_in(*self, platform.code_space()).expect("Invalid button for platform")
+            }
+
+            #[cfg(feature = "alloc")]
+            fn try_to_code(&self, platform: Platform) -> Result<usize, MouseParseError> {
+                code_in(*self, platform.code_space())
+                    .ok_or_else(|| MouseParseError::UnmappedButton(self.to_string()))
+            }
+
+            fn from_code(code: usize, platform: Platform) -> Option<Self> {
+                button_in(code, platform.code_space())
+            }
         }
+    };
+}
+
+standard_button_table!(define_phf_dispatch);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tables_are_inverse() {
+        super::super::tests::assert_inverse(code_in, button_in);
     }
 
-    fn from_code(code: usize, platform: Platform) -> Option<Self> {
-        match platform {
-            Platform::Windows => WINDOWS_REVERSE_MAP.get(&code).copied(),
-            Platform::Linux => LINUX_REVERSE_MAP.get(&code).copied(),
-            Platform::MacOS => MACOS_REVERSE_MAP.get(&code).copied(),
+    #[test]
+    fn platform_tables_are_inverse() {
+        for platform in [Platform::Windows, Platform::Linux, Platform::MacOS] {
+            for button in Button::iter() {
+                let code = CodeMapperImpl::to_code(&button, platform);
+                assert_eq!(
+                    <Button as CodeMapperImpl>::from_code(code, platform),
+                    Some(button)
+                );
+            }
         }
     }
 }
@@ -0,0 +1,142 @@
+//! Declarative source of truth for the standard button tables
+//!
+//! Each code space lists its `CodeSpace` variant with its documentation,
+//! display name, lowercase parser aliases and the `Platform` whose standard
+//! codes it holds, if any. Each row lists a button variant with its
+//! documentation, canonical name, lowercase parser aliases and its code in
+//! every code space, in the order the code spaces are listed. The `Button` and
+//! `CodeSpace` enums, the string parsers and every mapping backend are
+//! generated from this table through callback macros, so adding a row or a
+//! code space column updates all of them at once.
+//!
+//! Codes carry an explicit `usize` suffix because `phf_map!` needs typed keys.
+//! A `_` cell leaves the button without a code in that space; the columns of
+//! code spaces tied to a `Platform` must be complete.
+//!
+//! `standard_button_table!(callback)` passes the whole table to `callback`,
+//! while `standard_button_table!(@columns callback)` invokes `callback` once per
+//! code space as `CodeSpace(module); Button => "Name", code; ...`, leaving out
+//! the buttons without a code in that space. `standard_button_table!(@cell c)`
+//! turns a cell into an `Option<usize>`. Callbacks
+//! that only need the variant and module of each code space can match its
+//! metadata as a single `tt`.
+
+macro_rules! standard_button_table {
+    (@columns $callback:ident) => {
+        standard_button_table!(standard_button_table @peel $callback);
+    };
+    (
+        @peel $callback:ident
+        code_spaces: [
+            $(#[$first_attr:meta])* $space:ident($module:ident) => $first_meta:tt;
+            $($(#[$rest_attr:meta])* $rest:ident($rest_module:ident) => $rest_meta:tt;)*
+        ];
+        buttons: [$(
+            $(#[cfg($cfg:meta)])*
+            #[doc = $doc:literal]
+            $name:ident => $str:literal, [$($alias:literal),*], [$code:tt $(, $codes:tt)*];
+        )*];
+    ) => {
+        standard_button_table! {
+            @column $callback $space($module) []
+            $($(#[cfg($cfg)])* $name => $str, $code;)*
+        }
+
+        standard_button_table! {
+            @peel $callback
+            code_spaces: [$($(#[$rest_attr])* $rest($rest_module) => $rest_meta;)*];
+            buttons: [$(
+                $(#[cfg($cfg)])*
+                #[doc = $doc]
+                $name => $str, [$($alias),*], [$($codes),*];
+            )*];
+        }
+    };
+    (@peel $callback:ident code_spaces: []; buttons: [$($rows:tt)*];) => {};
+    (@column $callback:ident $space:ident($module:ident) [$($done:tt)*]) => {
+        $callback! {
+            $space($module);
+            $($done)*
+        }
+    };
+    (
+        @column $callback:ident $space:ident($module:ident) [$($done:tt)*]
+        $(#[cfg($cfg:meta)])* $name:ident => $str:literal, _;
+        $($rest:tt)*
+    ) => {
+        standard_button_table!(@column $callback $space($module) [$($done)*] $($rest)*);
+    };
+    (
+        @column $callback:ident $space:ident($module:ident) [$($done:tt)*]
+        $(#[cfg($cfg:meta)])* $name:ident => $str:literal, $code:tt;
+        $($rest:tt)*
+    ) => {
+        standard_button_table! {
+            @column $callback $space($module)
+            [$($done)* $(#[cfg($cfg)])* $name => $str, $code;]
+            $($rest)*
+        }
+    };
+    (@cell _) => {
+        None
+    };
+    (@cell $code:tt) => {
+        Some($code)
+    };
+    ($callback:ident $($prefix:tt)*) => {
+        $callback! {
+            $($prefix)*
+            code_spaces: [
+                /// Windows virtual key and `XBUTTON` numbering
+                Windows(windows) => ("Windows", [], [Windows]);
+                /// X11 core protocol button numbers
+                Linux(linux) => ("Linux", ["x11"], [Linux]);
+                /// macOS `NSEvent` button numbers
+                MacOS(macos) => ("macOS", ["osx"], [MacOS]);
+                /// Linux kernel `BTN_*` codes, used by evdev, uinput and Wayland
+                Evdev(evdev) => ("evdev", [], []);
+                /// SDL2 `SDL_BUTTON_*` indices, starting at 1 with the middle button before the right
+                Sdl(sdl) => ("SDL", ["sdl2"], []);
+                /// GLFW `GLFW_MOUSE_BUTTON_*` indices, starting at 0
+                ///
+                /// GLFW defines buttons 0 to 7, so `Extra6` and higher have no code.
+                Glfw(glfw) => ("GLFW", [], []);
+                /// winit `MouseButton` as an index: `Left`, `Right`, `Middle`, `Back` and
+                /// `Forward` are 0 to 4 and `Other(n)` is `n`
+                Winit(winit) => ("winit", [], []);
+            ];
+            buttons: [
+                /// Left mouse button
+                Left => "Left", ["lmb"], [1usize, 1usize, 0usize, 0x110_usize, 1usize, 0usize, 0usize];
+                /// Right mouse button
+                Right => "Right", ["rmb"], [2usize, 3usize, 1usize, 0x111_usize, 3usize, 1usize, 1usize];
+                /// Middle mouse button (scroll wheel press)
+                Middle => "Middle", ["mmb", "wheel"], [4usize, 2usize, 2usize, 0x112_usize, 2usize, 2usize, 2usize];
+                /// X1 button (usually back)
+                X1 => "X1", ["back"], [5usize, 8usize, 3usize, 0x113_usize, 4usize, 3usize, 3usize];
+                /// X2 button (usually forward)
+                X2 => "X2", ["forward"], [6usize, 9usize, 4usize, 0x114_usize, 5usize, 4usize, 4usize];
+                /// Extra button 3
+                Extra3 => "Extra3", [], [7usize, 10usize, 5usize, 0x115_usize, 6usize, 5usize, 5usize];
+                /// Extra button 4
+                Extra4 => "Extra4", [], [8usize, 11usize, 6usize, 0x116_usize, 7usize, 6usize, 6usize];
+                /// Extra button 5
+                Extra5 => "Extra5", [], [9usize, 12usize, 7usize, 0x117_usize, 8usize, 7usize, 7usize];
+                /// Extra button 6
+                Extra6 => "Extra6", [], [10usize, 13usize, 8usize, 0x118_usize, 9usize, _, 8usize];
+                /// Extra button 7
+                Extra7 => "Extra7", [], [11usize, 14usize, 9usize, 0x119_usize, 10usize, _, 9usize];
+                /// Extra button 8
+                Extra8 => "Extra8", [], [12usize, 15usize, 10usize, 0x11a_usize, 11usize, _, 10usize];
+                #[cfg(feature = "extended")]
+                /// Extra button 9 (extended feature)
+                Extra9 => "Extra9", [], [13usize, 16usize, 11usize, 0x11b_usize, 12usize, _, 11usize];
+                #[cfg(feature = "extended")]
+                /// Extra button 10 (extended feature)
+                Extra10 => "Extra10", [], [14usize, 17usize, 12usize, 0x11c_usize, 13usize, _, 12usize];
+            ];
+        }
+    };
+}
+
+pub(crate) use standard_button_table;
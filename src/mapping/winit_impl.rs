@@ -0,0 +1,166 @@
+//! Conversions between `Button` and winit's `MouseButton`
+//!
+//! winit names the first five buttons and fills `MouseButton::Other` with the
+//! number the windowing backend reported: X11 core protocol numbers on X11,
+//! kernel `BTN_*` codes on Wayland and `NSEvent` button numbers on macOS. On
+//! Windows and the web it reports no other buttons. Numbers that winit turns
+//! into a named button never arrive as `Other`, so they are rejected.
+//!
+//! The `TryFrom` conversions resolve `Other` against the backend detected by
+//! `InputBackend::detect`; `InputBackend::from_winit` and `to_winit` take the
+//! backend explicitly.
+
+use ::winit::event::MouseButton;
+
+use crate::error::MouseParseError;
+use crate::types::{Button, InputBackend};
+
+// winit 在 Wayland 上把 BTN_FORWARD 和 BTN_BACK 也报告为具名按钮
+const BTN_FORWARD: usize = 0x115;
+const BTN_BACK: usize = 0x116;
+
+impl InputBackend {
+    /// Get the winit button this backend reports for `button`
+    ///
+    /// Returns `None` for buttons winit cannot report on this backend, such as
+    /// every extra button on Windows. On Wayland, `Extra3` and `Extra4` are
+    /// `BTN_FORWARD` and `BTN_BACK`, which winit reports as `Forward` and `Back`.
+    pub fn to_winit(&self, button: Button) -> Option<MouseButton> {
+        match button {
+            Button::Left => return Some(MouseButton::Left),
+            Button::Right => return Some(MouseButton::Right),
+            Button::Middle => return Some(MouseButton::Middle),
+            Button::X1 => return Some(MouseButton::Back),
+            Button::X2 => return Some(MouseButton::Forward),
+            _ => {}
+        }
+        let code = match self {
+            InputBackend::Windows => return None,
+            backend => backend.code_space().to_code(button)?,
+        };
+        match (self, code) {
+            (InputBackend::Wayland | InputBackend::Evdev, BTN_FORWARD) => {
+                Some(MouseButton::Forward)
+            }
+            (InputBackend::Wayland | InputBackend::Evdev, BTN_BACK) => Some(MouseButton::Back),
+            _ => u16::try_from(code).ok().map(MouseButton::Other),
+        }
+    }
+
+    /// Convert a winit button reported by this backend
+    ///
+    /// `Other` codes that name no button in the backend's code space, or that
+    /// winit reports as a named button on this backend, are rejected.
+    pub fn from_winit(&self, button: MouseButton) -> Result<Button, MouseParseError> {
+        match button {
+            MouseButton::Left => Ok(Button::Left),
+            MouseButton::Right => Ok(Button::Right),
+            MouseButton::Middle => Ok(Button::Middle),
+            MouseButton::Back => Ok(Button::X1),
+            MouseButton::Forward => Ok(Button::X2),
+            MouseButton::Other(code) => self
+                .code_space()
+                .from_code(code.into())
+                .filter(|candidate| self.to_winit(*candidate) == Some(button))
+                .ok_or(MouseParseError::InvalidButtonCode(code.into())),
+        }
+    }
+}
+
+impl TryFrom<Button> for MouseButton {
+    type Error = MouseParseError;
+
+    /// Convert a button to the winit button the detected backend reports for it
+    fn try_from(button: Button) -> Result<Self, Self::Error> {
+        InputBackend::detect()
+            .unwrap_or(InputBackend::Windows)
+            .to_winit(button)
+            .ok_or_else(|| MouseParseError::UnmappedButton(button.as_str().into()))
+    }
+}
+
+impl TryFrom<MouseButton> for Button {
+    type Error = MouseParseError;
+
+    /// Convert a winit button, resolving `Other` against the detected backend
+    fn try_from(button: MouseButton) -> Result<Self, Self::Error> {
+        // 未知后端（例如 web）上 winit 只报告具名按钮
+        InputBackend::detect()
+            .unwrap_or(InputBackend::Windows)
+            .from_winit(button)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn other_codes_follow_the_backend() {
+        // X11 按钮 10 起为额外按钮，8 和 9 已是 Back/Forward
+        assert_eq!(
+            InputBackend::X11.from_winit(MouseButton::Other(10)),
+            Ok(Button::Extra3)
+        );
+        assert_eq!(
+            InputBackend::X11.to_winit(Button::Extra3),
+            Some(MouseButton::Other(10))
+        );
+        assert_eq!(
+            InputBackend::Wayland.from_winit(MouseButton::Other(0x117)),
+            Ok(Button::Extra5)
+        );
+        assert_eq!(
+            InputBackend::MacOS.from_winit(MouseButton::Other(5)),
+            Ok(Button::Extra3)
+        );
+        assert_eq!(InputBackend::Windows.to_winit(Button::Extra3), None);
+    }
+
+    #[test]
+    fn other_codes_of_named_buttons_are_rejected() {
+        for backend in [
+            InputBackend::Windows,
+            InputBackend::MacOS,
+            InputBackend::X11,
+            InputBackend::Wayland,
+        ] {
+            for code in 0..=4 {
+                assert!(backend.from_winit(MouseButton::Other(code)).is_err());
+            }
+        }
+        for code in [8, 9] {
+            assert!(InputBackend::X11
+                .from_winit(MouseButton::Other(code))
+                .is_err());
+        }
+        for code in 0x110..=0x116 {
+            assert!(InputBackend::Wayland
+                .from_winit(MouseButton::Other(code))
+                .is_err());
+        }
+        assert_eq!(
+            InputBackend::Wayland.to_winit(Button::Extra4),
+            Some(MouseButton::Back)
+        );
+    }
+
+    #[test]
+    fn buttons_round_trip_where_reported() {
+        for backend in [
+            InputBackend::Windows,
+            InputBackend::MacOS,
+            InputBackend::X11,
+            InputBackend::Wayland,
+        ] {
+            for button in Button::iter() {
+                let Some(winit) = backend.to_winit(button) else {
+                    continue;
+                };
+                if let MouseButton::Other(_) = winit {
+                    assert_eq!(backend.from_winit(winit), Ok(button));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,530 @@
+//! Pointer acceleration
+//!
+//! An `AccelerationProfile` maps the speed of the device to a gain applied to
+//! its deltas. Speeds are in device counts per millisecond; profiles that
+//! work in physical units take the device resolution to convert them.
+//!
+//! `Accelerator` applies a profile to `RelativeMove` events. Accelerated
+//! deltas are fractional, so the part that does not fit in a whole count is
+//! carried into the next motion: slow motion below one count per event still
+//! adds up to cursor movement instead of being rounded away.
+
+use core::time::Duration;
+
+use super::Remainder;
+use crate::types::{MouseEvent, TimedEvent};
+
+/// Resolution assumed for mice that do not report one, in counts per inch
+pub const DEFAULT_MOUSE_DPI: f64 = 1000.0;
+
+/// Time between motion events when none is known (a 125 Hz mouse)
+pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(8);
+
+/// Gain of the pointer as a function of device speed
+pub trait AccelerationProfile {
+    /// Get the factor applied to deltas moving at `speed` counts per millisecond
+    fn factor(&self, speed: f64) -> f64;
+}
+
+impl<P: AccelerationProfile + ?Sized> AccelerationProfile for &P {
+    fn factor(&self, speed: f64) -> f64 {
+        (**self).factor(speed)
+    }
+}
+
+impl<P: AccelerationProfile + ?Sized> AccelerationProfile for Box<P> {
+    fn factor(&self, speed: f64) -> f64 {
+        (**self).factor(speed)
+    }
+}
+
+/// libinput's flat profile: a constant factor set by the speed setting
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LibinputFlat {
+    factor: f64,
+}
+
+impl LibinputFlat {
+    /// Create the profile for a speed setting between -1 and 1
+    pub fn new(speed: f64) -> Self {
+        let speed = speed.clamp(-1.0, 1.0);
+        Self {
+            factor: (1.0 + speed).max(0.005),
+        }
+    }
+}
+
+impl Default for LibinputFlat {
+    fn default() -> Self {
+        Self::new(0.0)
+    }
+}
+
+impl AccelerationProfile for LibinputFlat {
+    fn factor(&self, _speed: f64) -> f64 {
+        self.factor
+    }
+}
+
+/// libinput's adaptive profile for mice
+///
+/// Slow motion is decelerated down to 0.3, motion between 0.07 counts/ms and
+/// the threshold is 1:1, and faster motion is accelerated along a linear
+/// incline up to the maximum factor. The speed setting moves the threshold,
+/// the incline and the maximum like libinput's `accelerator_set_speed`.
+/// Speeds are normalized to 1000 DPI first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LibinputAdaptive {
+    dpi: f64,
+    threshold: f64,
+    max_accel: f64,
+    incline: f64,
+}
+
+impl LibinputAdaptive {
+    /// Create the profile for a speed setting between -1 and 1
+    pub fn new(speed: f64) -> Self {
+        Self::with_dpi(speed, DEFAULT_MOUSE_DPI)
+    }
+
+    /// Create the profile for a mouse of the given resolution
+    ///
+    /// Resolutions below 1 DPI, including NaN, are raised to 1.
+    pub fn with_dpi(speed: f64, dpi: f64) -> Self {
+        let speed = speed.clamp(-1.0, 1.0);
+        Self {
+            dpi: dpi.max(1.0),
+            // 以下常量与 libinput filter-linear.c 一致（单位换算为 counts/ms）
+            threshold: (0.4 - 0.25 * speed).max(0.2),
+            max_accel: 2.0 + 1.5 * speed,
+            incline: 1.1 + 0.75 * speed,
+        }
+    }
+}
+
+impl Default for LibinputAdaptive {
+    fn default() -> Self {
+        Self::new(0.0)
+    }
+}
+
+impl AccelerationProfile for LibinputAdaptive {
+    fn factor(&self, speed: f64) -> f64 {
+        let speed = speed * DEFAULT_MOUSE_DPI / self.dpi;
+        let factor = if speed < 0.07 {
+            10.0 * speed + 0.3
+        } else if speed < self.threshold {
+            1.0
+        } else {
+            self.incline * (speed - self.threshold) + 1.0
+        };
+        factor.min(self.max_accel)
+    }
+}
+
+/// Default `SmoothMouseXCurve` registry value, in 16.16 fixed point
+pub const SMOOTH_MOUSE_X_CURVE: [u32; 5] =
+    [0x00000000, 0x00006E15, 0x00014000, 0x0003DC29, 0x00280000];
+
+/// Default `SmoothMouseYCurve` registry value, in 16.16 fixed point
+pub const SMOOTH_MOUSE_Y_CURVE: [u32; 5] =
+    [0x00000000, 0x00015EB8, 0x00054CCD, 0x00184CCD, 0x02380000];
+
+/// Windows "Enhance pointer precision"
+///
+/// The ballistics curve maps mouse speed in inches per second (the
+/// `SmoothMouseXCurve` points) to pointer speed in inches per second (the
+/// `SmoothMouseYCurve` points), interpolating linearly between points and
+/// extending the last segment. The pointer distance is converted to pixels
+/// at the screen resolution and scaled by the pointer speed slider, which
+/// goes from 1 to 20 with 10 as the 1:1 default.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowsEnhancedPrecision {
+    curve: [(f64, f64); 5],
+    mouse_dpi: f64,
+    screen_dpi: f64,
+    sensitivity: f64,
+}
+
+impl WindowsEnhancedPrecision {
+    /// Create the profile with the default curves, a 400 DPI mouse and a 96 DPI screen
+    pub fn new() -> Self {
+        Self::from_registry(SMOOTH_MOUSE_X_CURVE, SMOOTH_MOUSE_Y_CURVE)
+    }
+
+    /// Create the profile from `SmoothMouseXCurve` and `SmoothMouseYCurve` values
+    pub fn from_registry(x: [u32; 5], y: [u32; 5]) -> Self {
+        let fixed = |value: u32| f64::from(value) / 65536.0;
+        Self {
+            curve: core::array::from_fn(|i| (fixed(x[i]), fixed(y[i]))),
+            mouse_dpi: 400.0,
+            screen_dpi: 96.0,
+            sensitivity: 1.0,
+        }
+    }
+
+    /// Set the mouse and screen resolutions, in dots per inch
+    ///
+    /// Resolutions below 1 DPI, including NaN, are raised to 1.
+    pub fn with_resolution(mut self, mouse_dpi: f64, screen_dpi: f64) -> Self {
+        self.mouse_dpi = mouse_dpi.max(1.0);
+        self.screen_dpi = screen_dpi.max(1.0);
+        self
+    }
+
+    /// Set the pointer speed slider position, from 1 to 20
+    pub fn with_sensitivity(mut self, position: u8) -> Self {
+        self.sensitivity = f64::from(position.clamp(1, 20)) / 10.0;
+        self
+    }
+
+    /// Interpolate the pointer speed for a mouse speed, both in inches per second
+    fn pointer_speed(&self, speed: f64) -> f64 {
+        let last = self.curve.len() - 1;
+        let segment = (1..last).find(|&i| speed < self.curve[i].0).unwrap_or(last);
+        let (x0, y0) = self.curve[segment - 1];
+        let (x1, y1) = self.curve[segment];
+        if x1 <= x0 {
+            return y1;
+        }
+        y0 + (speed - x0) * (y1 - y0) / (x1 - x0)
+    }
+}
+
+impl Default for WindowsEnhancedPrecision {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AccelerationProfile for WindowsEnhancedPrecision {
+    fn factor(&self, speed: f64) -> f64 {
+        // 鼠标速度：counts/ms -> 英寸/秒
+        let inches = speed * 1000.0 / self.mouse_dpi;
+        let gain = if inches > 0.0 {
+            self.pointer_speed(inches) / inches
+        } else {
+            // 静止时取第一段的斜率
+            let (x0, y0) = self.curve[0];
+            let (x1, y1) = self.curve[1];
+            (y1 - y0) / (x1 - x0)
+        };
+        gain * self.screen_dpi / self.mouse_dpi * self.sensitivity
+    }
+}
+
+/// macOS parametric acceleration
+///
+/// The IOHID parametric curve maps device speed in inches per second to
+/// cursor speed: a polynomial up to the linear tangent speed, a straight line
+/// from there to the root tangent speed, and a square root beyond it, each
+/// piece continuing the previous one's value and slope. The acceleration
+/// setting (`com.apple.mouse.scaling`, 0 to 3) scales the non-linear gains.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MacAcceleration {
+    gain: [f64; 4],
+    tangent_linear: f64,
+    tangent_root: f64,
+    mouse_dpi: f64,
+}
+
+impl MacAcceleration {
+    /// Create the profile for an acceleration setting
+    pub fn new(acceleration: f64) -> Self {
+        let acceleration = acceleration.clamp(0.0, 3.0);
+        Self::with_curve(
+            [1.0, 0.08 * acceleration, 0.004 * acceleration, 0.0],
+            12.0,
+            30.0,
+        )
+    }
+
+    /// Create the profile from the curve parameters
+    ///
+    /// `gain` holds the linear, parabolic, cubic and quartic gains.
+    pub fn with_curve(gain: [f64; 4], tangent_linear: f64, tangent_root: f64) -> Self {
+        Self {
+            gain,
+            tangent_linear,
+            tangent_root: tangent_root.max(tangent_linear),
+            mouse_dpi: 400.0,
+        }
+    }
+
+    /// Set the mouse resolution, in counts per inch
+    ///
+    /// Resolutions below 1 DPI, including NaN, are raised to 1.
+    pub fn with_dpi(mut self, dpi: f64) -> Self {
+        self.mouse_dpi = dpi.max(1.0);
+        self
+    }
+
+    /// Value and slope of the polynomial piece
+    fn polynomial(&self, x: f64) -> (f64, f64) {
+        let [g1, g2, g3, g4] = self.gain;
+        let value = x * (g1 + x * (g2 + x * (g3 + x * g4)));
+        let slope = g1 + x * (2.0 * g2 + x * (3.0 * g3 + x * 4.0 * g4));
+        (value, slope)
+    }
+
+    /// Cursor speed for a device speed, both in inches per second
+    fn cursor_speed(&self, x: f64) -> f64 {
+        let (y1, slope) = self.polynomial(x.min(self.tangent_linear));
+        if x <= self.tangent_linear {
+            return y1;
+        }
+        let y2 = y1 + slope * (x.min(self.tangent_root) - self.tangent_linear);
+        if x <= self.tangent_root {
+            return y2;
+        }
+        // sqrt(a·x + b) 在切点处与直线段的值和斜率相同
+        let a = 2.0 * y2 * slope;
+        let b = y2 * y2 - a * self.tangent_root;
+        (a * x + b).sqrt()
+    }
+}
+
+impl Default for MacAcceleration {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl AccelerationProfile for MacAcceleration {
+    fn factor(&self, speed: f64) -> f64 {
+        let inches = speed * 1000.0 / self.mouse_dpi;
+        if inches > 0.0 {
+            self.cursor_speed(inches) / inches
+        } else {
+            self.gain[0]
+        }
+    }
+}
+
+/// Applies an acceleration profile to relative motion
+///
+/// The speed of each motion is its length divided by the time since the
+/// previous one. Fractions of a count are carried over between motions;
+/// a motion that accelerates to less than one count produces no event.
+#[derive(Debug, Clone)]
+pub struct Accelerator<P> {
+    profile: P,
+    interval: Duration,
+    remainder: Remainder,
+    last: Option<Duration>,
+}
+
+impl<P: AccelerationProfile> Accelerator<P> {
+    /// Create an accelerator
+    pub fn new(profile: P) -> Self {
+        Self {
+            profile,
+            interval: DEFAULT_INTERVAL,
+            remainder: Remainder::default(),
+            last: None,
+        }
+    }
+
+    /// Set the time between untimed motions and before the first timed one
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Get the profile
+    pub fn profile(&self) -> &P {
+        &self.profile
+    }
+
+    /// Get the fraction of a count carried into the next motion
+    pub fn remainder(&self) -> (f64, f64) {
+        self.remainder.get()
+    }
+
+    /// Forget the carried fractions and the time of the last motion
+    pub fn reset(&mut self) {
+        self.remainder = Remainder::default();
+        self.last = None;
+    }
+
+    /// Accelerate a delta that took `elapsed` to move
+    pub fn accelerate(&mut self, dx: i32, dy: i32, elapsed: Duration) -> (i32, i32) {
+        let elapsed = if elapsed.is_zero() {
+            self.interval
+        } else {
+            elapsed
+        };
+        let (dx, dy) = (f64::from(dx), f64::from(dy));
+        let speed = dx.hypot(dy) / (elapsed.as_secs_f64() * 1000.0);
+        let factor = self.profile.factor(speed);
+
+        self.remainder.carry(dx * factor, dy * factor)
+    }
+
+    /// Accelerate an event, assuming motions are one interval apart
+    ///
+    /// Returns `None` when a motion is carried over entirely.
+    pub fn apply(&mut self, event: &MouseEvent) -> Option<MouseEvent> {
+        match *event {
+            MouseEvent::RelativeMove(dx, dy) => match self.accelerate(dx, dy, self.interval) {
+                (0, 0) => None,
+                (dx, dy) => Some(MouseEvent::RelativeMove(dx, dy)),
+            },
+            event => Some(event),
+        }
+    }
+
+    /// Accelerate a timed event, measuring the time since the previous motion
+    ///
+    /// Returns `None` when a motion is carried over entirely.
+    pub fn apply_timed(&mut self, event: &TimedEvent) -> Option<TimedEvent> {
+        let MouseEvent::RelativeMove(dx, dy) = event.event else {
+            return Some(*event);
+        };
+        let elapsed = match self.last.replace(event.time) {
+            Some(last) => event.time.saturating_sub(last),
+            None => self.interval,
+        };
+        match self.accelerate(dx, dy, elapsed) {
+            (0, 0) => None,
+            (dx, dy) => Some(TimedEvent::new(
+                event.time,
+                MouseEvent::RelativeMove(dx, dy),
+            )),
+        }
+    }
+
+    /// Accelerate a stream of timed events
+    pub fn stream<I>(self, events: I) -> Accelerated<I::IntoIter, P>
+    where
+        I: IntoIterator<Item = TimedEvent>,
+    {
+        Accelerated {
+            events: events.into_iter(),
+            accelerator: self,
+        }
+    }
+}
+
+/// Iterator returned by `Accelerator::stream`
+#[derive(Debug, Clone)]
+pub struct Accelerated<I, P> {
+    events: I,
+    accelerator: Accelerator<P>,
+}
+
+impl<I, P> Accelerated<I, P> {
+    /// Get the accelerator
+    pub fn accelerator(&self) -> &Accelerator<P> {
+        &self.accelerator
+    }
+}
+
+impl<I, P> Iterator for Accelerated<I, P>
+where
+    I: Iterator<Item = TimedEvent>,
+    P: AccelerationProfile,
+{
+    type Item = TimedEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.events
+            .by_ref()
+            .find_map(|event| self.accelerator.apply_timed(&event))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Motion at one default interval apart
+    const STREAM: [(i32, i32); 4] = [(8, 0), (2, 0), (40, 0), (0, -8)];
+
+    fn run<P: AccelerationProfile>(profile: P) -> Vec<(i32, i32)> {
+        let mut accelerator = Accelerator::new(profile);
+        STREAM
+            .iter()
+            .map(|&(dx, dy)| accelerator.accelerate(dx, dy, DEFAULT_INTERVAL))
+            .collect()
+    }
+
+    #[test]
+    fn libinput_flat() {
+        // 速度设置 0.5 即恒定 1.5 倍
+        assert_eq!(
+            run(LibinputFlat::new(0.5)),
+            [(12, 0), (3, 0), (60, 0), (0, -12)]
+        );
+    }
+
+    #[test]
+    fn libinput_adaptive() {
+        // 1 count/ms 位于阈值 0.4 之上：1.1 × 0.6 + 1 = 1.66；5 counts/ms 达到上限 2
+        assert_eq!(
+            run(LibinputAdaptive::default()),
+            [(13, 0), (2, 0), (80, 0), (0, -13)]
+        );
+        // 2000 DPI 的鼠标速度减半后再套用曲线
+        assert_eq!(
+            run(LibinputAdaptive::with_dpi(0.0, 2000.0)),
+            [(8, 0), (2, 0), (80, 0), (0, -8)]
+        );
+    }
+
+    #[test]
+    fn windows_enhanced_precision() {
+        // 1 count/ms 即 2.5 英寸/秒，位于第三段：增益约 5.76，乘以 96/400
+        assert_eq!(
+            run(WindowsEnhancedPrecision::new()),
+            [(11, 0), (1, 0), (119, 0), (0, -11)]
+        );
+    }
+
+    #[test]
+    fn mac_acceleration() {
+        // 2.5 英寸/秒位于多项式段：1 + 2.5 × (0.08 + 2.5 × 0.004) = 1.225；
+        // 12.5 英寸/秒刚过线性切点，约 2.62
+        assert_eq!(
+            run(MacAcceleration::default()),
+            [(9, 0), (2, 0), (105, 0), (0, -9)]
+        );
+        assert_eq!(
+            run(MacAcceleration::new(0.0)),
+            [(8, 0), (2, 0), (40, 0), (0, -8)]
+        );
+    }
+
+    #[test]
+    fn fractions_carry_over() {
+        // 每次 0.3 个计数，十次累计为 3 个像素
+        let mut accelerator = Accelerator::new(LibinputFlat::new(-0.7));
+        let total: i32 = (0..10)
+            .filter_map(|_| accelerator.apply(&MouseEvent::RelativeMove(1, 0)))
+            .map(|event| match event {
+                MouseEvent::RelativeMove(dx, _) => dx,
+                _ => 0,
+            })
+            .sum();
+        assert_eq!(total, 3);
+        assert!(accelerator.remainder().0.abs() < 1e-9);
+    }
+
+    #[test]
+    fn zero_dpi_is_clamped() {
+        for dpi in [0.0, -5.0, f64::NAN] {
+            let profiles: [Box<dyn AccelerationProfile>; 3] = [
+                Box::new(LibinputAdaptive::with_dpi(0.0, dpi)),
+                Box::new(WindowsEnhancedPrecision::new().with_resolution(dpi, dpi)),
+                Box::new(MacAcceleration::default().with_dpi(dpi)),
+            ];
+            for profile in profiles {
+                let mut accelerator = Accelerator::new(profile);
+                accelerator.accelerate(3, 4, DEFAULT_INTERVAL);
+                let (x, y) = accelerator.remainder();
+                assert!(x.is_finite() && y.is_finite(), "{}", dpi);
+                assert_ne!(accelerator.accelerate(1, 0, DEFAULT_INTERVAL), (0, 0));
+            }
+        }
+    }
+}
@@ -0,0 +1,383 @@
+//! Desktop geometry
+//!
+//! A `Desktop` is a set of monitors placed on the virtual desktop, each with
+//! its origin and size in physical pixels and a scale factor. The virtual
+//! desktop is the rectangle bounding them; it can have gaps where no monitor
+//! is, which the cursor can never reach.
+//!
+//! `MouseEvent::Move` coordinates are physical virtual-desktop pixels here.
+//! Logical coordinates divide distances from a monitor's origin by its scale
+//! factor, like per-monitor DPI awareness does on Windows, and normalized
+//! coordinates map the virtual desktop onto 0..=65535 as `SendInput` expects
+//! with `MOUSEEVENTF_ABSOLUTE | MOUSEEVENTF_VIRTUALDESK`.
+
+use crate::types::MouseEvent;
+
+/// Largest normalized absolute coordinate
+pub const NORMALIZED_MAX: i32 = 65535;
+
+/// Map `value` in `origin..origin + length` onto `0..=NORMALIZED_MAX`
+fn normalize(value: i32, origin: i32, length: u32) -> i32 {
+    let span = i64::from(length.max(2) - 1);
+    let offset = (i64::from(value) - i64::from(origin)).clamp(0, span);
+    // 四舍五入，保证与 denormalize 往返一致
+    ((offset * i64::from(NORMALIZED_MAX) + span / 2) / span) as i32
+}
+
+/// Map `value` in `0..=NORMALIZED_MAX` back onto `origin..origin + length`
+fn denormalize(value: i32, origin: i32, length: u32) -> i32 {
+    let span = i64::from(length.max(2) - 1);
+    let value = i64::from(value.clamp(0, NORMALIZED_MAX));
+    let max = i64::from(NORMALIZED_MAX);
+    (i64::from(origin) + (value * span + max / 2) / max) as i32
+}
+
+/// Clamp `value` to `origin..end`, or to `origin` when the range is empty
+fn clamp_axis(value: i32, origin: i32, end: i32) -> i32 {
+    // 在 i64 中计算 end - 1，避免 end 为 i32::MIN 时溢出
+    let last = (i64::from(end) - 1).max(i64::from(origin));
+    i64::from(value).clamp(i64::from(origin), last) as i32
+}
+
+/// Distance of `value` from `origin`, exact in `f64`
+fn offset(value: i32, origin: i32) -> f64 {
+    f64::from(value) - f64::from(origin)
+}
+
+/// Move `origin` by a distance, rounding and saturating to the `i32` range
+fn translate(origin: i32, distance: f64) -> i32 {
+    // 浮点数到整数的 as 转换会饱和
+    (f64::from(origin) + distance.round()) as i32
+}
+
+/// A monitor on the virtual desktop
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Monitor {
+    /// Left edge, in physical pixels
+    pub x: i32,
+    /// Top edge, in physical pixels
+    pub y: i32,
+    /// Width, in physical pixels
+    pub width: u32,
+    /// Height, in physical pixels
+    pub height: u32,
+    /// Physical pixels per logical pixel (1.5 for 144 DPI on Windows)
+    ///
+    /// Conversions treat a scale that is not finite and positive as 1.
+    pub scale: f64,
+}
+
+impl Monitor {
+    /// Create a monitor with a scale factor of 1
+    pub const fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            scale: 1.0,
+        }
+    }
+
+    /// Set the scale factor
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `scale` is finite and positive; use `try_with_scale` for
+    /// scales read from elsewhere.
+    pub fn with_scale(self, scale: f64) -> Self {
+        self.try_with_scale(scale).expect("Invalid monitor scale")
+    }
+
+    /// Set the scale factor, if it is finite and positive
+    pub fn try_with_scale(mut self, scale: f64) -> Option<Self> {
+        if !(scale.is_finite() && scale > 0.0) {
+            return None;
+        }
+        self.scale = scale;
+        Some(self)
+    }
+
+    /// Scale factor used by conversions
+    fn factor(&self) -> f64 {
+        if self.scale.is_finite() && self.scale > 0.0 {
+            self.scale
+        } else {
+            1.0
+        }
+    }
+
+    /// Right edge, exclusive
+    pub fn right(&self) -> i32 {
+        self.x.saturating_add_unsigned(self.width)
+    }
+
+    /// Bottom edge, exclusive
+    pub fn bottom(&self) -> i32 {
+        self.y.saturating_add_unsigned(self.height)
+    }
+
+    /// Check whether a physical point lies on the monitor
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        (self.x..self.right()).contains(&x) && (self.y..self.bottom()).contains(&y)
+    }
+
+    /// Get the closest physical point on the monitor
+    pub fn clamp(&self, x: i32, y: i32) -> (i32, i32) {
+        (
+            clamp_axis(x, self.x, self.right()),
+            clamp_axis(y, self.y, self.bottom()),
+        )
+    }
+
+    /// Get the size in logical pixels
+    pub fn logical_size(&self) -> (u32, u32) {
+        (
+            (f64::from(self.width) / self.factor()).round() as u32,
+            (f64::from(self.height) / self.factor()).round() as u32,
+        )
+    }
+
+    /// Check whether a logical point lies on the monitor
+    pub fn contains_logical(&self, x: i32, y: i32) -> bool {
+        let (width, height) = self.logical_size();
+        (self.x..self.x.saturating_add_unsigned(width)).contains(&x)
+            && (self.y..self.y.saturating_add_unsigned(height)).contains(&y)
+    }
+
+    /// Convert a physical point into logical coordinates
+    ///
+    /// Results beyond the `i32` range saturate.
+    pub fn to_logical(&self, x: i32, y: i32) -> (i32, i32) {
+        let scale =
+            |value: i32, origin: i32| translate(origin, offset(value, origin) / self.factor());
+        (scale(x, self.x), scale(y, self.y))
+    }
+
+    /// Convert a logical point into physical coordinates
+    ///
+    /// Results beyond the `i32` range saturate.
+    pub fn to_physical(&self, x: i32, y: i32) -> (i32, i32) {
+        let scale =
+            |value: i32, origin: i32| translate(origin, offset(value, origin) * self.factor());
+        (scale(x, self.x), scale(y, self.y))
+    }
+
+    /// Map a physical point onto 0..=65535 over this monitor
+    ///
+    /// This is what `MOUSEEVENTF_ABSOLUTE` expects without
+    /// `MOUSEEVENTF_VIRTUALDESK`, for the primary monitor.
+    pub fn normalize(&self, x: i32, y: i32) -> (i32, i32) {
+        (
+            normalize(x, self.x, self.width),
+            normalize(y, self.y, self.height),
+        )
+    }
+
+    /// Map a normalized point back onto the monitor
+    pub fn denormalize(&self, x: i32, y: i32) -> (i32, i32) {
+        (
+            denormalize(x, self.x, self.width),
+            denormalize(y, self.y, self.height),
+        )
+    }
+}
+
+/// Monitors of the virtual desktop and the cursor position on it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Desktop {
+    monitors: Vec<Monitor>,
+    position: (i32, i32),
+}
+
+impl Desktop {
+    /// Create a desktop, with the cursor at the closest point to the origin
+    pub fn new(monitors: impl IntoIterator<Item = Monitor>) -> Self {
+        let mut desktop = Self {
+            monitors: monitors.into_iter().collect(),
+            position: (0, 0),
+        };
+        desktop.position = desktop.clamp(0, 0);
+        desktop
+    }
+
+    /// Get the monitors
+    pub fn monitors(&self) -> &[Monitor] {
+        &self.monitors
+    }
+
+    /// Get the cursor position, in physical pixels
+    pub fn position(&self) -> (i32, i32) {
+        self.position
+    }
+
+    /// Move the cursor, clamped to the monitors
+    pub fn set_position(&mut self, x: i32, y: i32) {
+        self.position = self.clamp(x, y);
+    }
+
+    /// Get the virtual desktop: the left, top, width and height bounding all monitors
+    pub fn bounds(&self) -> (i32, i32, u32, u32) {
+        let Some(first) = self.monitors.first() else {
+            return (0, 0, 0, 0);
+        };
+        let (mut left, mut top) = (first.x, first.y);
+        let (mut right, mut bottom) = (first.right(), first.bottom());
+        for monitor in &self.monitors[1..] {
+            left = left.min(monitor.x);
+            top = top.min(monitor.y);
+            right = right.max(monitor.right());
+            bottom = bottom.max(monitor.bottom());
+        }
+        (left, top, right.abs_diff(left), bottom.abs_diff(top))
+    }
+
+    /// Get the monitor under a physical point
+    pub fn monitor_at(&self, x: i32, y: i32) -> Option<&Monitor> {
+        self.monitors.iter().find(|monitor| monitor.contains(x, y))
+    }
+
+    /// Get the closest physical point on any monitor
+    ///
+    /// Points in gaps between monitors move to the nearest monitor edge.
+    /// Without monitors the point is returned unchanged.
+    pub fn clamp(&self, x: i32, y: i32) -> (i32, i32) {
+        self.monitors
+            .iter()
+            .map(|monitor| monitor.clamp(x, y))
+            .min_by_key(|&(cx, cy)| {
+                let (dx, dy) = (i64::from(cx) - i64::from(x), i64::from(cy) - i64::from(y));
+                dx * dx + dy * dy
+            })
+            .unwrap_or((x, y))
+    }
+
+    /// Convert a physical point into logical coordinates of its monitor
+    pub fn to_logical(&self, x: i32, y: i32) -> Option<(i32, i32)> {
+        self.monitor_at(x, y)
+            .map(|monitor| monitor.to_logical(x, y))
+    }
+
+    /// Convert a logical point into physical coordinates
+    pub fn to_physical(&self, x: i32, y: i32) -> Option<(i32, i32)> {
+        self.monitors
+            .iter()
+            .find(|monitor| monitor.contains_logical(x, y))
+            .map(|monitor| monitor.to_physical(x, y))
+    }
+
+    /// Map a physical point onto 0..=65535 over the virtual desktop
+    pub fn normalize(&self, x: i32, y: i32) -> (i32, i32) {
+        let (left, top, width, height) = self.bounds();
+        (normalize(x, left, width), normalize(y, top, height))
+    }
+
+    /// Map a normalized point back onto the virtual desktop
+    ///
+    /// The result can lie in a gap between monitors; `clamp` moves it onto one.
+    pub fn denormalize(&self, x: i32, y: i32) -> (i32, i32) {
+        let (left, top, width, height) = self.bounds();
+        (denormalize(x, left, width), denormalize(y, top, height))
+    }
+
+    /// Turn motion into an absolute `Move`, clamped to the monitors
+    ///
+    /// Other events are returned unchanged.
+    pub fn to_absolute(&mut self, event: &MouseEvent) -> MouseEvent {
+        let (x, y) = match *event {
+            MouseEvent::Move(x, y) => (x, y),
+            MouseEvent::RelativeMove(dx, dy) => (
+                self.position.0.saturating_add(dx),
+                self.position.1.saturating_add(dy),
+            ),
+            event => return event,
+        };
+        self.set_position(x, y);
+        MouseEvent::Move(self.position.0, self.position.1)
+    }
+
+    /// Turn motion into a `RelativeMove` from the cursor position
+    ///
+    /// The target of a `Move` is clamped to the monitors first. Other events
+    /// are returned unchanged.
+    pub fn to_relative(&mut self, event: &MouseEvent) -> MouseEvent {
+        let (x, y) = self.position;
+        match self.to_absolute(event) {
+            MouseEvent::Move(..) => MouseEvent::RelativeMove(
+                self.position.0.saturating_sub(x),
+                self.position.1.saturating_sub(y),
+            ),
+            event => event,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaled_conversions() {
+        let monitor = Monitor::new(1920, -200, 3840, 2160).with_scale(1.5);
+        assert_eq!(monitor.logical_size(), (2560, 1440));
+        assert_eq!(
+            monitor.to_logical(1920 + 300, -200 + 150),
+            (1920 + 200, -200 + 100)
+        );
+        assert_eq!(
+            monitor.to_physical(1920 + 200, -200 + 100),
+            (1920 + 300, -200 + 150)
+        );
+        assert!(monitor.contains_logical(1920 + 2559, 1239));
+        assert!(!monitor.contains_logical(1920 + 2560, 0));
+    }
+
+    #[test]
+    fn invalid_scales_are_rejected() {
+        let monitor = Monitor::new(0, 0, 100, 100);
+        for scale in [0.0, -1.5, f64::NAN, f64::INFINITY] {
+            assert_eq!(monitor.try_with_scale(scale), None, "{}", scale);
+        }
+        assert_eq!(monitor.try_with_scale(2.0).map(|m| m.scale), Some(2.0));
+
+        // 直接写入字段的无效缩放按 1 处理
+        let monitor = Monitor {
+            scale: 0.0,
+            ..monitor
+        };
+        assert_eq!(monitor.to_logical(50, 50), (50, 50));
+        assert_eq!(monitor.logical_size(), (100, 100));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid monitor scale")]
+    fn with_scale_panics_on_zero() {
+        Monitor::new(0, 0, 100, 100).with_scale(0.0);
+    }
+
+    #[test]
+    fn extreme_coordinates_saturate() {
+        // 宽度为 0 且原点为 i32::MIN 时，right() - 1 不能溢出
+        let empty = Monitor::new(i32::MIN, i32::MIN, 0, 0);
+        assert_eq!(empty.clamp(0, i32::MAX), (i32::MIN, i32::MIN));
+
+        let edge = Monitor::new(i32::MAX - 10, 0, 100, 100);
+        assert_eq!(edge.clamp(i32::MAX, -5), (i32::MAX - 1, 0));
+
+        let far = Monitor::new(i32::MIN, i32::MIN, u32::MAX, u32::MAX).with_scale(2.0);
+        assert_eq!(far.to_physical(i32::MAX, 0), (i32::MAX, i32::MAX));
+        assert_eq!(far.to_logical(i32::MAX, i32::MIN), (0, i32::MIN));
+
+        let small = Monitor::new(i32::MAX, i32::MIN, 1, 1).with_scale(0.5);
+        assert_eq!(small.to_logical(i32::MIN, i32::MAX), (i32::MIN, i32::MAX));
+        assert_eq!(small.to_physical(i32::MIN, i32::MAX), (-1, 0));
+
+        let mut desktop = Desktop::new([Monitor::new(i32::MIN, 0, u32::MAX, 1)]);
+        desktop.set_position(i32::MAX - 1, 0);
+        assert_eq!(
+            desktop.to_relative(&MouseEvent::Move(i32::MIN, 0)),
+            MouseEvent::RelativeMove(i32::MIN, 0)
+        );
+    }
+}
@@ -0,0 +1,743 @@
+//! Filters over timestamped event streams
+//!
+//! An `EventFilter` takes events one at a time and lets through, drops,
+//! delays or replaces them. `EventFilter::stream` turns a filter into an
+//! iterator adapter, so filters compose by feeding one filter's stream into
+//! the next.
+//!
+//! The smoothing filters work on positions: `Move` coordinates directly, and
+//! `RelativeMove` deltas summed into a position whose smoothed differences
+//! are emitted, with fractions of a count carried over. Smoothing lags behind
+//! the raw motion, so `flush` emits what is left: a last `RelativeMove` making
+//! the total displacement equal the raw one, and a last `Move` to the raw
+//! position.
+
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+
+use core::time::Duration;
+
+use super::Remainder;
+use crate::types::{MouseEvent, TimedEvent};
+
+/// Filter over timestamped events
+pub trait EventFilter {
+    /// Feed an event, appending the events let through to `output`
+    fn push(&mut self, event: TimedEvent, output: &mut VecDeque<TimedEvent>);
+
+    /// Append the events still held back, at the end of a stream
+    fn flush(&mut self, _output: &mut VecDeque<TimedEvent>) {}
+
+    /// Filter a stream of events
+    fn stream<I>(self, events: I) -> Filtered<I::IntoIter, Self>
+    where
+        Self: Sized,
+        I: IntoIterator<Item = TimedEvent>,
+    {
+        Filtered {
+            events: events.into_iter(),
+            filter: self,
+            queue: VecDeque::new(),
+            done: false,
+        }
+    }
+}
+
+/// Iterator returned by `EventFilter::stream`
+#[derive(Debug, Clone)]
+pub struct Filtered<I, F> {
+    events: I,
+    filter: F,
+    queue: VecDeque<TimedEvent>,
+    done: bool,
+}
+
+impl<I, F> Filtered<I, F> {
+    /// Get the filter
+    pub fn filter(&self) -> &F {
+        &self.filter
+    }
+}
+
+impl<I, F> Iterator for Filtered<I, F>
+where
+    I: Iterator<Item = TimedEvent>,
+    F: EventFilter,
+{
+    type Item = TimedEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.queue.pop_front() {
+                return Some(event);
+            }
+            if self.done {
+                return None;
+            }
+            match self.events.next() {
+                Some(event) => self.filter.push(event, &mut self.queue),
+                None => {
+                    self.done = true;
+                    self.filter.flush(&mut self.queue);
+                }
+            }
+        }
+    }
+}
+
+/// Smoothing of one coordinate
+trait Smoother: Default {
+    type Params;
+
+    /// Smooth a sample taken `dt` seconds after the previous one
+    fn smooth(&mut self, value: f64, dt: f64, params: &Self::Params) -> f64;
+}
+
+/// Smoothing state of one kind of motion
+#[derive(Debug, Clone, Default)]
+struct Channel<S> {
+    x: S,
+    y: S,
+    time: Option<Duration>,
+}
+
+impl<S: Smoother> Channel<S> {
+    fn smooth(&mut self, x: f64, y: f64, time: Duration, params: &S::Params) -> (f64, f64) {
+        let dt = match self.time.replace(time) {
+            Some(last) => time.saturating_sub(last).as_secs_f64(),
+            None => 0.0,
+        };
+        (self.x.smooth(x, dt, params), self.y.smooth(y, dt, params))
+    }
+}
+
+/// Smoothing state of `Move` and `RelativeMove` events
+#[derive(Debug, Clone, Default)]
+struct Smoothing<S> {
+    absolute: Channel<S>,
+    relative: Channel<S>,
+    // 最后一次 Move 的原始位置与输出位置
+    last_move: Option<((i32, i32), (i32, i32))>,
+    // 相对移动累加出的原始位置与上一次平滑后的位置
+    position: (f64, f64),
+    smoothed: (f64, f64),
+    remainder: Remainder,
+}
+
+impl<S: Smoother> Smoothing<S> {
+    fn push(&mut self, event: TimedEvent, params: &S::Params, output: &mut VecDeque<TimedEvent>) {
+        let smoothed = match event.event {
+            MouseEvent::Move(x, y) => {
+                let (sx, sy) = self
+                    .absolute
+                    .smooth(f64::from(x), f64::from(y), event.time, params);
+                let smoothed = (sx.round() as i32, sy.round() as i32);
+                self.last_move = Some(((x, y), smoothed));
+                MouseEvent::Move(smoothed.0, smoothed.1)
+            }
+            MouseEvent::RelativeMove(dx, dy) => {
+                self.position.0 += f64::from(dx);
+                self.position.1 += f64::from(dy);
+                let (x, y) =
+                    self.relative
+                        .smooth(self.position.0, self.position.1, event.time, params);
+                let (dx, dy) = (x - self.smoothed.0, y - self.smoothed.1);
+                self.smoothed = (x, y);
+                match self.remainder.carry(dx, dy) {
+                    (0, 0) => return,
+                    (dx, dy) => MouseEvent::RelativeMove(dx, dy),
+                }
+            }
+            _ => event.event,
+        };
+        output.push_back(TimedEvent::new(event.time, smoothed));
+    }
+
+    /// Emit the motion smoothing still holds back and start over at rest
+    fn flush(&mut self, output: &mut VecDeque<TimedEvent>) {
+        if let (Some(time), Some((raw, smoothed))) = (self.absolute.time, self.last_move) {
+            if raw != smoothed {
+                output.push_back(TimedEvent::new(time, MouseEvent::Move(raw.0, raw.1)));
+            }
+        }
+        if let Some(time) = self.relative.time {
+            // 已输出的总位移为平滑位置减去余数，与原始位置之差为整数
+            let (rx, ry) = self.remainder.get();
+            let dx = (self.position.0 - self.smoothed.0 + rx).round() as i32;
+            let dy = (self.position.1 - self.smoothed.1 + ry).round() as i32;
+            if (dx, dy) != (0, 0) {
+                output.push_back(TimedEvent::new(time, MouseEvent::RelativeMove(dx, dy)));
+            }
+        }
+        *self = Self::default();
+    }
+}
+
+/// Exponential moving average of one coordinate
+#[derive(Debug, Clone, Copy, Default)]
+struct LowPass {
+    value: Option<f64>,
+}
+
+impl LowPass {
+    fn filter(&mut self, value: f64, alpha: f64) -> f64 {
+        let value = match self.value {
+            Some(previous) => previous + alpha * (value - previous),
+            None => value,
+        };
+        self.value = Some(value);
+        value
+    }
+}
+
+impl Smoother for LowPass {
+    type Params = f64;
+
+    fn smooth(&mut self, value: f64, _dt: f64, alpha: &f64) -> f64 {
+        self.filter(value, *alpha)
+    }
+}
+
+/// Exponential smoothing of pointer motion
+///
+/// Each position moves `alpha` of the way from the previous smoothed position
+/// to the new one: 1 lets motion through unchanged, values near 0 smooth
+/// heavily and lag behind.
+#[derive(Debug, Clone)]
+pub struct ExponentialSmoothing {
+    alpha: f64,
+    state: Smoothing<LowPass>,
+}
+
+impl ExponentialSmoothing {
+    /// Create a filter with a smoothing factor between 0 and 1
+    ///
+    /// Finite factors outside that range are clamped to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alpha` is NaN or infinite; use `try_new` for factors read
+    /// from elsewhere.
+    pub fn new(alpha: f64) -> Self {
+        Self::try_new(alpha).expect("Invalid smoothing factor")
+    }
+
+    /// Create a filter with a smoothing factor, if it is finite
+    pub fn try_new(alpha: f64) -> Option<Self> {
+        // NaN 会穿过 clamp 并让所有坐标变为 0
+        if !alpha.is_finite() {
+            return None;
+        }
+        Some(Self {
+            alpha: alpha.clamp(f64::EPSILON, 1.0),
+            state: Smoothing::default(),
+        })
+    }
+
+    /// Get the smoothing factor
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+}
+
+impl EventFilter for ExponentialSmoothing {
+    fn push(&mut self, event: TimedEvent, output: &mut VecDeque<TimedEvent>) {
+        self.state.push(event, &self.alpha, output);
+    }
+
+    fn flush(&mut self, output: &mut VecDeque<TimedEvent>) {
+        self.state.flush(output);
+    }
+}
+
+/// Parameters of the One Euro filter
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct OneEuroParams {
+    min_cutoff: f64,
+    beta: f64,
+    derivative_cutoff: f64,
+}
+
+/// One Euro filter state of one coordinate
+#[derive(Debug, Clone, Copy, Default)]
+struct OneEuro {
+    value: LowPass,
+    derivative: LowPass,
+    raw: Option<f64>,
+}
+
+/// Smoothing factor of a low-pass filter at `cutoff` Hz for a `dt` second step
+fn alpha(cutoff: f64, dt: f64) -> f64 {
+    let tau = 1.0 / (2.0 * PI * cutoff);
+    1.0 / (1.0 + tau / dt)
+}
+
+impl Smoother for OneEuro {
+    type Params = OneEuroParams;
+
+    fn smooth(&mut self, value: f64, dt: f64, params: &OneEuroParams) -> f64 {
+        let Some(raw) = self.raw.replace(value) else {
+            self.derivative.filter(0.0, 1.0);
+            return self.value.filter(value, 1.0);
+        };
+        if dt <= 0.0 {
+            // 同一时刻的样本无法估计速度，直接采用
+            self.value.value = Some(value);
+            return value;
+        }
+        let speed = self
+            .derivative
+            .filter((value - raw) / dt, alpha(params.derivative_cutoff, dt));
+        let cutoff = params.min_cutoff + params.beta * speed.abs();
+        self.value.filter(value, alpha(cutoff, dt))
+    }
+}
+
+/// One Euro filter (Casiez et al.) for pointer motion
+///
+/// A low-pass filter whose cutoff frequency rises with speed: slow motion is
+/// smoothed strongly to remove jitter, fast motion hardly at all to avoid
+/// lag. `min_cutoff` is the cutoff at rest in Hz, `beta` how fast it rises
+/// with speed in pixels (or counts) per second.
+#[derive(Debug, Clone)]
+pub struct OneEuroFilter {
+    params: OneEuroParams,
+    state: Smoothing<OneEuro>,
+}
+
+impl OneEuroFilter {
+    /// Create a filter with the cutoff at rest and the speed coefficient
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `min_cutoff` is finite and positive and `beta` finite
+    /// and not negative; use `try_new` for parameters read from elsewhere.
+    pub fn new(min_cutoff: f64, beta: f64) -> Self {
+        Self::try_new(min_cutoff, beta).expect("Invalid One Euro filter parameters")
+    }
+
+    /// Create a filter, if `min_cutoff` is finite and positive and `beta`
+    /// finite and not negative
+    pub fn try_new(min_cutoff: f64, beta: f64) -> Option<Self> {
+        if !(is_cutoff(min_cutoff) && beta.is_finite() && beta >= 0.0) {
+            return None;
+        }
+        Some(Self {
+            params: OneEuroParams {
+                min_cutoff,
+                beta,
+                derivative_cutoff: 1.0,
+            },
+            state: Smoothing::default(),
+        })
+    }
+
+    /// Set the cutoff of the speed estimate, in Hz
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `cutoff` is finite and positive; use
+    /// `try_with_derivative_cutoff` for cutoffs read from elsewhere.
+    pub fn with_derivative_cutoff(self, cutoff: f64) -> Self {
+        self.try_with_derivative_cutoff(cutoff)
+            .expect("Invalid derivative cutoff")
+    }
+
+    /// Set the cutoff of the speed estimate, if it is finite and positive
+    pub fn try_with_derivative_cutoff(mut self, cutoff: f64) -> Option<Self> {
+        if !is_cutoff(cutoff) {
+            return None;
+        }
+        self.params.derivative_cutoff = cutoff;
+        Some(self)
+    }
+}
+
+/// Check that a cutoff frequency is usable
+fn is_cutoff(cutoff: f64) -> bool {
+    cutoff.is_finite() && cutoff > 0.0
+}
+
+impl Default for OneEuroFilter {
+    fn default() -> Self {
+        Self::new(1.0, 0.007)
+    }
+}
+
+impl EventFilter for OneEuroFilter {
+    fn push(&mut self, event: TimedEvent, output: &mut VecDeque<TimedEvent>) {
+        self.state.push(event, &self.params, output);
+    }
+
+    fn flush(&mut self, output: &mut VecDeque<TimedEvent>) {
+        self.state.flush(output);
+    }
+}
+
+/// Button debouncing
+///
+/// A release followed by a press of the same button within the window is
+/// contact bounce of a worn switch, not a second click: both are dropped.
+/// A release is held back until the window has passed, and so are the
+/// events after it to keep the stream in order; `flush` lets them through at
+/// the end of the stream.
+///
+/// Only a later event or a call to `expire` notices that the window has
+/// passed. `stream` suits recorded streams, which end; a live pipeline has to
+/// call `expire` from a timer once `deadline` has passed, or a click followed
+/// by no further input leaves the button held downstream.
+#[derive(Debug, Clone)]
+pub struct Debounce {
+    window: Duration,
+    // 等待确认的释放事件（true）及其后被推迟的事件
+    pending: VecDeque<(TimedEvent, bool)>,
+}
+
+impl Debounce {
+    /// Create a filter ignoring release and press pairs within `window`
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Get the debounce window
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Get the time after which `expire` lets the oldest held release through
+    ///
+    /// Returns `None` when no release is held back.
+    pub fn deadline(&self) -> Option<Duration> {
+        self.pending
+            .iter()
+            .find(|(_, tentative)| *tentative)
+            .map(|(event, _)| event.time.saturating_add(self.window))
+    }
+
+    /// Let through the events no longer waiting on a release at `now`
+    pub fn expire(&mut self, now: Duration, output: &mut VecDeque<TimedEvent>) {
+        for (event, tentative) in &mut self.pending {
+            if *tentative && now.saturating_sub(event.time) > self.window {
+                *tentative = false;
+            }
+        }
+        while let Some((event, false)) = self.pending.front().copied() {
+            output.push_back(event);
+            self.pending.pop_front();
+        }
+    }
+}
+
+impl EventFilter for Debounce {
+    fn push(&mut self, event: TimedEvent, output: &mut VecDeque<TimedEvent>) {
+        self.expire(event.time, output);
+        match event.event {
+            MouseEvent::Press(button) => {
+                let bounce = self.pending.iter().position(|(pending, tentative)| {
+                    *tentative && pending.event == MouseEvent::Release(button)
+                });
+                if let Some(index) = bounce {
+                    self.pending.remove(index);
+                    self.expire(event.time, output);
+                    return;
+                }
+            }
+            MouseEvent::Release(_) => {
+                self.pending.push_back((event, true));
+                return;
+            }
+            _ => {}
+        }
+        if self.pending.is_empty() {
+            output.push_back(event);
+        } else {
+            self.pending.push_back((event, false));
+        }
+    }
+
+    fn flush(&mut self, output: &mut VecDeque<TimedEvent>) {
+        output.extend(self.pending.drain(..).map(|(event, _)| event));
+    }
+}
+
+/// Jitter suppression
+///
+/// Motion that stays within `radius` pixels (or counts) of the last position
+/// let through is dropped. `RelativeMove` deltas are summed until they leave
+/// the radius and then emitted together, so slow deliberate motion still
+/// arrives while back-and-forth noise cancels out.
+#[derive(Debug, Clone)]
+pub struct JitterFilter {
+    radius: f64,
+    anchor: Option<(i32, i32)>,
+    pending: (i32, i32),
+}
+
+impl JitterFilter {
+    /// Create a filter ignoring motion within `radius`
+    pub fn new(radius: f64) -> Self {
+        Self {
+            radius,
+            anchor: None,
+            pending: (0, 0),
+        }
+    }
+
+    /// Get the radius
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    fn within(&self, dx: i32, dy: i32) -> bool {
+        f64::from(dx).hypot(f64::from(dy)) < self.radius
+    }
+}
+
+impl EventFilter for JitterFilter {
+    fn push(&mut self, event: TimedEvent, output: &mut VecDeque<TimedEvent>) {
+        match event.event {
+            MouseEvent::Move(x, y) => {
+                if let Some((ax, ay)) = self.anchor {
+                    if self.within(x.saturating_sub(ax), y.saturating_sub(ay)) {
+                        return;
+                    }
+                }
+                self.anchor = Some((x, y));
+            }
+            MouseEvent::RelativeMove(dx, dy) => {
+                let (x, y) = (
+                    self.pending.0.saturating_add(dx),
+                    self.pending.1.saturating_add(dy),
+                );
+                if self.within(x, y) {
+                    self.pending = (x, y);
+                    return;
+                }
+                self.pending = (0, 0);
+                let moved = TimedEvent::new(event.time, MouseEvent::RelativeMove(x, y));
+                output.push_back(moved);
+                return;
+            }
+            _ => {}
+        }
+        output.push_back(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Button;
+
+    fn at(ms: u64, event: MouseEvent) -> TimedEvent {
+        TimedEvent::new(Duration::from_millis(ms), event)
+    }
+
+    /// Slow rightward motion with ±2 counts of vertical jitter, 125 Hz
+    fn jittery() -> Vec<TimedEvent> {
+        (0..40)
+            .map(|i| {
+                let dy = if i % 2 == 0 { 2 } else { -2 };
+                at(i * 8, MouseEvent::RelativeMove(1, dy))
+            })
+            .collect()
+    }
+
+    fn displacement(events: &[TimedEvent]) -> ((i32, i32), i32) {
+        let mut total = (0, 0);
+        let mut path = 0;
+        for event in events {
+            if let MouseEvent::RelativeMove(dx, dy) = event.event {
+                total = (total.0 + dx, total.1 + dy);
+                path += dy.abs();
+            }
+        }
+        (total, path)
+    }
+
+    #[test]
+    fn exponential_smoothing_preserves_displacement() {
+        let input = jittery();
+        let output: Vec<TimedEvent> = ExponentialSmoothing::new(0.25)
+            .stream(input.clone())
+            .collect();
+        let (total, path) = displacement(&output);
+        assert_eq!(total, displacement(&input).0);
+        assert!(path < displacement(&input).1 / 2, "{}", path);
+
+        // alpha 为 1 时原样通过
+        let output: Vec<TimedEvent> = ExponentialSmoothing::new(1.0)
+            .stream(input.clone())
+            .collect();
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn exponential_smoothing_lags_and_flushes() {
+        let mut filter = ExponentialSmoothing::new(0.5);
+        let mut output = VecDeque::new();
+        // 第一个样本直接采用，之后每次走剩余距离的一半
+        for (ms, dx) in [(0, 4), (8, 8), (16, 0), (24, 0)] {
+            filter.push(at(ms, MouseEvent::RelativeMove(dx, 0)), &mut output);
+        }
+        filter.flush(&mut output);
+        assert_eq!(
+            Vec::from(output),
+            [
+                at(0, MouseEvent::RelativeMove(4, 0)),
+                at(8, MouseEvent::RelativeMove(4, 0)),
+                at(16, MouseEvent::RelativeMove(2, 0)),
+                at(24, MouseEvent::RelativeMove(1, 0)),
+                at(24, MouseEvent::RelativeMove(1, 0)),
+            ]
+        );
+
+        // 绝对移动在结束时补上最后的原始位置
+        let mut output = VecDeque::new();
+        filter.push(at(0, MouseEvent::Move(0, 0)), &mut output);
+        filter.push(at(8, MouseEvent::Move(10, 0)), &mut output);
+        filter.flush(&mut output);
+        assert_eq!(
+            Vec::from(output),
+            [
+                at(0, MouseEvent::Move(0, 0)),
+                at(8, MouseEvent::Move(5, 0)),
+                at(8, MouseEvent::Move(10, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn one_euro_smooths_jitter_and_preserves_displacement() {
+        let input = jittery();
+        let output: Vec<TimedEvent> = OneEuroFilter::new(1.0, 0.0).stream(input.clone()).collect();
+        let (total, path) = displacement(&output);
+        assert_eq!(total, displacement(&input).0);
+        assert!(path < displacement(&input).1 / 2, "{}", path);
+
+        // 快速移动时截止频率升高，几乎没有滞后
+        let fast: Vec<TimedEvent> = (0..10)
+            .map(|i| at(i * 8, MouseEvent::RelativeMove(400, 0)))
+            .collect();
+        let output: Vec<TimedEvent> = OneEuroFilter::new(1.0, 1.0).stream(fast).collect();
+        assert_eq!(displacement(&output).0, (4000, 0));
+        assert!(matches!(output[9].event, MouseEvent::RelativeMove(dx, 0) if dx > 390));
+    }
+
+    #[test]
+    fn debounce_swallows_bounce_within_window() {
+        let events = [
+            at(0, MouseEvent::Press(Button::Left)),
+            at(100, MouseEvent::Release(Button::Left)),
+            at(102, MouseEvent::RelativeMove(1, 0)),
+            at(105, MouseEvent::Press(Button::Left)),
+            at(300, MouseEvent::Release(Button::Left)),
+            at(320, MouseEvent::Press(Button::Left)),
+        ];
+        let output: Vec<TimedEvent> = Debounce::new(Duration::from_millis(10))
+            .stream(events)
+            .collect();
+        assert_eq!(
+            output,
+            [
+                at(0, MouseEvent::Press(Button::Left)),
+                at(102, MouseEvent::RelativeMove(1, 0)),
+                at(300, MouseEvent::Release(Button::Left)),
+                at(320, MouseEvent::Press(Button::Left)),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_smoothing_parameters_are_rejected() {
+        assert!(ExponentialSmoothing::try_new(f64::NAN).is_none());
+        assert!(ExponentialSmoothing::try_new(f64::INFINITY).is_none());
+        assert_eq!(ExponentialSmoothing::new(7.0).alpha(), 1.0);
+        assert_eq!(ExponentialSmoothing::new(-1.0).alpha(), f64::EPSILON);
+
+        assert!(OneEuroFilter::try_new(0.0, 0.0).is_none());
+        assert!(OneEuroFilter::try_new(f64::NAN, 0.0).is_none());
+        assert!(OneEuroFilter::try_new(1.0, -0.5).is_none());
+        assert!(OneEuroFilter::try_new(1.0, f64::INFINITY).is_none());
+        assert!(OneEuroFilter::default()
+            .try_with_derivative_cutoff(f64::NAN)
+            .is_none());
+        assert!(OneEuroFilter::try_new(1.0, 0.0).is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid smoothing factor")]
+    fn exponential_smoothing_panics_on_nan() {
+        ExponentialSmoothing::new(f64::NAN);
+    }
+
+    #[test]
+    fn debounce_reports_when_to_expire() {
+        let mut filter = Debounce::new(Duration::from_millis(10));
+        let mut output = VecDeque::new();
+        assert_eq!(filter.deadline(), None);
+        filter.push(at(0, MouseEvent::Press(Button::Left)), &mut output);
+        filter.push(at(5, MouseEvent::Release(Button::Left)), &mut output);
+        assert_eq!(filter.deadline(), Some(Duration::from_millis(15)));
+
+        // 没有后续输入时，由调用者在截止时间后调用 expire 释放按钮
+        filter.expire(filter.deadline().unwrap(), &mut output);
+        assert_eq!(output.len(), 1);
+        filter.expire(Duration::from_millis(16), &mut output);
+        assert_eq!(
+            output.back(),
+            Some(&at(5, MouseEvent::Release(Button::Left)))
+        );
+        assert_eq!(filter.deadline(), None);
+    }
+
+    #[test]
+    fn debounce_lets_lone_release_through_on_expire() {
+        let mut filter = Debounce::new(Duration::from_millis(10));
+        let mut output = VecDeque::new();
+        filter.push(at(0, MouseEvent::Press(Button::Right)), &mut output);
+        filter.push(at(50, MouseEvent::Release(Button::Right)), &mut output);
+        filter.push(at(55, MouseEvent::RelativeMove(0, 1)), &mut output);
+        assert_eq!(output.len(), 1);
+
+        // 窗口未过时仍被推迟，过后按顺序放行
+        filter.expire(Duration::from_millis(60), &mut output);
+        assert_eq!(output.len(), 1);
+        filter.expire(Duration::from_millis(61), &mut output);
+        assert_eq!(
+            Vec::from(output),
+            [
+                at(0, MouseEvent::Press(Button::Right)),
+                at(50, MouseEvent::Release(Button::Right)),
+                at(55, MouseEvent::RelativeMove(0, 1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn jitter_filter_suppresses_small_motion() {
+        let events = [
+            at(0, MouseEvent::RelativeMove(1, 0)),
+            at(8, MouseEvent::RelativeMove(-1, 1)),
+            at(16, MouseEvent::RelativeMove(1, -1)),
+            at(24, MouseEvent::RelativeMove(2, 1)),
+            at(32, MouseEvent::Move(100, 100)),
+            at(40, MouseEvent::Move(101, 102)),
+            at(48, MouseEvent::Move(104, 100)),
+            at(56, MouseEvent::Press(Button::Left)),
+        ];
+        let output: Vec<TimedEvent> = JitterFilter::new(3.0).stream(events).collect();
+        assert_eq!(
+            output,
+            [
+                at(24, MouseEvent::RelativeMove(3, 1)),
+                at(32, MouseEvent::Move(100, 100)),
+                at(48, MouseEvent::Move(104, 100)),
+                at(56, MouseEvent::Press(Button::Left)),
+            ]
+        );
+    }
+}
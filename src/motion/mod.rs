@@ -0,0 +1,41 @@
+//! Processing of pointer motion
+//!
+//! These modules turn raw device motion into cursor motion. They work on
+//! `MouseEvent` streams and pass through the events they do not process, so
+//! they can sit anywhere in an event pipeline.
+
+/// Pointer acceleration profiles of libinput, Windows and macOS
+pub mod acceleration;
+/// Monitors, virtual desktop coordinates and absolute/relative conversion
+pub mod desktop;
+/// Smoothing, debouncing and jitter filters over timed event streams
+pub mod filter;
+/// Sensitivity conversion across games and mouse resolutions
+pub mod sensitivity;
+
+/// Fractions of a count carried between scaled motions
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) struct Remainder {
+    x: f64,
+    y: f64,
+}
+
+impl Remainder {
+    /// Get the carried fractions
+    pub(crate) fn get(&self) -> (f64, f64) {
+        (self.x, self.y)
+    }
+
+    /// Add a scaled delta and take its whole counts, keeping the fractions
+    ///
+    /// A NaN or infinite delta is dropped so that it cannot poison later ones.
+    pub(crate) fn carry(&mut self, x: f64, y: f64) -> (i32, i32) {
+        let finite = |value: f64| if value.is_finite() { value } else { 0.0 };
+        let (x, y) = (finite(x) + self.x, finite(y) + self.y);
+        // 向零截断，小数部分留给下一次移动
+        let (whole_x, whole_y) = (x.trunc(), y.trunc());
+        self.x = x - whole_x;
+        self.y = y - whole_y;
+        (whole_x as i32, whole_y as i32)
+    }
+}
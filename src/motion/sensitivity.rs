@@ -0,0 +1,318 @@
+//! Mouse sensitivity conversion
+//!
+//! A game turns the view by its yaw, in degrees per count, times the
+//! in-game sensitivity for every count the mouse reports. Together with the
+//! mouse resolution this gives the physical distance of a full turn (cm/360),
+//! which is what stays the same when a player moves between games or mice.
+//!
+//! `DpiScaler` rescales recorded `RelativeMove` deltas from one resolution to
+//! another, carrying fractions of a count over like `Accelerator` does.
+
+use core::fmt;
+
+use super::Remainder;
+use crate::types::MouseEvent;
+
+/// Centimetres per inch
+pub const CM_PER_INCH: f64 = 2.54;
+
+/// Games whose yaw is known
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Game {
+    /// Source and Source 2 games (Counter-Strike 2, Team Fortress 2, Half-Life)
+    Source,
+    /// Quake engine games (Quake Live, Quake Champions)
+    Quake,
+    /// Apex Legends
+    Apex,
+    /// Overwatch 2
+    Overwatch,
+    /// Valorant
+    Valorant,
+    /// Call of Duty (Modern Warfare 2019 and later, legacy sensitivity)
+    CallOfDuty,
+}
+
+impl Game {
+    /// All games with a known yaw
+    pub const ALL: [Game; 6] = [
+        Game::Source,
+        Game::Quake,
+        Game::Apex,
+        Game::Overwatch,
+        Game::Valorant,
+        Game::CallOfDuty,
+    ];
+
+    /// Get the display name
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Game::Source => "Source",
+            Game::Quake => "Quake",
+            Game::Apex => "Apex Legends",
+            Game::Overwatch => "Overwatch",
+            Game::Valorant => "Valorant",
+            Game::CallOfDuty => "Call of Duty",
+        }
+    }
+
+    /// Get the yaw at sensitivity 1, in degrees per count
+    pub const fn yaw(&self) -> f64 {
+        match self {
+            Game::Source | Game::Quake | Game::Apex => 0.022,
+            Game::Overwatch | Game::CallOfDuty => 0.0066,
+            Game::Valorant => 0.07,
+        }
+    }
+
+    /// Get the distance of a full turn, in centimetres
+    pub fn cm_per_360(&self, sensitivity: f64, dpi: f64) -> f64 {
+        cm_per_360(self.yaw(), sensitivity, dpi)
+    }
+
+    /// Get the sensitivity that turns a full circle in `cm` centimetres
+    pub fn sensitivity_for(&self, cm: f64, dpi: f64) -> f64 {
+        sensitivity_for(self.yaw(), cm, dpi)
+    }
+
+    /// Convert a sensitivity of this game into `other`'s at the same resolution
+    pub fn convert(&self, sensitivity: f64, other: Game) -> f64 {
+        sensitivity * self.yaw() / other.yaw()
+    }
+}
+
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Get the effective DPI: resolution times sensitivity
+pub fn edpi(sensitivity: f64, dpi: f64) -> f64 {
+    sensitivity * dpi
+}
+
+/// Get the distance of a full turn, in centimetres, for a yaw in degrees per count
+pub fn cm_per_360(yaw: f64, sensitivity: f64, dpi: f64) -> f64 {
+    let counts = 360.0 / (yaw * sensitivity);
+    counts / dpi * CM_PER_INCH
+}
+
+/// Get the sensitivity that turns a full circle in `cm` centimetres
+pub fn sensitivity_for(yaw: f64, cm: f64, dpi: f64) -> f64 {
+    let counts = cm / CM_PER_INCH * dpi;
+    360.0 / (yaw * counts)
+}
+
+/// Get the sensitivity that keeps the same cm/360 after changing resolution
+pub fn rescale_sensitivity(sensitivity: f64, from_dpi: f64, to_dpi: f64) -> f64 {
+    sensitivity * from_dpi / to_dpi
+}
+
+/// Rescales relative motion recorded at one resolution to another
+#[derive(Debug, Clone)]
+pub struct DpiScaler {
+    ratio: f64,
+    remainder: Remainder,
+}
+
+impl DpiScaler {
+    /// Create a scaler from a recording resolution to a target resolution
+    ///
+    /// # Panics
+    ///
+    /// Panics unless both resolutions are finite and positive; use `try_new`
+    /// for resolutions read from elsewhere.
+    pub fn new(from_dpi: f64, to_dpi: f64) -> Self {
+        Self::try_new(from_dpi, to_dpi).expect("Invalid DPI")
+    }
+
+    /// Create a scaler between two resolutions, if both are finite and positive
+    pub fn try_new(from_dpi: f64, to_dpi: f64) -> Option<Self> {
+        if !(is_positive(from_dpi) && is_positive(to_dpi)) {
+            return None;
+        }
+        Self::try_with_ratio(to_dpi / from_dpi)
+    }
+
+    /// Create a scaler multiplying deltas by `ratio`
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `ratio` is finite and positive; use `try_with_ratio` for
+    /// ratios read from elsewhere.
+    pub fn with_ratio(ratio: f64) -> Self {
+        Self::try_with_ratio(ratio).expect("Invalid DPI ratio")
+    }
+
+    /// Create a scaler multiplying deltas by `ratio`, if it is finite and positive
+    pub fn try_with_ratio(ratio: f64) -> Option<Self> {
+        // 非有限的比例会让 Remainder 丢弃所有移动
+        if !is_positive(ratio) {
+            return None;
+        }
+        Some(Self {
+            ratio,
+            remainder: Remainder::default(),
+        })
+    }
+
+    /// Get the factor applied to deltas
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    /// Get the fraction of a count carried into the next motion
+    pub fn remainder(&self) -> (f64, f64) {
+        self.remainder.get()
+    }
+
+    /// Forget the carried fractions
+    pub fn reset(&mut self) {
+        self.remainder = Remainder::default();
+    }
+
+    /// Rescale a delta
+    pub fn scale(&mut self, dx: i32, dy: i32) -> (i32, i32) {
+        self.remainder
+            .carry(f64::from(dx) * self.ratio, f64::from(dy) * self.ratio)
+    }
+
+    /// Rescale an event
+    ///
+    /// Returns `None` when a motion is carried over entirely.
+    pub fn apply(&mut self, event: &MouseEvent) -> Option<MouseEvent> {
+        match *event {
+            MouseEvent::RelativeMove(dx, dy) => match self.scale(dx, dy) {
+                (0, 0) => None,
+                (dx, dy) => Some(MouseEvent::RelativeMove(dx, dy)),
+            },
+            event => Some(event),
+        }
+    }
+
+    /// Rescale a stream of events
+    pub fn stream<I>(self, events: I) -> Rescaled<I::IntoIter>
+    where
+        I: IntoIterator<Item = MouseEvent>,
+    {
+        Rescaled {
+            events: events.into_iter(),
+            scaler: self,
+        }
+    }
+}
+
+fn is_positive(value: f64) -> bool {
+    value.is_finite() && value > 0.0
+}
+
+/// Iterator returned by `DpiScaler::stream`
+#[derive(Debug, Clone)]
+pub struct Rescaled<I> {
+    events: I,
+    scaler: DpiScaler,
+}
+
+impl<I> Rescaled<I> {
+    /// Get the scaler
+    pub fn scaler(&self) -> &DpiScaler {
+        &self.scaler
+    }
+}
+
+impl<I: Iterator<Item = MouseEvent>> Iterator for Rescaled<I> {
+    type Item = MouseEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.events
+            .by_ref()
+            .find_map(|event| self.scaler.apply(&event))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn yaw_table_gives_known_turn_distances() {
+        // 800 DPI、灵敏度 1 时 Source 引擎转一圈约 51.95 cm
+        assert!(close(
+            Game::Source.cm_per_360(1.0, 800.0),
+            51.954_545_454_545_45
+        ));
+        assert!(close(Game::Valorant.yaw(), 0.07));
+        assert!(close(Game::Overwatch.yaw(), Game::CallOfDuty.yaw()));
+        assert!(close(
+            Game::Source.convert(1.0, Game::Valorant),
+            0.022 / 0.07
+        ));
+        assert!(close(
+            Game::Source.convert(Game::Valorant.convert(2.5, Game::Source), Game::Valorant),
+            2.5
+        ));
+        for game in Game::ALL {
+            let sensitivity = game.sensitivity_for(30.0, 1600.0);
+            assert!(
+                close(game.cm_per_360(sensitivity, 1600.0), 30.0),
+                "{}",
+                game
+            );
+        }
+        assert!(close(edpi(2.0, 800.0), 1600.0));
+        assert!(close(rescale_sensitivity(1.0, 800.0, 1600.0), 0.5));
+    }
+
+    #[test]
+    fn scaler_carries_fractions() {
+        let mut scaler = DpiScaler::new(1600.0, 400.0);
+        assert!(close(scaler.ratio(), 0.25));
+        let moves: Vec<_> = (0..4).map(|_| scaler.scale(1, -3)).collect();
+        // 小数部分向零截断并累积到下一次，总量保持为 (1, -3)
+        assert_eq!(moves, [(0, 0), (0, -1), (0, -1), (1, -1)]);
+        assert_eq!(scaler.remainder(), (0.0, 0.0));
+
+        // 完全被累积的移动不产生事件，其他事件原样通过
+        let events = [
+            MouseEvent::RelativeMove(2, 0),
+            MouseEvent::Press(crate::types::Button::Left),
+            MouseEvent::RelativeMove(2, 0),
+        ];
+        let rescaled: Vec<_> = DpiScaler::new(1600.0, 400.0).stream(events).collect();
+        assert_eq!(
+            rescaled,
+            [
+                MouseEvent::Press(crate::types::Button::Left),
+                MouseEvent::RelativeMove(1, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_resolutions_are_rejected() {
+        for (from, to) in [
+            (0.0, 800.0),
+            (800.0, 0.0),
+            (f64::NAN, 800.0),
+            (-800.0, -400.0),
+        ] {
+            assert!(DpiScaler::try_new(from, to).is_none(), "{} {}", from, to);
+        }
+        assert!(DpiScaler::try_new(f64::MIN_POSITIVE, f64::MAX).is_none());
+        assert!(DpiScaler::try_with_ratio(f64::INFINITY).is_none());
+        assert!(DpiScaler::try_with_ratio(0.0).is_none());
+        assert!(DpiScaler::try_with_ratio(2.0).is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid DPI")]
+    fn new_panics_on_zero_dpi() {
+        DpiScaler::new(0.0, 800.0);
+    }
+}
@@ -0,0 +1,169 @@
+//! Allocation-free parsing functions
+//!
+//! These mirror the parsers in the parent module but return `ParseError`,
+//! which borrows the offending input, so they work without the `alloc`
+//! feature.
+
+use crate::{
+    error::ParseError,
+    mapping::CodeSpace,
+    types::{Button, ButtonRole, Handedness, MouseEvent, Platform, ScrollDirection},
+};
+
+/// Parse a button from a string with exact matching
+pub fn parse_button_from_str(s: &str) -> Result<Button, ParseError<'_>> {
+    Button::iter()
+        .find(|btn| btn.as_str() == s)
+        .ok_or(ParseError::UnknownButton(s))
+}
+
+/// Parse a button from a string with case-insensitive matching
+pub fn parse_button_ignore_case(s: &str) -> Result<Button, ParseError<'_>> {
+    Button::iter()
+        .find(|btn| btn.as_str().eq_ignore_ascii_case(s))
+        .ok_or(ParseError::UnknownButton(s))
+}
+
+/// Parse a button with support for common aliases
+pub fn parse_button_with_aliases(s: &str) -> Result<Button, ParseError<'_>> {
+    Button::iter()
+        .find(|btn| {
+            btn.as_str().eq_ignore_ascii_case(s)
+                || btn
+                    .aliases()
+                    .iter()
+                    .any(|alias| alias.eq_ignore_ascii_case(s))
+        })
+        .ok_or(ParseError::UnknownButton(s))
+}
+
+/// Parse a button role from its name or an alias, ignoring case
+pub fn parse_button_role(s: &str) -> Result<ButtonRole, ParseError<'_>> {
+    ButtonRole::ALL
+        .into_iter()
+        .find(|role| {
+            role.as_str().eq_ignore_ascii_case(s)
+                || role
+                    .aliases()
+                    .iter()
+                    .any(|alias| alias.eq_ignore_ascii_case(s))
+        })
+        .ok_or(ParseError::UnknownButton(s))
+}
+
+/// Parse a button, resolving role names against a handedness
+///
+/// Role names and their aliases, as `parse_button_role` accepts them,
+/// resolve to the button playing that role; anything else is parsed like
+/// `parse_button_with_aliases`.
+pub fn parse_button_for(s: &str, handedness: Handedness) -> Result<Button, ParseError<'_>> {
+    match parse_button_role(s) {
+        Ok(role) => Ok(role.button(handedness)),
+        Err(_) => parse_button_with_aliases(s),
+    }
+}
+
+/// Parse a scroll direction from a string
+pub fn parse_scroll_direction(s: &str) -> Result<ScrollDirection, ParseError<'_>> {
+    const DIRECTIONS: [(&str, &str, ScrollDirection); 4] = [
+        ("verticalup", "up", ScrollDirection::VerticalUp),
+        ("verticaldown", "down", ScrollDirection::VerticalDown),
+        ("horizontalleft", "left", ScrollDirection::HorizontalLeft),
+        ("horizontalright", "right", ScrollDirection::HorizontalRight),
+    ];
+
+    DIRECTIONS
+        .iter()
+        .find(|(name, alias, _)| name.eq_ignore_ascii_case(s) || alias.eq_ignore_ascii_case(s))
+        .map(|(_, _, dir)| *dir)
+        .ok_or(ParseError::UnknownButton(s))
+}
+
+/// Parse a platform from its name
+pub fn parse_platform(s: &str) -> Result<Platform, ParseError<'_>> {
+    const PLATFORMS: [(&str, Platform); 4] = [
+        ("windows", Platform::Windows),
+        ("linux", Platform::Linux),
+        ("macos", Platform::MacOS),
+        ("osx", Platform::MacOS),
+    ];
+
+    PLATFORMS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(s))
+        .map(|(_, platform)| *platform)
+        .ok_or(ParseError::UnknownPlatform)
+}
+
+/// Parse a code space from its name
+///
+/// Accepts every platform name as well as the name and aliases of every
+/// code space, such as `x11`, `evdev`, `sdl2`, `glfw` and `winit`.
+pub fn parse_code_space(s: &str) -> Result<CodeSpace, ParseError<'_>> {
+    if let Ok(platform) = parse_platform(s) {
+        return Ok(platform.code_space());
+    }
+
+    CodeSpace::ALL
+        .into_iter()
+        .find(|space| {
+            space.as_str().eq_ignore_ascii_case(s)
+                || space
+                    .aliases()
+                    .iter()
+                    .any(|alias| alias.eq_ignore_ascii_case(s))
+        })
+        .ok_or(ParseError::UnknownPlatform)
+}
+
+/// Parse a mouse event from a string representation
+///
+/// Malformed events are reported as `InvalidEvent` with the whole input, and
+/// unknown buttons or scroll directions as `UnknownButton` with just that
+/// parameter.
+pub fn parse_mouse_input(s: &str) -> Result<MouseEvent, ParseError<'_>> {
+    if s.is_empty() {
+        return Err(ParseError::EmptyInput);
+    }
+
+    let invalid = ParseError::InvalidEvent(s);
+    let (event_type, params) = s
+        .split_once('(')
+        .and_then(|(t, p)| p.strip_suffix(')').map(|p| (t, p)))
+        .ok_or(invalid)?;
+
+    match event_type {
+        "Press" => Ok(MouseEvent::Press(parse_button_with_aliases(params)?)),
+        "Release" => Ok(MouseEvent::Release(parse_button_with_aliases(params)?)),
+        "Scroll" => {
+            let (dir, amount) = split_pair(params).ok_or(invalid)?;
+            let dir = parse_scroll_direction(dir)?;
+            let amount = amount.parse().map_err(|_| invalid)?;
+            Ok(MouseEvent::Scroll(dir, amount))
+        }
+        "Move" => {
+            let (x, y) = parse_coordinates(params).ok_or(invalid)?;
+            Ok(MouseEvent::Move(x, y))
+        }
+        "RelativeMove" => {
+            let (dx, dy) = parse_coordinates(params).ok_or(invalid)?;
+            Ok(MouseEvent::RelativeMove(dx, dy))
+        }
+        _ => Err(invalid),
+    }
+}
+
+/// Split `a, b` into its two trimmed parts
+fn split_pair(params: &str) -> Option<(&str, &str)> {
+    let (first, second) = params.split_once(',')?;
+    if second.contains(',') {
+        return None;
+    }
+    Some((first.trim(), second.trim()))
+}
+
+/// Parse `x, y` into a pair of integers
+fn parse_coordinates(params: &str) -> Option<(i32, i32)> {
+    let (x, y) = split_pair(params)?;
+    Some((x.parse().ok()?, y.parse().ok()?))
+}
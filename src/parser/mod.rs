@@ -1,102 +1,96 @@
 //! Parsing utilities for mouse buttons and events
 
+/// Allocation-free parsers returning borrowed errors
+pub mod borrowed;
+
+#[cfg(feature = "alloc")]
 use crate::{
-    error::MouseParseError,
-    types::{Button, MouseEvent, ScrollDirection},
+    error::{MouseParseError, ParseError},
+    types::{Button, ButtonRole, Handedness, MouseEvent, ScrollDirection},
 };
 
 /// Parse a button with support for common aliases
+#[cfg(feature = "alloc")]
 pub fn parse_button_with_aliases(s: &str) -> Result<Button, MouseParseError> {
-    let s = s.to_lowercase();
-    match s.as_str() {
-        "left" | "lmb" => Ok(Button::Left),
-        "right" | "rmb" => Ok(Button::Right),
-        "middle" | "mmb" | "wheel" => Ok(Button::Middle),
-        "x1" | "back" => Ok(Button::X1),
-        "x2" | "forward" => Ok(Button::X2),
-        "extra3" => Ok(Button::Extra3),
-        "extra4" => Ok(Button::Extra4),
-        "extra5" => Ok(Button::Extra5),
-        "extra6" => Ok(Button::Extra6),
-        "extra7" => Ok(Button::Extra7),
-        "extra8" => Ok(Button::Extra8),
-        #[cfg(feature = "extended")]
-        "extra9" => Ok(Button::Extra9),
-        #[cfg(feature = "extended")]
-        "extra10" => Ok(Button::Extra10),
-        _ => Err(MouseParseError::UnknownButton(s)),
-    }
+    borrowed::parse_button_with_aliases(s)
+        .map_err(|_| MouseParseError::UnknownButton(s.to_lowercase()))
+}
+
+/// Parse a button role from its name or an alias, ignoring case
+#[cfg(feature = "alloc")]
+pub fn parse_button_role(s: &str) -> Result<ButtonRole, MouseParseError> {
+    borrowed::parse_button_role(s).map_err(|_| MouseParseError::UnknownButton(s.to_lowercase()))
+}
+
+/// Parse a button, resolving role names against a handedness
+#[cfg(feature = "alloc")]
+pub fn parse_button_for(s: &str, handedness: Handedness) -> Result<Button, MouseParseError> {
+    borrowed::parse_button_for(s, handedness)
+        .map_err(|_| MouseParseError::UnknownButton(s.to_lowercase()))
 }
 
 /// Parse a scroll direction from a string
+#[cfg(feature = "alloc")]
 pub fn parse_scroll_direction(s: &str) -> Result<ScrollDirection, MouseParseError> {
-    let s = s.to_lowercase();
-    match s.as_str() {
-        "verticalup" | "up" => Ok(ScrollDirection::VerticalUp),
-        "verticaldown" | "down" => Ok(ScrollDirection::VerticalDown),
-        "horizontalleft" | "left" => Ok(ScrollDirection::HorizontalLeft),
-        "horizontalright" | "right" => Ok(ScrollDirection::HorizontalRight),
-        _ => Err(MouseParseError::UnknownButton(s)),
-    }
+    borrowed::parse_scroll_direction(s)
+        .map_err(|_| MouseParseError::UnknownButton(s.to_lowercase()))
 }
 
 /// Parse a mouse event from a string representation
+#[cfg(feature = "alloc")]
 pub fn parse_mouse_input(s: &str) -> Result<MouseEvent, MouseParseError> {
-    if s.is_empty() {
-        return Err(MouseParseError::EmptyInput);
+    borrowed::parse_mouse_input(s).map_err(|err| match err {
+        // 按钮或滚动方向解析失败时，沿用小写形式的错误信息
+        ParseError::UnknownButton(part) => MouseParseError::UnknownButton(part.to_lowercase()),
+        err => err.into_owned(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseError;
+
+    #[test]
+    fn borrowed_errors_carry_the_failing_span() {
+        assert_eq!(
+            borrowed::parse_mouse_input("Press(Thumb)"),
+            Err(ParseError::UnknownButton("Thumb"))
+        );
+        assert_eq!(
+            borrowed::parse_mouse_input("Scroll(Sideways, 1)"),
+            Err(ParseError::UnknownButton("Sideways"))
+        );
+        assert_eq!(
+            borrowed::parse_mouse_input("Move(1)"),
+            Err(ParseError::InvalidEvent("Move(1)"))
+        );
+        // 参数与整个输入等长时也不会被误判
+        assert_eq!(
+            borrowed::parse_mouse_input("Thumb"),
+            Err(ParseError::InvalidEvent("Thumb"))
+        );
+        assert_eq!(borrowed::parse_mouse_input(""), Err(ParseError::EmptyInput));
     }
 
-    let (event_type, params) = s
-        .split_once('(')
-        .and_then(|(t, p)| p.strip_suffix(')').map(|p| (t, p)))
-        .ok_or_else(|| MouseParseError::UnknownButton(s.to_string()))?;
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn owned_errors_lowercase_only_the_failing_parameter() {
+        use alloc::string::ToString;
 
-    match event_type {
-        "Press" => {
-            let button = parse_button_with_aliases(params)?;
-            Ok(MouseEvent::Press(button))
-        }
-        "Release" => {
-            let button = parse_button_with_aliases(params)?;
-            Ok(MouseEvent::Release(button))
-        }
-        "Scroll" => {
-            let parts: Vec<&str> = params.split(',').map(|s| s.trim()).collect();
-            if parts.len() != 2 {
-                return Err(MouseParseError::UnknownButton(s.to_string()));
-            }
-            let dir = parse_scroll_direction(parts[0])?;
-            let amount = parts[1]
-                .parse()
-                .map_err(|_| MouseParseError::UnknownButton(s.to_string()))?;
-            Ok(MouseEvent::Scroll(dir, amount))
-        }
-        "Move" => {
-            let parts: Vec<&str> = params.split(',').map(|s| s.trim()).collect();
-            if parts.len() != 2 {
-                return Err(MouseParseError::UnknownButton(s.to_string()));
-            }
-            let x = parts[0]
-                .parse()
-                .map_err(|_| MouseParseError::UnknownButton(s.to_string()))?;
-            let y = parts[1]
-                .parse()
-                .map_err(|_| MouseParseError::UnknownButton(s.to_string()))?;
-            Ok(MouseEvent::Move(x, y))
-        }
-        "RelativeMove" => {
-            let parts: Vec<&str> = params.split(',').map(|s| s.trim()).collect();
-            if parts.len() != 2 {
-                return Err(MouseParseError::UnknownButton(s.to_string()));
-            }
-            let dx = parts[0]
-                .parse()
-                .map_err(|_| MouseParseError::UnknownButton(s.to_string()))?;
-            let dy = parts[1]
-                .parse()
-                .map_err(|_| MouseParseError::UnknownButton(s.to_string()))?;
-            Ok(MouseEvent::RelativeMove(dx, dy))
-        }
-        _ => Err(MouseParseError::UnknownButton(s.to_string())),
+        assert_eq!(
+            parse_mouse_input("Press(Thumb)"),
+            Err(MouseParseError::UnknownButton("thumb".to_string()))
+        );
+        assert_eq!(
+            parse_mouse_input("Scroll(Up, many)"),
+            Err(MouseParseError::UnknownButton(
+                "Scroll(Up, many)".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_mouse_input("Hover(Left)"),
+            Err(MouseParseError::UnknownButton("Hover(Left)".to_string()))
+        );
     }
 }
@@ -0,0 +1,478 @@
+//! Decoding of `input_event` streams into mouse events
+
+use std::collections::VecDeque;
+use std::io::{self, Read};
+use std::mem;
+use std::time::Duration;
+
+use super::{
+    InputEvent, TimeLayout, ABS_X, ABS_Y, EV_ABS, EV_KEY, EV_REL, REL_HWHEEL, REL_HWHEEL_HI_RES,
+    REL_WHEEL, REL_WHEEL_HI_RES, REL_X, REL_Y, WHEEL_HI_RES_UNITS,
+};
+use crate::mapping::CodeSpace;
+use crate::types::{MouseEvent, ScrollDirection, TimedEvent};
+
+/// Records of one hardware report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// Timestamp of the terminating `SYN_REPORT`
+    pub time: Duration,
+    /// Records of the report, without the `SYN_REPORT`
+    pub events: Vec<InputEvent>,
+    /// Records were lost before this report
+    pub dropped: bool,
+}
+
+impl Frame {
+    /// Decode the report into mouse events without state from earlier reports
+    ///
+    /// Absolute positions missing one axis and partial high-resolution wheel
+    /// motion need earlier reports; use a `FrameDecoder` for whole streams.
+    pub fn mouse_events(&self) -> Vec<MouseEvent> {
+        FrameDecoder::new().decode(self)
+    }
+}
+
+/// Stateful decoder turning reports into mouse events
+///
+/// Keeps the last absolute position, since a report only carries the axes
+/// that changed, and the partial high-resolution wheel motion. Once a device
+/// has sent a high-resolution wheel record, the legacy detent records of that
+/// axis are ignored, since the kernel emits both for the same motion.
+#[derive(Debug, Clone, Default)]
+pub struct FrameDecoder {
+    position: (i32, i32),
+    wheel: HiResAxis,
+    hwheel: HiResAxis,
+}
+
+/// Wheel axis that may report high-resolution motion
+#[derive(Debug, Clone, Copy, Default)]
+struct HiResAxis {
+    hi_res: bool,
+    remainder: i32,
+}
+
+impl HiResAxis {
+    /// Fold a report's legacy and high-resolution values into whole detents
+    fn detents(&mut self, legacy: i32, hi_res: Option<i32>) -> i32 {
+        match hi_res {
+            Some(units) => {
+                self.hi_res = true;
+                // 累积不足一格的高精度滚动，余数保留到后续报告
+                let total = self.remainder.saturating_add(units);
+                self.remainder = total % WHEEL_HI_RES_UNITS;
+                total / WHEEL_HI_RES_UNITS
+            }
+            None if self.hi_res => 0,
+            None => legacy,
+        }
+    }
+}
+
+impl FrameDecoder {
+    /// Create a decoder with no prior state
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the state carried between reports, e.g. when switching devices
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Decode one report into mouse events
+    ///
+    /// Button changes come first in record order, followed by the absolute
+    /// position, the summed motion and the wheel detents. Buttons outside the
+    /// standard evdev code space and key auto-repeat records are skipped.
+    pub fn decode(&mut self, frame: &Frame) -> Vec<MouseEvent> {
+        let mut events = Vec::new();
+        let (mut dx, mut dy, mut wheel, mut hwheel) = (0i32, 0i32, 0i32, 0i32);
+        let (mut wheel_hi_res, mut hwheel_hi_res) = (None::<i32>, None::<i32>);
+        let mut moved = false;
+
+        for record in &frame.events {
+            match (record.kind, record.code) {
+                (EV_KEY, code) => {
+                    let Some(button) = CodeSpace::Evdev.from_code(code.into()) else {
+                        continue;
+                    };
+                    match record.value {
+                        1 => events.push(MouseEvent::Press(button)),
+                        0 => events.push(MouseEvent::Release(button)),
+                        _ => {}
+                    }
+                }
+                (EV_REL, REL_X) => dx = dx.saturating_add(record.value),
+                (EV_REL, REL_Y) => dy = dy.saturating_add(record.value),
+                (EV_REL, REL_WHEEL) => wheel = wheel.saturating_add(record.value),
+                (EV_REL, REL_HWHEEL) => hwheel = hwheel.saturating_add(record.value),
+                (EV_REL, REL_WHEEL_HI_RES) => {
+                    wheel_hi_res = Some(wheel_hi_res.unwrap_or(0).saturating_add(record.value))
+                }
+                (EV_REL, REL_HWHEEL_HI_RES) => {
+                    hwheel_hi_res = Some(hwheel_hi_res.unwrap_or(0).saturating_add(record.value))
+                }
+                (EV_ABS, ABS_X) => {
+                    self.position.0 = record.value;
+                    moved = true;
+                }
+                (EV_ABS, ABS_Y) => {
+                    self.position.1 = record.value;
+                    moved = true;
+                }
+                _ => {}
+            }
+        }
+
+        if moved {
+            events.push(MouseEvent::Move(self.position.0, self.position.1));
+        }
+        if dx != 0 || dy != 0 {
+            events.push(MouseEvent::RelativeMove(dx, dy));
+        }
+
+        // 滚轮正值表示向上/向右
+        let wheel = self.wheel.detents(wheel, wheel_hi_res);
+        if wheel != 0 {
+            let direction = if wheel > 0 {
+                ScrollDirection::VerticalUp
+            } else {
+                ScrollDirection::VerticalDown
+            };
+            events.push(MouseEvent::Scroll(direction, wheel.saturating_abs()));
+        }
+        let hwheel = self.hwheel.detents(hwheel, hwheel_hi_res);
+        if hwheel != 0 {
+            let direction = if hwheel > 0 {
+                ScrollDirection::HorizontalRight
+            } else {
+                ScrollDirection::HorizontalLeft
+            };
+            events.push(MouseEvent::Scroll(direction, hwheel.saturating_abs()));
+        }
+        events
+    }
+}
+
+/// Iterator over the reports of an `input_event` stream
+///
+/// After `SYN_DROPPED` the records up to and including the next `SYN_REPORT`
+/// are discarded, as the kernel documentation requires; the next report then
+/// has `dropped` set.
+#[derive(Debug)]
+pub struct FrameReader<R> {
+    reader: R,
+    layout: TimeLayout,
+    pending: Vec<InputEvent>,
+    dropping: bool,
+    dropped: bool,
+}
+
+impl<R: Read> FrameReader<R> {
+    /// Read reports in the native record layout from `reader`
+    pub fn new(reader: R) -> Self {
+        Self::with_layout(reader, TimeLayout::NATIVE)
+    }
+
+    /// Read reports in the given record layout from `reader`
+    pub fn with_layout(reader: R, layout: TimeLayout) -> Self {
+        Self {
+            reader,
+            layout,
+            pending: Vec::new(),
+            dropping: false,
+            dropped: false,
+        }
+    }
+
+    /// Get the underlying reader back
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn take_frame(&mut self, time: Duration) -> Frame {
+        Frame {
+            time,
+            events: mem::take(&mut self.pending),
+            dropped: mem::take(&mut self.dropped),
+        }
+    }
+}
+
+impl<R: Read> Iterator for FrameReader<R> {
+    type Item = io::Result<Frame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match InputEvent::read_from(&mut self.reader, self.layout) {
+                Ok(Some(record)) if record.is_dropped() => {
+                    self.pending.clear();
+                    self.dropping = true;
+                }
+                // 丢弃 SYN_DROPPED 之后直到下一个 SYN_REPORT 的所有记录
+                Ok(Some(record)) if self.dropping => {
+                    if record.is_report() {
+                        self.dropping = false;
+                        self.dropped = true;
+                    }
+                }
+                Ok(Some(record)) if record.is_report() => {
+                    return Some(Ok(self.take_frame(record.time)));
+                }
+                Ok(Some(record)) => self.pending.push(record),
+                // 流结束时仍有未同步的记录，作为最后一帧返回
+                Ok(None) => {
+                    let time = self.pending.last()?.time;
+                    return Some(Ok(self.take_frame(time)));
+                }
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+/// Iterator decoding an `input_event` stream into timestamped mouse events
+#[derive(Debug)]
+pub struct Decoder<R> {
+    frames: FrameReader<R>,
+    state: FrameDecoder,
+    queue: VecDeque<TimedEvent>,
+}
+
+impl<R: Read> Decoder<R> {
+    /// Decode events in the native record layout from `reader`
+    pub fn new(reader: R) -> Self {
+        Self::with_layout(reader, TimeLayout::NATIVE)
+    }
+
+    /// Decode events in the given record layout from `reader`
+    pub fn with_layout(reader: R, layout: TimeLayout) -> Self {
+        Self {
+            frames: FrameReader::with_layout(reader, layout),
+            state: FrameDecoder::new(),
+            queue: VecDeque::new(),
+        }
+    }
+}
+
+impl<R: Read> Iterator for Decoder<R> {
+    type Item = io::Result<TimedEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.queue.pop_front() {
+                return Some(Ok(event));
+            }
+            let frame = match self.frames.next()? {
+                Ok(frame) => frame,
+                Err(err) => return Some(Err(err)),
+            };
+            self.queue.extend(
+                self.state
+                    .decode(&frame)
+                    .into_iter()
+                    .map(|event| TimedEvent::new(frame.time, event)),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{EV_SYN, SYN_DROPPED, SYN_REPORT};
+    use super::*;
+    use crate::types::Button;
+
+    const BTN_LEFT: u16 = 0x110;
+    const BTN_SIDE: u16 = 0x113;
+
+    /// Encode records by hand, independently of `InputEvent::to_bytes`
+    fn fixture(layout: TimeLayout, records: &[(u32, u16, u16, i32)]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for &(usec, kind, code, value) in records {
+            let (sec, usec) = (usec / 1_000_000, usec % 1_000_000);
+            match layout {
+                TimeLayout::Time64 => {
+                    bytes.extend_from_slice(&i64::from(sec).to_ne_bytes());
+                    bytes.extend_from_slice(&i64::from(usec).to_ne_bytes());
+                }
+                TimeLayout::Time32 => {
+                    bytes.extend_from_slice(&(sec as i32).to_ne_bytes());
+                    bytes.extend_from_slice(&(usec as i32).to_ne_bytes());
+                }
+            }
+            bytes.extend_from_slice(&kind.to_ne_bytes());
+            bytes.extend_from_slice(&code.to_ne_bytes());
+            bytes.extend_from_slice(&value.to_ne_bytes());
+        }
+        bytes
+    }
+
+    fn decode(bytes: &[u8], layout: TimeLayout) -> Vec<TimedEvent> {
+        Decoder::with_layout(bytes, layout)
+            .collect::<io::Result<_>>()
+            .unwrap()
+    }
+
+    fn at(usec: u64, event: MouseEvent) -> TimedEvent {
+        TimedEvent::new(Duration::from_micros(usec), event)
+    }
+
+    #[test]
+    fn decodes_both_time_layouts() {
+        let records = [
+            (1_000_500, EV_KEY, BTN_LEFT, 1),
+            (1_000_500, EV_SYN, SYN_REPORT, 0),
+            (1_002_000, EV_REL, REL_X, 5),
+            (1_002_000, EV_REL, REL_Y, -3),
+            (1_002_000, EV_SYN, SYN_REPORT, 0),
+            (1_004_000, EV_KEY, BTN_LEFT, 0),
+            (1_004_000, EV_KEY, BTN_SIDE, 1),
+            (1_004_000, EV_SYN, SYN_REPORT, 0),
+        ];
+        let expected = [
+            at(1_000_500, MouseEvent::Press(Button::Left)),
+            at(1_002_000, MouseEvent::RelativeMove(5, -3)),
+            at(1_004_000, MouseEvent::Release(Button::Left)),
+            at(1_004_000, MouseEvent::Press(Button::X1)),
+        ];
+        for (layout, size) in [(TimeLayout::Time64, 24), (TimeLayout::Time32, 16)] {
+            let bytes = fixture(layout, &records);
+            assert_eq!(bytes.len(), records.len() * size);
+            assert_eq!(decode(&bytes, layout), expected, "{:?}", layout);
+        }
+    }
+
+    #[test]
+    fn groups_records_per_report() {
+        // 同一报告中的多条 REL_X 合并，按键自动重复被忽略
+        let bytes = fixture(
+            TimeLayout::Time64,
+            &[
+                (10, EV_REL, REL_X, 2),
+                (10, EV_REL, REL_X, 3),
+                (10, EV_KEY, BTN_LEFT, 1),
+                (10, EV_SYN, SYN_REPORT, 0),
+                (20, EV_KEY, BTN_LEFT, 2),
+                (20, EV_REL, REL_Y, 4),
+                (20, EV_SYN, SYN_REPORT, 0),
+            ],
+        );
+        let frames: Vec<Frame> = FrameReader::with_layout(&bytes[..], TimeLayout::Time64)
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].events.len(), 3);
+        assert_eq!(frames[1].time, Duration::from_micros(20));
+        assert_eq!(
+            decode(&bytes, TimeLayout::Time64),
+            [
+                at(10, MouseEvent::Press(Button::Left)),
+                at(10, MouseEvent::RelativeMove(5, 0)),
+                at(20, MouseEvent::RelativeMove(0, 4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn accumulates_high_resolution_wheel() {
+        // 不足 120 的高精度滚动累积到一格，旧式 REL_WHEEL 被忽略
+        let bytes = fixture(
+            TimeLayout::Time64,
+            &[
+                (10, EV_REL, REL_WHEEL_HI_RES, 60),
+                (10, EV_SYN, SYN_REPORT, 0),
+                (20, EV_REL, REL_WHEEL_HI_RES, 60),
+                (20, EV_REL, REL_WHEEL, 1),
+                (20, EV_SYN, SYN_REPORT, 0),
+                (30, EV_REL, REL_WHEEL_HI_RES, -40),
+                (30, EV_SYN, SYN_REPORT, 0),
+                (40, EV_REL, REL_WHEEL, -1),
+                (40, EV_SYN, SYN_REPORT, 0),
+                (50, EV_REL, REL_WHEEL_HI_RES, -80),
+                (50, EV_SYN, SYN_REPORT, 0),
+            ],
+        );
+        assert_eq!(
+            decode(&bytes, TimeLayout::Time64),
+            [
+                at(20, MouseEvent::Scroll(ScrollDirection::VerticalUp, 1)),
+                at(50, MouseEvent::Scroll(ScrollDirection::VerticalDown, 1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn discards_records_until_report_after_syn_dropped() {
+        let bytes = fixture(
+            TimeLayout::Time64,
+            &[
+                (10, EV_KEY, BTN_LEFT, 1),
+                (10, EV_SYN, SYN_DROPPED, 0),
+                (20, EV_REL, REL_X, 7),
+                (20, EV_SYN, SYN_REPORT, 0),
+                (30, EV_REL, REL_Y, 2),
+                (30, EV_SYN, SYN_REPORT, 0),
+                (40, EV_REL, REL_Y, 1),
+                (40, EV_SYN, SYN_REPORT, 0),
+            ],
+        );
+        let frames: Vec<Frame> = FrameReader::with_layout(&bytes[..], TimeLayout::Time64)
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(
+            frames,
+            [
+                Frame {
+                    time: Duration::from_micros(30),
+                    events: vec![InputEvent {
+                        time: Duration::from_micros(30),
+                        ..InputEvent::new(EV_REL, REL_Y, 2)
+                    }],
+                    dropped: true,
+                },
+                Frame {
+                    time: Duration::from_micros(40),
+                    events: vec![InputEvent {
+                        time: Duration::from_micros(40),
+                        ..InputEvent::new(EV_REL, REL_Y, 1)
+                    }],
+                    dropped: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn truncated_tail_is_an_error() {
+        for layout in [TimeLayout::Time64, TimeLayout::Time32] {
+            let mut bytes = fixture(
+                layout,
+                &[
+                    (10, EV_KEY, BTN_LEFT, 1),
+                    (10, EV_SYN, SYN_REPORT, 0),
+                    (20, EV_REL, REL_X, 1),
+                ],
+            );
+            bytes.truncate(bytes.len() - 3);
+            let mut decoder = Decoder::with_layout(&bytes[..], layout);
+            assert_eq!(
+                decoder.next().unwrap().unwrap(),
+                at(10, MouseEvent::Press(Button::Left))
+            );
+            let err = decoder.next().unwrap().unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        }
+    }
+
+    #[test]
+    fn unsynchronized_tail_is_a_last_frame() {
+        let bytes = fixture(TimeLayout::Time32, &[(10, EV_REL, REL_X, 1)]);
+        assert_eq!(
+            decode(&bytes, TimeLayout::Time32),
+            [at(10, MouseEvent::RelativeMove(1, 0))]
+        );
+    }
+}
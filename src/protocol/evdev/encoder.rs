@@ -0,0 +1,334 @@
+//! Encoding of mouse events into `input_event` records
+
+use std::collections::BTreeSet;
+use std::ffi::c_ulong;
+use std::io::{self, Write};
+
+use super::{
+    InputEvent, TimeLayout, ABS_X, ABS_Y, EV_ABS, EV_KEY, EV_REL, EV_SYN, REL_HWHEEL,
+    REL_HWHEEL_HI_RES, REL_WHEEL, REL_WHEEL_HI_RES, REL_X, REL_Y, SYN_REPORT, UI_SET_ABSBIT,
+    UI_SET_EVBIT, UI_SET_KEYBIT, UI_SET_RELBIT, WHEEL_HI_RES_UNITS,
+};
+use crate::mapping::CodeSpace;
+use crate::types::{Button, MouseEvent, ScrollDirection};
+
+/// Capability bit to enable on a uinput device before creating it
+///
+/// Absolute axes additionally need their range configured with
+/// `UI_ABS_SETUP` before the device is created.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Capability {
+    /// Event type, enabled with `UI_SET_EVBIT`
+    Event(u16),
+    /// Key or button code, enabled with `UI_SET_KEYBIT`
+    Key(u16),
+    /// Relative axis, enabled with `UI_SET_RELBIT`
+    Rel(u16),
+    /// Absolute axis, enabled with `UI_SET_ABSBIT`
+    Abs(u16),
+}
+
+impl Capability {
+    /// Get the `ioctl` request enabling this capability
+    ///
+    /// The request is encoded for the architecture this crate is compiled for.
+    pub const fn request(&self) -> c_ulong {
+        match self {
+            Capability::Event(_) => UI_SET_EVBIT,
+            Capability::Key(_) => UI_SET_KEYBIT,
+            Capability::Rel(_) => UI_SET_RELBIT,
+            Capability::Abs(_) => UI_SET_ABSBIT,
+        }
+    }
+
+    /// Get the `ioctl` argument enabling this capability
+    pub const fn code(&self) -> u16 {
+        match self {
+            Capability::Event(code)
+            | Capability::Key(code)
+            | Capability::Rel(code)
+            | Capability::Abs(code) => *code,
+        }
+    }
+}
+
+/// Writer turning mouse events into the records a uinput device expects
+///
+/// Buttons are written as kernel `BTN_*` codes from `CodeSpace::Evdev`, and
+/// every event is followed by `SYN_REPORT`.
+#[derive(Debug)]
+pub struct Encoder<W> {
+    writer: W,
+    layout: TimeLayout,
+    hi_res_wheel: bool,
+}
+
+impl<W> Encoder<W> {
+    /// Write records in the native layout to `writer`
+    pub fn new(writer: W) -> Self {
+        Self::with_layout(writer, TimeLayout::NATIVE)
+    }
+
+    /// Write records in the given layout to `writer`
+    pub fn with_layout(writer: W, layout: TimeLayout) -> Self {
+        Self {
+            writer,
+            layout,
+            hi_res_wheel: false,
+        }
+    }
+
+    /// Also emit `REL_WHEEL_HI_RES`/`REL_HWHEEL_HI_RES` for scroll events
+    pub fn set_hi_res_wheel(&mut self, enabled: bool) {
+        self.hi_res_wheel = enabled;
+    }
+
+    /// Get the records for one event, including the trailing `SYN_REPORT`
+    ///
+    /// Events without any effect, such as a zero motion, produce no records.
+    pub fn records(&self, event: &MouseEvent) -> Vec<InputEvent> {
+        let mut records = Vec::new();
+        match *event {
+            MouseEvent::Press(button) => records.extend(key(button, 1)),
+            MouseEvent::Release(button) => records.extend(key(button, 0)),
+            MouseEvent::RelativeMove(dx, dy) => {
+                if dx != 0 {
+                    records.push(InputEvent::new(EV_REL, REL_X, dx));
+                }
+                if dy != 0 {
+                    records.push(InputEvent::new(EV_REL, REL_Y, dy));
+                }
+            }
+            MouseEvent::Move(x, y) => {
+                records.push(InputEvent::new(EV_ABS, ABS_X, x));
+                records.push(InputEvent::new(EV_ABS, ABS_Y, y));
+            }
+            MouseEvent::Scroll(direction, amount) if amount != 0 => {
+                let (code, hi_res_code, detents) = wheel(direction, amount);
+                records.push(InputEvent::new(EV_REL, code, detents));
+                if self.hi_res_wheel {
+                    let units = detents.saturating_mul(WHEEL_HI_RES_UNITS);
+                    records.push(InputEvent::new(EV_REL, hi_res_code, units));
+                }
+            }
+            MouseEvent::Scroll(..) => {}
+        }
+
+        if !records.is_empty() {
+            records.push(InputEvent::new(EV_SYN, SYN_REPORT, 0));
+        }
+        records
+    }
+
+    /// Get the capabilities needed to replay `events`, sorted by `ioctl`
+    ///
+    /// Event types come first, as uinput expects them to be enabled before
+    /// their codes.
+    pub fn capabilities<'a, I>(&self, events: I) -> Vec<Capability>
+    where
+        I: IntoIterator<Item = &'a MouseEvent>,
+    {
+        let mut capabilities = BTreeSet::new();
+        for event in events {
+            for record in self.records(event) {
+                match record.kind {
+                    EV_KEY => capabilities.insert(Capability::Key(record.code)),
+                    EV_REL => capabilities.insert(Capability::Rel(record.code)),
+                    EV_ABS => capabilities.insert(Capability::Abs(record.code)),
+                    _ => continue,
+                };
+                capabilities.insert(Capability::Event(record.kind));
+            }
+        }
+        capabilities.into_iter().collect()
+    }
+
+    /// Get the capabilities of a relative mouse with every standard button and both wheels
+    pub fn mouse_capabilities(&self) -> Vec<Capability> {
+        let events: Vec<MouseEvent> = Button::iter()
+            .map(MouseEvent::Press)
+            .chain([
+                MouseEvent::RelativeMove(1, 1),
+                MouseEvent::Scroll(ScrollDirection::VerticalUp, 1),
+                MouseEvent::Scroll(ScrollDirection::HorizontalRight, 1),
+            ])
+            .collect();
+        self.capabilities(&events)
+    }
+
+    /// Get a reference to the underlying writer
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Get the underlying writer back
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> Encoder<W> {
+    /// Write the records of one event
+    pub fn write_event(&mut self, event: &MouseEvent) -> io::Result<()> {
+        for record in self.records(event) {
+            record.write_to(&mut self.writer, self.layout)?;
+        }
+        Ok(())
+    }
+
+    /// Write the records of several events
+    pub fn write_events<'a, I>(&mut self, events: I) -> io::Result<()>
+    where
+        I: IntoIterator<Item = &'a MouseEvent>,
+    {
+        events
+            .into_iter()
+            .try_for_each(|event| self.write_event(event))
+    }
+
+    /// Flush the underlying writer
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+fn key(button: Button, value: i32) -> Option<InputEvent> {
+    // 标准按钮的 BTN_* 代码均小于 0x200，可以安全转换为 u16
+    let code = CodeSpace::Evdev.to_code(button)? as u16;
+    Some(InputEvent::new(EV_KEY, code, value))
+}
+
+// 滚轮正值表示向上/向右
+fn wheel(direction: ScrollDirection, amount: i32) -> (u16, u16, i32) {
+    match direction {
+        ScrollDirection::VerticalUp => (REL_WHEEL, REL_WHEEL_HI_RES, amount),
+        ScrollDirection::VerticalDown => (REL_WHEEL, REL_WHEEL_HI_RES, amount.saturating_neg()),
+        ScrollDirection::HorizontalRight => (REL_HWHEEL, REL_HWHEEL_HI_RES, amount),
+        ScrollDirection::HorizontalLeft => (REL_HWHEEL, REL_HWHEEL_HI_RES, amount.saturating_neg()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Decoder, FrameReader};
+    use super::*;
+    use crate::types::TimedEvent;
+
+    const BTN_LEFT: u16 = 0x110;
+
+    fn encode(encoder: &mut Encoder<Vec<u8>>, events: &[MouseEvent]) -> Vec<u8> {
+        encoder.write_events(events).unwrap();
+        core::mem::take(&mut encoder.writer)
+    }
+
+    #[test]
+    fn records_use_the_input_event_layout() {
+        for (layout, size) in [(TimeLayout::Time64, 24), (TimeLayout::Time32, 16)] {
+            let mut encoder = Encoder::with_layout(Vec::new(), layout);
+            let bytes = encode(&mut encoder, &[MouseEvent::Press(Button::Left)]);
+            assert_eq!(bytes.len(), 2 * size);
+
+            // 时间戳为零，随后是 type、code 与 value
+            let (key, syn) = bytes.split_at(size);
+            assert!(key[..size - 8].iter().all(|byte| *byte == 0));
+            assert_eq!(key[size - 8..size - 6], EV_KEY.to_ne_bytes());
+            assert_eq!(key[size - 6..size - 4], BTN_LEFT.to_ne_bytes());
+            assert_eq!(key[size - 4..], 1i32.to_ne_bytes());
+            assert_eq!(syn[size - 8..size - 6], EV_SYN.to_ne_bytes());
+            assert_eq!(syn[size - 6..size - 4], SYN_REPORT.to_ne_bytes());
+            assert_eq!(syn[size - 4..], 0i32.to_ne_bytes());
+        }
+    }
+
+    #[test]
+    fn every_event_is_one_report() {
+        let events = [
+            MouseEvent::Press(Button::X1),
+            MouseEvent::RelativeMove(5, -3),
+            MouseEvent::RelativeMove(0, 0),
+            MouseEvent::Scroll(ScrollDirection::VerticalDown, 2),
+            MouseEvent::Scroll(ScrollDirection::HorizontalLeft, 0),
+            MouseEvent::Move(100, 200),
+            MouseEvent::Release(Button::X1),
+        ];
+        let mut encoder = Encoder::new(Vec::new());
+        let bytes = encode(&mut encoder, &events);
+
+        // 无效果的事件不产生记录，其余每个事件恰好一个以 SYN_REPORT 结尾的报告
+        let frames: Vec<_> = FrameReader::new(&bytes[..])
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[1].events.len(), 2);
+
+        let decoded: Vec<MouseEvent> = Decoder::new(&bytes[..])
+            .map(|event| event.map(|TimedEvent { event, .. }| event))
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(
+            decoded,
+            [
+                MouseEvent::Press(Button::X1),
+                MouseEvent::RelativeMove(5, -3),
+                MouseEvent::Scroll(ScrollDirection::VerticalDown, 2),
+                MouseEvent::Move(100, 200),
+                MouseEvent::Release(Button::X1),
+            ]
+        );
+    }
+
+    #[test]
+    fn hi_res_wheel_decodes_to_the_same_detents() {
+        let mut encoder = Encoder::new(Vec::new());
+        encoder.set_hi_res_wheel(true);
+        let scroll = MouseEvent::Scroll(ScrollDirection::HorizontalRight, 3);
+        assert_eq!(
+            encoder.records(&scroll),
+            [
+                InputEvent::new(EV_REL, REL_HWHEEL, 3),
+                InputEvent::new(EV_REL, REL_HWHEEL_HI_RES, 360),
+                InputEvent::new(EV_SYN, SYN_REPORT, 0),
+            ]
+        );
+
+        let bytes = encode(&mut encoder, &[scroll]);
+        let decoded: Vec<_> = Decoder::new(&bytes[..]).collect::<io::Result<_>>().unwrap();
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].event, scroll);
+    }
+
+    #[test]
+    fn capabilities_list_event_types_first() {
+        let encoder = Encoder::new(io::sink());
+        let capabilities = encoder.capabilities(&[
+            MouseEvent::RelativeMove(1, 0),
+            MouseEvent::Press(Button::Left),
+            MouseEvent::Release(Button::Left),
+        ]);
+        assert_eq!(
+            capabilities,
+            [
+                Capability::Event(EV_KEY),
+                Capability::Event(EV_REL),
+                Capability::Key(BTN_LEFT),
+                Capability::Rel(REL_X),
+            ]
+        );
+        assert_eq!(Capability::Key(BTN_LEFT).request(), UI_SET_KEYBIT);
+        assert_eq!(Capability::Rel(REL_X).code(), REL_X);
+
+        let mouse = encoder.mouse_capabilities();
+        assert!(mouse.contains(&Capability::Rel(REL_WHEEL)));
+        assert!(mouse.contains(&Capability::Rel(REL_HWHEEL)));
+        assert!(!mouse.contains(&Capability::Event(EV_ABS)));
+    }
+
+    #[cfg(any(target_arch = "x86_64", target_arch = "x86", target_arch = "aarch64"))]
+    #[test]
+    fn ioctl_requests_match_the_kernel_headers() {
+        assert_eq!(UI_SET_EVBIT, 0x4004_5564);
+        assert_eq!(UI_SET_KEYBIT, 0x4004_5565);
+        assert_eq!(UI_SET_RELBIT, 0x4004_5566);
+        assert_eq!(UI_SET_ABSBIT, 0x4004_5567);
+    }
+}
@@ -0,0 +1,242 @@
+//! Linux evdev `input_event` records
+//!
+//! Evdev devices (`/dev/input/event*`) deliver a stream of fixed-size
+//! `struct input_event` records holding a timestamp, an event type, a code and
+//! a value. The records of one hardware report are terminated by an
+//! `EV_SYN`/`SYN_REPORT` record. This module reads such streams from any
+//! `Read`, so captured byte fixtures can be replayed without a device, and
+//! writes the records a uinput virtual mouse needs to any `Write`.
+//!
+//! Records are encoded and decoded in native byte order. The size of the timestamp
+//! depends on the kernel ABI and is selected with `TimeLayout`.
+
+use std::ffi::c_ulong;
+use std::io::{self, Read, Write};
+use std::time::Duration;
+
+/// Decoding of `input_event` streams into mouse events
+mod decoder;
+/// Encoding of mouse events into `input_event` records
+mod encoder;
+
+pub use decoder::{Decoder, Frame, FrameDecoder, FrameReader};
+pub use encoder::{Capability, Encoder};
+
+/// Synchronization event type
+pub const EV_SYN: u16 = 0x00;
+/// Key and button event type
+pub const EV_KEY: u16 = 0x01;
+/// Relative axis event type
+pub const EV_REL: u16 = 0x02;
+/// Absolute axis event type
+pub const EV_ABS: u16 = 0x03;
+
+/// End of a hardware report
+pub const SYN_REPORT: u16 = 0x00;
+/// The kernel buffer overflowed and records were lost
+pub const SYN_DROPPED: u16 = 0x03;
+
+/// Horizontal motion
+pub const REL_X: u16 = 0x00;
+/// Vertical motion
+pub const REL_Y: u16 = 0x01;
+/// Horizontal wheel, in detents
+pub const REL_HWHEEL: u16 = 0x06;
+/// Vertical wheel, in detents
+pub const REL_WHEEL: u16 = 0x08;
+/// Vertical wheel, in 1/120 of a detent
+pub const REL_WHEEL_HI_RES: u16 = 0x0b;
+/// Horizontal wheel, in 1/120 of a detent
+pub const REL_HWHEEL_HI_RES: u16 = 0x0c;
+
+/// Absolute horizontal position
+pub const ABS_X: u16 = 0x00;
+/// Absolute vertical position
+pub const ABS_Y: u16 = 0x01;
+
+/// High-resolution wheel units per detent
+pub const WHEEL_HI_RES_UNITS: i32 = 120;
+
+/// `ioctl` request enabling an event type on a uinput device
+pub const UI_SET_EVBIT: c_ulong = uinput_iow_int(100);
+/// `ioctl` request enabling a key or button code on a uinput device
+pub const UI_SET_KEYBIT: c_ulong = uinput_iow_int(101);
+/// `ioctl` request enabling a relative axis on a uinput device
+pub const UI_SET_RELBIT: c_ulong = uinput_iow_int(102);
+/// `ioctl` request enabling an absolute axis on a uinput device
+pub const UI_SET_ABSBIT: c_ulong = uinput_iow_int(103);
+
+// _IOC_WRITE 方向位：asm-generic（x86、ARM、RISC-V 等）为 1 << 30，
+// MIPS、PowerPC 与 SPARC 为 4 << 29
+#[cfg(not(any(
+    target_arch = "mips",
+    target_arch = "mips64",
+    target_arch = "powerpc",
+    target_arch = "powerpc64",
+    target_arch = "sparc",
+    target_arch = "sparc64"
+)))]
+const IOC_WRITE: c_ulong = 1 << 30;
+#[cfg(any(
+    target_arch = "mips",
+    target_arch = "mips64",
+    target_arch = "powerpc",
+    target_arch = "powerpc64",
+    target_arch = "sparc",
+    target_arch = "sparc64"
+))]
+const IOC_WRITE: c_ulong = 4 << 29;
+
+/// `_IOW('U', nr, int)` for the target architecture
+const fn uinput_iow_int(nr: c_ulong) -> c_ulong {
+    IOC_WRITE | (4 << 16) | ((b'U' as c_ulong) << 8) | nr
+}
+
+/// Size of the `timeval` in an `input_event` record
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimeLayout {
+    /// Two 64-bit fields, 24-byte records (64-bit kernels and `time64` ABIs)
+    Time64,
+    /// Two 32-bit fields, 16-byte records (32-bit userspace with 32-bit `time_t`)
+    Time32,
+}
+
+impl TimeLayout {
+    /// Layout used by the target this crate is compiled for
+    #[cfg(target_pointer_width = "64")]
+    pub const NATIVE: TimeLayout = TimeLayout::Time64;
+    /// Layout used by the target this crate is compiled for
+    #[cfg(not(target_pointer_width = "64"))]
+    pub const NATIVE: TimeLayout = TimeLayout::Time32;
+
+    /// Size in bytes of one record
+    pub const fn record_size(&self) -> usize {
+        self.time_size() + 8
+    }
+
+    const fn time_size(&self) -> usize {
+        match self {
+            TimeLayout::Time64 => 16,
+            TimeLayout::Time32 => 8,
+        }
+    }
+}
+
+/// Size in bytes of the largest record layout
+const MAX_RECORD_SIZE: usize = TimeLayout::Time64.record_size();
+
+/// A single `struct input_event` record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputEvent {
+    /// Timestamp of the record
+    pub time: Duration,
+    /// Event type, such as `EV_KEY`
+    pub kind: u16,
+    /// Event code, such as `BTN_LEFT` or `REL_X`
+    pub code: u16,
+    /// Event value
+    pub value: i32,
+}
+
+impl InputEvent {
+    /// Create a record with a zero timestamp
+    ///
+    /// The kernel stamps records written to uinput itself.
+    pub const fn new(kind: u16, code: u16, value: i32) -> Self {
+        Self {
+            time: Duration::ZERO,
+            kind,
+            code,
+            value,
+        }
+    }
+
+    /// Decode a record from its native-endian bytes
+    ///
+    /// Returns `None` if `bytes` is not exactly one record of `layout`.
+    pub fn from_bytes(bytes: &[u8], layout: TimeLayout) -> Option<Self> {
+        if bytes.len() != layout.record_size() {
+            return None;
+        }
+        let (time, rest) = bytes.split_at(layout.time_size());
+        let (sec, usec) = match layout {
+            TimeLayout::Time64 => (
+                i64::from_ne_bytes(time[0..8].try_into().ok()?),
+                i64::from_ne_bytes(time[8..16].try_into().ok()?),
+            ),
+            TimeLayout::Time32 => (
+                i32::from_ne_bytes(time[0..4].try_into().ok()?).into(),
+                i32::from_ne_bytes(time[4..8].try_into().ok()?).into(),
+            ),
+        };
+        Some(Self {
+            time: timeval(sec, usec),
+            kind: u16::from_ne_bytes(rest[0..2].try_into().ok()?),
+            code: u16::from_ne_bytes(rest[2..4].try_into().ok()?),
+            value: i32::from_ne_bytes(rest[4..8].try_into().ok()?),
+        })
+    }
+
+    /// Encode the record into its native-endian bytes
+    pub fn to_bytes(&self, layout: TimeLayout) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(layout.record_size());
+        let (sec, usec) = (self.time.as_secs(), self.time.subsec_micros());
+        // 32 位布局的秒数在 2038 年后溢出，与内核行为一致地截断
+        match layout {
+            TimeLayout::Time64 => {
+                bytes.extend_from_slice(&(sec as i64).to_ne_bytes());
+                bytes.extend_from_slice(&i64::from(usec).to_ne_bytes());
+            }
+            TimeLayout::Time32 => {
+                bytes.extend_from_slice(&(sec as i32).to_ne_bytes());
+                bytes.extend_from_slice(&(usec as i32).to_ne_bytes());
+            }
+        }
+        bytes.extend_from_slice(&self.kind.to_ne_bytes());
+        bytes.extend_from_slice(&self.code.to_ne_bytes());
+        bytes.extend_from_slice(&self.value.to_ne_bytes());
+        bytes
+    }
+
+    /// Write the record to `writer`
+    pub fn write_to<W: Write>(&self, writer: &mut W, layout: TimeLayout) -> io::Result<()> {
+        writer.write_all(&self.to_bytes(layout))
+    }
+
+    /// Read the next record, returning `None` at a clean end of stream
+    ///
+    /// A stream ending in the middle of a record is reported as
+    /// `ErrorKind::UnexpectedEof`.
+    pub fn read_from<R: Read>(reader: &mut R, layout: TimeLayout) -> io::Result<Option<Self>> {
+        let mut buf = [0u8; MAX_RECORD_SIZE];
+        let buf = &mut buf[..layout.record_size()];
+        let mut filled = 0;
+        while filled < buf.len() {
+            match reader.read(&mut buf[filled..]) {
+                Ok(0) if filled == 0 => return Ok(None),
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(n) => filled += n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(Self::from_bytes(buf, layout))
+    }
+
+    /// Check whether this record ends a hardware report
+    pub fn is_report(&self) -> bool {
+        self.kind == EV_SYN && self.code == SYN_REPORT
+    }
+
+    /// Check whether this record reports lost records
+    pub fn is_dropped(&self) -> bool {
+        self.kind == EV_SYN && self.code == SYN_DROPPED
+    }
+}
+
+// 负的时间戳无法用 Duration 表示，按 0 处理
+fn timeval(sec: i64, usec: i64) -> Duration {
+    let sec = u64::try_from(sec).unwrap_or(0);
+    let usec = u64::try_from(usec).unwrap_or(0);
+    Duration::from_secs(sec) + Duration::from_micros(usec)
+}
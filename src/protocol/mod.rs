@@ -0,0 +1,132 @@
+//! Wire formats of mouse input protocols
+//!
+//! Each submodule decodes one protocol's byte stream into `MouseEvent`s and
+//! encodes `MouseEvent`s back into that format. Packet-based protocols share
+//! the `Packet` representation below.
+
+use alloc::vec::Vec;
+
+use crate::types::{ButtonSet, MouseEvent, ScrollDirection};
+
+/// Linux evdev `input_event` records
+#[cfg(feature = "std")]
+pub mod evdev;
+/// PS/2 mouse packets, including the IntelliMouse extensions
+pub mod ps2;
+/// RDP fast-path and slow-path mouse input events
+pub mod rdp;
+/// RFB (VNC) PointerEvent messages
+pub mod rfb;
+/// Microsoft and Mouse Systems serial mouse packets
+pub mod serial;
+
+/// Button state and motion carried by one protocol packet
+///
+/// Signs follow the evdev conventions whatever the wire format uses: `dx`
+/// grows to the right, `dy` grows downwards, `wheel` is positive when
+/// scrolling up and `hwheel` when scrolling right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Packet {
+    /// Buttons held down
+    pub buttons: ButtonSet,
+    /// Horizontal motion
+    pub dx: i32,
+    /// Vertical motion
+    pub dy: i32,
+    /// Vertical wheel detents
+    pub wheel: i32,
+    /// Horizontal wheel detents
+    pub hwheel: i32,
+}
+
+impl Packet {
+    /// Get the events of this packet, given the buttons held before it
+    ///
+    /// Button edges come first, followed by the motion and the wheels.
+    pub fn events(&self, previous: ButtonSet) -> Vec<MouseEvent> {
+        let mut events: Vec<MouseEvent> = previous.changes(self.buttons).collect();
+        if self.dx != 0 || self.dy != 0 {
+            events.push(MouseEvent::RelativeMove(self.dx, self.dy));
+        }
+        if self.wheel != 0 {
+            let direction = if self.wheel > 0 {
+                ScrollDirection::VerticalUp
+            } else {
+                ScrollDirection::VerticalDown
+            };
+            events.push(MouseEvent::Scroll(direction, self.wheel.saturating_abs()));
+        }
+        if self.hwheel != 0 {
+            let direction = if self.hwheel > 0 {
+                ScrollDirection::HorizontalRight
+            } else {
+                ScrollDirection::HorizontalLeft
+            };
+            events.push(MouseEvent::Scroll(direction, self.hwheel.saturating_abs()));
+        }
+        events
+    }
+
+    /// Split an event into packets whose values fit the given ranges
+    ///
+    /// `buttons` is the state before the event and is updated by presses and
+    /// releases. Motion is split into steps of at most `motion` per axis and
+    /// wheel detents into steps of at most `wheel`. Absolute moves have no
+    /// packet representation and produce no packets.
+    pub(crate) fn split(
+        buttons: &mut ButtonSet,
+        event: &MouseEvent,
+        motion: i32,
+        wheel: i32,
+    ) -> Vec<Packet> {
+        buttons.apply(event);
+        let base = Packet {
+            buttons: *buttons,
+            ..Packet::default()
+        };
+
+        match *event {
+            MouseEvent::Press(_) | MouseEvent::Release(_) => [base].into(),
+            MouseEvent::RelativeMove(dx, dy) => steps(dx, dy, motion)
+                .map(|(dx, dy)| Packet { dx, dy, ..base })
+                .collect(),
+            MouseEvent::Scroll(direction, amount) => {
+                let (vertical, horizontal) = match direction {
+                    ScrollDirection::VerticalUp => (amount, 0),
+                    ScrollDirection::VerticalDown => (amount.saturating_neg(), 0),
+                    ScrollDirection::HorizontalRight => (0, amount),
+                    ScrollDirection::HorizontalLeft => (0, amount.saturating_neg()),
+                };
+                steps(vertical, horizontal, wheel)
+                    .map(|(wheel, hwheel)| Packet {
+                        wheel,
+                        hwheel,
+                        ..base
+                    })
+                    .collect()
+            }
+            MouseEvent::Move(..) => Vec::new(),
+        }
+    }
+}
+
+/// Split `(x, y)` into steps of at most `limit` per axis
+///
+/// The last step carries the remainder; a zero vector yields no steps.
+fn steps(mut x: i32, mut y: i32, limit: i32) -> impl Iterator<Item = (i32, i32)> {
+    core::iter::from_fn(move || {
+        if x == 0 && y == 0 {
+            return None;
+        }
+        let step = (x.clamp(-limit, limit), y.clamp(-limit, limit));
+        x -= step.0;
+        y -= step.1;
+        Some(step)
+    })
+}
+
+/// Sign-extend the low `bits` bits of `value`
+pub(crate) const fn sign_extend(value: u32, bits: u32) -> i32 {
+    let shift = 32 - bits;
+    ((value << shift) as i32) >> shift
+}
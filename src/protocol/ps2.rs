@@ -0,0 +1,367 @@
+//! PS/2 mouse packets
+//!
+//! A standard packet has three bytes: a header with the left, right and
+//! middle buttons, bit 3 always set and the sign and overflow bits of the
+//! motion, followed by the low eight bits of X and Y. Y grows upwards. After
+//! the IntelliMouse knock sequences a device reports ID 3 and appends a signed
+//! wheel byte, or ID 4 (Explorer) and appends a byte holding a 4-bit wheel
+//! and buttons 4 and 5. Explorer bytes with bit 7 or bit 6 set instead carry a
+//! 6-bit vertical or horizontal wheel value, as the Linux driver expects.
+
+use alloc::vec::Vec;
+
+use super::{sign_extend, Packet};
+use crate::types::{Button, ButtonSet, MouseEvent, ScrollDirection};
+
+/// Largest motion per axis in one packet
+const MOTION_LIMIT: i32 = 255;
+/// Largest wheel value per packet reported by IntelliMouse devices
+const WHEEL_LIMIT: i32 = 7;
+
+/// PS/2 packet format negotiated with the device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ps2Protocol {
+    /// Three-byte packets with three buttons (device ID 0)
+    Standard,
+    /// Four-byte packets with a vertical wheel (device ID 3)
+    IntelliMouse,
+    /// Four-byte packets with a wheel and buttons 4 and 5 (device ID 4)
+    Explorer,
+}
+
+impl Ps2Protocol {
+    /// Size in bytes of one packet
+    pub const fn packet_size(&self) -> usize {
+        match self {
+            Ps2Protocol::Standard => 3,
+            Ps2Protocol::IntelliMouse | Ps2Protocol::Explorer => 4,
+        }
+    }
+
+    /// Device ID reported by a mouse speaking this protocol
+    pub const fn device_id(&self) -> u8 {
+        match self {
+            Ps2Protocol::Standard => 0,
+            Ps2Protocol::IntelliMouse => 3,
+            Ps2Protocol::Explorer => 4,
+        }
+    }
+
+    /// Check whether packets of this protocol can carry `event`
+    pub fn supports(&self, event: &MouseEvent) -> bool {
+        match event {
+            MouseEvent::Press(button) | MouseEvent::Release(button) => match button {
+                Button::Left | Button::Right | Button::Middle => true,
+                Button::X1 | Button::X2 => *self == Ps2Protocol::Explorer,
+                _ => false,
+            },
+            MouseEvent::RelativeMove(..) => true,
+            MouseEvent::Scroll(direction, _) => match self {
+                Ps2Protocol::Standard => false,
+                Ps2Protocol::IntelliMouse => matches!(
+                    direction,
+                    ScrollDirection::VerticalUp | ScrollDirection::VerticalDown
+                ),
+                Ps2Protocol::Explorer => true,
+            },
+            MouseEvent::Move(..) => false,
+        }
+    }
+}
+
+/// Check the fixed bit of a header byte and reject overflowed motion
+///
+/// Overflow is rare enough in real traffic that a header claiming it is more
+/// likely a data byte seen after losing sync.
+const fn is_header(byte: u8) -> bool {
+    byte & 0x08 != 0 && byte & 0xC0 == 0
+}
+
+/// Byte-by-byte PS/2 packet decoder with sync recovery
+///
+/// Bytes that cannot start a packet are skipped, so a decoder fed a stream
+/// joined mid-packet or with dropped bytes realigns on a later header.
+#[derive(Debug, Clone)]
+pub struct Ps2Decoder {
+    protocol: Ps2Protocol,
+    buffer: [u8; 4],
+    len: usize,
+    buttons: ButtonSet,
+}
+
+impl Ps2Decoder {
+    /// Create a decoder for `protocol`
+    pub fn new(protocol: Ps2Protocol) -> Self {
+        Self {
+            protocol,
+            buffer: [0; 4],
+            len: 0,
+            buttons: ButtonSet::EMPTY,
+        }
+    }
+
+    /// Get the protocol being decoded
+    pub fn protocol(&self) -> Ps2Protocol {
+        self.protocol
+    }
+
+    /// Get the buttons held according to the last packet
+    pub fn buttons(&self) -> ButtonSet {
+        self.buttons
+    }
+
+    /// Drop any partially received packet
+    pub fn resync(&mut self) {
+        self.len = 0;
+    }
+
+    /// Feed one byte, returning the packet it completes
+    pub fn push(&mut self, byte: u8) -> Option<Packet> {
+        if self.len == 0 && !is_header(byte) {
+            return None;
+        }
+        self.buffer[self.len] = byte;
+        self.len += 1;
+        if self.len < self.protocol.packet_size() {
+            return None;
+        }
+        self.len = 0;
+        let packet = self.parse();
+        self.buttons = packet.buttons;
+        Some(packet)
+    }
+
+    /// Feed bytes, returning the events of every completed packet
+    pub fn decode(&mut self, bytes: &[u8]) -> Vec<MouseEvent> {
+        let mut events = Vec::new();
+        for &byte in bytes {
+            let previous = self.buttons;
+            if let Some(packet) = self.push(byte) {
+                events.extend(packet.events(previous));
+            }
+        }
+        events
+    }
+
+    fn parse(&self) -> Packet {
+        let [header, x, y, extra] = self.buffer;
+        let header = u32::from(header);
+        let mut buttons = self.buttons;
+        buttons.set(Button::Left, header & 0x01 != 0);
+        buttons.set(Button::Right, header & 0x02 != 0);
+        buttons.set(Button::Middle, header & 0x04 != 0);
+
+        // 9 位补码，符号位位于包头；PS/2 的 Y 轴向上为正
+        let mut packet = Packet {
+            dx: sign_extend((header & 0x10) << 4 | u32::from(x), 9),
+            dy: -sign_extend((header & 0x20) << 3 | u32::from(y), 9),
+            ..Packet::default()
+        };
+
+        let extra = u32::from(extra);
+        match self.protocol {
+            Ps2Protocol::Standard => {}
+            // 滚轮字节向下为正
+            Ps2Protocol::IntelliMouse => packet.wheel = -sign_extend(extra, 8),
+            Ps2Protocol::Explorer => match extra & 0xC0 {
+                0x80 => packet.wheel = -sign_extend(extra & 0x3F, 6),
+                0x40 => packet.hwheel = -sign_extend(extra & 0x3F, 6),
+                _ => {
+                    packet.wheel = -sign_extend(extra & 0x0F, 4);
+                    buttons.set(Button::X1, extra & 0x10 != 0);
+                    buttons.set(Button::X2, extra & 0x20 != 0);
+                }
+            },
+        }
+        packet.buttons = buttons;
+        packet
+    }
+}
+
+/// PS/2 packet encoder tracking the button state
+#[derive(Debug, Clone)]
+pub struct Ps2Encoder {
+    protocol: Ps2Protocol,
+    buttons: ButtonSet,
+}
+
+impl Ps2Encoder {
+    /// Create an encoder for `protocol`
+    pub fn new(protocol: Ps2Protocol) -> Self {
+        Self {
+            protocol,
+            buttons: ButtonSet::EMPTY,
+        }
+    }
+
+    /// Get the protocol being encoded
+    pub fn protocol(&self) -> Ps2Protocol {
+        self.protocol
+    }
+
+    /// Encode an event into as many packets as its values need
+    ///
+    /// Events the protocol cannot carry produce no bytes.
+    pub fn encode(&mut self, event: &MouseEvent) -> Vec<u8> {
+        if !self.protocol.supports(event) {
+            return Vec::new();
+        }
+        let packets = Packet::split(&mut self.buttons, event, MOTION_LIMIT, WHEEL_LIMIT);
+        packets
+            .iter()
+            .flat_map(|packet| self.encode_packet(packet))
+            .collect()
+    }
+
+    /// Encode one packet, clamping values that do not fit
+    pub fn encode_packet(&self, packet: &Packet) -> Vec<u8> {
+        let buttons = packet.buttons;
+        let x = packet.dx.clamp(-256, 255);
+        let y = (-packet.dy).clamp(-256, 255);
+
+        let mut header = 0x08;
+        header |= u8::from(buttons.contains(Button::Left));
+        header |= u8::from(buttons.contains(Button::Right)) << 1;
+        header |= u8::from(buttons.contains(Button::Middle)) << 2;
+        header |= u8::from(x < 0) << 4;
+        header |= u8::from(y < 0) << 5;
+
+        // 截断为低 8 位，符号位已写入包头
+        let mut bytes = Vec::with_capacity(self.protocol.packet_size());
+        bytes.extend_from_slice(&[header, x as u8, y as u8]);
+
+        match self.protocol {
+            Ps2Protocol::Standard => {}
+            Ps2Protocol::IntelliMouse => bytes.push((-packet.wheel).clamp(-8, 7) as u8),
+            Ps2Protocol::Explorer if packet.hwheel != 0 => {
+                bytes.push(0x40 | ((-packet.hwheel).clamp(-32, 31) as u8 & 0x3F));
+            }
+            Ps2Protocol::Explorer => {
+                let mut extra = (-packet.wheel).clamp(-8, 7) as u8 & 0x0F;
+                extra |= u8::from(buttons.contains(Button::X1)) << 4;
+                extra |= u8::from(buttons.contains(Button::X2)) << 5;
+                bytes.push(extra);
+            }
+        }
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(protocol: Ps2Protocol, events: &[MouseEvent]) {
+        let mut encoder = Ps2Encoder::new(protocol);
+        let bytes: Vec<u8> = events.iter().flat_map(|e| encoder.encode(e)).collect();
+        assert_eq!(
+            Ps2Decoder::new(protocol).decode(&bytes),
+            events,
+            "{:?}",
+            protocol
+        );
+    }
+
+    #[test]
+    fn events_round_trip() {
+        let common = [
+            MouseEvent::Press(Button::Left),
+            MouseEvent::RelativeMove(255, -255),
+            MouseEvent::RelativeMove(-255, 17),
+            MouseEvent::Press(Button::Middle),
+            MouseEvent::Release(Button::Left),
+            MouseEvent::Release(Button::Middle),
+        ];
+        round_trip(Ps2Protocol::Standard, &common);
+
+        let mut wheel = common.to_vec();
+        wheel.extend([
+            MouseEvent::Scroll(ScrollDirection::VerticalUp, 7),
+            MouseEvent::Scroll(ScrollDirection::VerticalDown, 3),
+        ]);
+        round_trip(Ps2Protocol::IntelliMouse, &wheel);
+
+        let mut explorer = wheel;
+        explorer.extend([
+            MouseEvent::Press(Button::X1),
+            MouseEvent::Press(Button::X2),
+            MouseEvent::Release(Button::X1),
+            MouseEvent::Scroll(ScrollDirection::HorizontalLeft, 2),
+            MouseEvent::Release(Button::X2),
+        ]);
+        round_trip(Ps2Protocol::Explorer, &explorer);
+    }
+
+    #[test]
+    fn motion_uses_nine_bit_twos_complement() {
+        let mut decoder = Ps2Decoder::new(Ps2Protocol::Standard);
+        // X 符号位为 bit 4，Y 符号位为 bit 5；Y 向上为正
+        let cases = [
+            ([0x08, 0xFF, 0x00], (255, 0)),
+            ([0x18, 0x00, 0x00], (-256, 0)),
+            ([0x18, 0xFF, 0x00], (-1, 0)),
+            ([0x28, 0x00, 0xFD], (0, 3)),
+            ([0x08, 0x00, 0x05], (0, -5)),
+            ([0x38, 0x80, 0x00], (-128, 256)),
+        ];
+        for (bytes, (dx, dy)) in cases {
+            let packet = bytes.iter().find_map(|&b| decoder.push(b)).unwrap();
+            assert_eq!((packet.dx, packet.dy), (dx, dy), "{:02x?}", bytes);
+            assert_eq!(
+                Ps2Encoder::new(Ps2Protocol::Standard).encode_packet(&packet),
+                bytes
+            );
+        }
+    }
+
+    #[test]
+    fn explorer_fourth_byte() {
+        let decode = |extra: u8| {
+            let mut decoder = Ps2Decoder::new(Ps2Protocol::Explorer);
+            decoder.decode(&[0x08, 0x00, 0x00, extra])
+        };
+        // 低 4 位为滚轮（向下为正），bit 4/5 为按钮 4/5
+        assert_eq!(
+            decode(0x01),
+            [MouseEvent::Scroll(ScrollDirection::VerticalDown, 1)]
+        );
+        assert_eq!(
+            decode(0x0F),
+            [MouseEvent::Scroll(ScrollDirection::VerticalUp, 1)]
+        );
+        assert_eq!(
+            decode(0x38),
+            [
+                MouseEvent::Press(Button::X1),
+                MouseEvent::Press(Button::X2),
+                MouseEvent::Scroll(ScrollDirection::VerticalUp, 8),
+            ]
+        );
+        // bit 7 或 bit 6 置位时为 6 位垂直或水平滚轮
+        assert_eq!(
+            decode(0x81),
+            [MouseEvent::Scroll(ScrollDirection::VerticalDown, 1)]
+        );
+        assert_eq!(
+            decode(0x7E),
+            [MouseEvent::Scroll(ScrollDirection::HorizontalRight, 2)]
+        );
+        assert_eq!(
+            Ps2Decoder::new(Ps2Protocol::IntelliMouse).decode(&[0x08, 0x00, 0x00, 0xFE]),
+            [MouseEvent::Scroll(ScrollDirection::VerticalUp, 2)]
+        );
+    }
+
+    #[test]
+    fn resynchronizes_on_header() {
+        let mut decoder = Ps2Decoder::new(Ps2Protocol::Standard);
+        // 前两个字节不是包头（bit 3 未置位或溢出位置位），被跳过
+        assert_eq!(
+            decoder.decode(&[0x05, 0xC9, 0x09, 0x01, 0x00]),
+            [
+                MouseEvent::Press(Button::Left),
+                MouseEvent::RelativeMove(1, 0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,472 @@
+//! RDP mouse input events
+//!
+//! TS_POINTER_EVENT and TS_POINTERX_EVENT share one layout: little-endian
+//! `pointerFlags`, `xPos` and `yPos`. Mouse events carry the left, right and
+//! middle buttons (`PTRFLAGS_BUTTON1` to `PTRFLAGS_BUTTON3`) with
+//! `PTRFLAGS_DOWN` for presses, `PTRFLAGS_MOVE` for motion, and wheel rotation
+//! as a 9-bit two's complement value whose sign bit is
+//! `PTRFLAGS_WHEEL_NEGATIVE`, in units of `WHEEL_DELTA` per detent. Extended
+//! mouse events carry the X buttons.
+//!
+//! On the fast path an event follows a one-byte header holding the event
+//! code in its top three bits; on the slow path it follows a 4-byte event
+//! time and a 2-byte message type.
+
+use alloc::vec::Vec;
+
+use super::sign_extend;
+use crate::types::{Button, MouseEvent, ScrollDirection};
+
+/// Horizontal wheel rotation
+pub const PTRFLAGS_HWHEEL: u16 = 0x0400;
+/// Vertical wheel rotation
+pub const PTRFLAGS_WHEEL: u16 = 0x0200;
+/// Sign bit of the wheel rotation
+pub const PTRFLAGS_WHEEL_NEGATIVE: u16 = 0x0100;
+/// Bits holding the wheel rotation, including its sign
+pub const WHEEL_ROTATION_MASK: u16 = 0x01FF;
+/// Pointer motion
+pub const PTRFLAGS_MOVE: u16 = 0x0800;
+/// Button pressed rather than released
+pub const PTRFLAGS_DOWN: u16 = 0x8000;
+/// Left button
+pub const PTRFLAGS_BUTTON1: u16 = 0x1000;
+/// Right button
+pub const PTRFLAGS_BUTTON2: u16 = 0x2000;
+/// Middle button
+pub const PTRFLAGS_BUTTON3: u16 = 0x4000;
+/// Extended button pressed rather than released
+pub const PTRXFLAGS_DOWN: u16 = 0x8000;
+/// First extended button (back)
+pub const PTRXFLAGS_BUTTON1: u16 = 0x0001;
+/// Second extended button (forward)
+pub const PTRXFLAGS_BUTTON2: u16 = 0x0002;
+
+/// Wheel rotation of one detent
+pub const WHEEL_DELTA: i32 = 120;
+
+/// Slow-path message type of TS_POINTER_EVENT
+pub const INPUT_EVENT_MOUSE: u16 = 0x8001;
+/// Slow-path message type of TS_POINTERX_EVENT
+pub const INPUT_EVENT_MOUSEX: u16 = 0x8002;
+/// Fast-path event code of TS_POINTER_EVENT
+pub const FASTPATH_INPUT_EVENT_MOUSE: u8 = 0x1;
+/// Fast-path event code of TS_POINTERX_EVENT
+pub const FASTPATH_INPUT_EVENT_MOUSEX: u8 = 0x2;
+
+/// Kind of pointer event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PointerKind {
+    /// TS_POINTER_EVENT: motion, wheels and the three main buttons
+    Mouse,
+    /// TS_POINTERX_EVENT: the X buttons
+    MouseX,
+}
+
+impl PointerKind {
+    /// Slow-path message type
+    pub const fn message_type(&self) -> u16 {
+        match self {
+            PointerKind::Mouse => INPUT_EVENT_MOUSE,
+            PointerKind::MouseX => INPUT_EVENT_MOUSEX,
+        }
+    }
+
+    /// Fast-path event code
+    pub const fn fast_path_code(&self) -> u8 {
+        match self {
+            PointerKind::Mouse => FASTPATH_INPUT_EVENT_MOUSE,
+            PointerKind::MouseX => FASTPATH_INPUT_EVENT_MOUSEX,
+        }
+    }
+
+    /// Get the kind of a slow-path message type
+    pub const fn from_message_type(message_type: u16) -> Option<Self> {
+        match message_type {
+            INPUT_EVENT_MOUSE => Some(PointerKind::Mouse),
+            INPUT_EVENT_MOUSEX => Some(PointerKind::MouseX),
+            _ => None,
+        }
+    }
+
+    /// Get the kind of a fast-path event code
+    pub const fn from_fast_path_code(code: u8) -> Option<Self> {
+        match code {
+            FASTPATH_INPUT_EVENT_MOUSE => Some(PointerKind::Mouse),
+            FASTPATH_INPUT_EVENT_MOUSEX => Some(PointerKind::MouseX),
+            _ => None,
+        }
+    }
+}
+
+/// One TS_POINTER_EVENT or TS_POINTERX_EVENT
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PointerEvent {
+    /// Event kind, which selects the meaning of `flags`
+    pub kind: PointerKind,
+    /// `pointerFlags`
+    pub flags: u16,
+    /// Horizontal position
+    pub x: u16,
+    /// Vertical position
+    pub y: u16,
+}
+
+impl PointerEvent {
+    /// Size of the event body in bytes
+    pub const SIZE: usize = 6;
+    /// Size of a fast-path event in bytes
+    pub const FAST_PATH_SIZE: usize = 7;
+    /// Size of a slow-path event in bytes
+    pub const SLOW_PATH_SIZE: usize = 12;
+
+    /// Create a pointer event
+    pub const fn new(kind: PointerKind, flags: u16, x: u16, y: u16) -> Self {
+        Self { kind, flags, x, y }
+    }
+
+    /// Get the signed wheel rotation, if this is a wheel event
+    ///
+    /// Returns the rotation and whether it is horizontal. Positive values
+    /// scroll up or right.
+    pub const fn wheel_rotation(&self) -> Option<(i32, bool)> {
+        if !matches!(self.kind, PointerKind::Mouse)
+            || self.flags & (PTRFLAGS_WHEEL | PTRFLAGS_HWHEEL) == 0
+        {
+            return None;
+        }
+        let rotation = sign_extend((self.flags & WHEEL_ROTATION_MASK) as u32, 9);
+        Some((rotation, self.flags & PTRFLAGS_HWHEEL != 0))
+    }
+
+    /// Get the button of this event and whether it is pressed
+    pub const fn button(&self) -> Option<(Button, bool)> {
+        let flags = self.flags;
+        let button = match self.kind {
+            PointerKind::Mouse if flags & (PTRFLAGS_WHEEL | PTRFLAGS_HWHEEL) != 0 => None,
+            PointerKind::Mouse if flags & PTRFLAGS_BUTTON1 != 0 => Some(Button::Left),
+            PointerKind::Mouse if flags & PTRFLAGS_BUTTON2 != 0 => Some(Button::Right),
+            PointerKind::Mouse if flags & PTRFLAGS_BUTTON3 != 0 => Some(Button::Middle),
+            PointerKind::MouseX if flags & PTRXFLAGS_BUTTON1 != 0 => Some(Button::X1),
+            PointerKind::MouseX if flags & PTRXFLAGS_BUTTON2 != 0 => Some(Button::X2),
+            _ => None,
+        };
+        // PTRFLAGS_DOWN 与 PTRXFLAGS_DOWN 取值相同
+        match button {
+            Some(button) => Some((button, flags & PTRFLAGS_DOWN != 0)),
+            None => None,
+        }
+    }
+
+    /// Parse the 6-byte event body
+    pub fn from_bytes(kind: PointerKind, bytes: &[u8]) -> Option<Self> {
+        let [f0, f1, x0, x1, y0, y1, ..] = *bytes else {
+            return None;
+        };
+        Some(Self::new(
+            kind,
+            u16::from_le_bytes([f0, f1]),
+            u16::from_le_bytes([x0, x1]),
+            u16::from_le_bytes([y0, y1]),
+        ))
+    }
+
+    /// Serialize the 6-byte event body
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let [f0, f1] = self.flags.to_le_bytes();
+        let [x0, x1] = self.x.to_le_bytes();
+        let [y0, y1] = self.y.to_le_bytes();
+        [f0, f1, x0, x1, y0, y1]
+    }
+
+    /// Parse a fast-path input event
+    ///
+    /// Returns `None` for other event codes or truncated input.
+    pub fn from_fast_path(bytes: &[u8]) -> Option<Self> {
+        let (&header, body) = bytes.split_first()?;
+        // 高 3 位为事件代码，低 5 位为事件标志
+        let kind = PointerKind::from_fast_path_code(header >> 5)?;
+        Self::from_bytes(kind, body)
+    }
+
+    /// Serialize as a fast-path input event
+    pub fn to_fast_path(&self) -> [u8; Self::FAST_PATH_SIZE] {
+        let [f0, f1, x0, x1, y0, y1] = self.to_bytes();
+        [self.kind.fast_path_code() << 5, f0, f1, x0, x1, y0, y1]
+    }
+
+    /// Parse a slow-path TS_INPUT_EVENT, returning its event time too
+    ///
+    /// Returns `None` for other message types or truncated input.
+    pub fn from_slow_path(bytes: &[u8]) -> Option<(u32, Self)> {
+        let [t0, t1, t2, t3, m0, m1, ..] = *bytes else {
+            return None;
+        };
+        let kind = PointerKind::from_message_type(u16::from_le_bytes([m0, m1]))?;
+        let event = Self::from_bytes(kind, &bytes[6..])?;
+        Some((u32::from_le_bytes([t0, t1, t2, t3]), event))
+    }
+
+    /// Serialize as a slow-path TS_INPUT_EVENT
+    pub fn to_slow_path(&self, time: u32) -> [u8; Self::SLOW_PATH_SIZE] {
+        let [t0, t1, t2, t3] = time.to_le_bytes();
+        let [m0, m1] = self.kind.message_type().to_le_bytes();
+        let [f0, f1, x0, x1, y0, y1] = self.to_bytes();
+        [t0, t1, t2, t3, m0, m1, f0, f1, x0, x1, y0, y1]
+    }
+}
+
+/// Stateful pointer event to `MouseEvent` translator
+///
+/// Wheel rotation below one detent, as sent by high-resolution wheels and
+/// touchpads, is carried over until it adds up to whole detents.
+#[derive(Debug, Clone, Default)]
+pub struct RdpDecoder {
+    position: Option<(u16, u16)>,
+    wheel: i32,
+    hwheel: i32,
+}
+
+impl RdpDecoder {
+    /// Create a decoder with no known position
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the position of the last positioned event
+    pub fn position(&self) -> Option<(u16, u16)> {
+        self.position
+    }
+
+    /// Translate one event
+    ///
+    /// Button events at a new position are preceded by a `Move` there, so
+    /// the click lands where the client sent it. Wheel events ignore the
+    /// position, as the protocol specifies.
+    pub fn decode(&mut self, event: &PointerEvent) -> Vec<MouseEvent> {
+        let mut events = Vec::new();
+
+        if let Some((rotation, horizontal)) = event.wheel_rotation() {
+            let remainder = if horizontal {
+                &mut self.hwheel
+            } else {
+                &mut self.wheel
+            };
+            *remainder += rotation;
+            let detents = *remainder / WHEEL_DELTA;
+            *remainder %= WHEEL_DELTA;
+            if detents != 0 {
+                let direction = match (horizontal, detents > 0) {
+                    (false, true) => ScrollDirection::VerticalUp,
+                    (false, false) => ScrollDirection::VerticalDown,
+                    (true, true) => ScrollDirection::HorizontalRight,
+                    (true, false) => ScrollDirection::HorizontalLeft,
+                };
+                events.push(MouseEvent::Scroll(direction, detents.abs()));
+            }
+            return events;
+        }
+
+        let position = (event.x, event.y);
+        let moved = event.kind == PointerKind::Mouse && event.flags & PTRFLAGS_MOVE != 0;
+        if moved || self.position != Some(position) {
+            events.push(MouseEvent::Move(event.x.into(), event.y.into()));
+            self.position = Some(position);
+        }
+
+        match event.button() {
+            Some((button, true)) => events.push(MouseEvent::Press(button)),
+            Some((button, false)) => events.push(MouseEvent::Release(button)),
+            None => {}
+        }
+        events
+    }
+}
+
+/// `MouseEvent` to pointer event translator tracking the position
+#[derive(Debug, Clone, Default)]
+pub struct RdpEncoder {
+    x: u16,
+    y: u16,
+}
+
+impl RdpEncoder {
+    /// Create an encoder at position (0, 0)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the position used by button events
+    pub fn position(&self) -> (u16, u16) {
+        (self.x, self.y)
+    }
+
+    /// Set the position used by following events without sending one
+    pub fn set_position(&mut self, x: u16, y: u16) {
+        self.x = x;
+        self.y = y;
+    }
+
+    /// Translate an event into pointer events
+    ///
+    /// Positions are clamped to the `u16` range and relative moves applied
+    /// to the current position. Scrolls become one event per detent, as
+    /// Windows clients send them, for at most `MouseEvent::MAX_SCROLL_DETENTS`
+    /// detents.
+    /// Buttons other than the left, right,
+    /// middle and X buttons produce no events.
+    pub fn encode(&mut self, event: &MouseEvent) -> Vec<PointerEvent> {
+        match *event {
+            MouseEvent::Press(button) | MouseEvent::Release(button) => {
+                let (kind, flag, down) = match button {
+                    Button::Left => (PointerKind::Mouse, PTRFLAGS_BUTTON1, PTRFLAGS_DOWN),
+                    Button::Right => (PointerKind::Mouse, PTRFLAGS_BUTTON2, PTRFLAGS_DOWN),
+                    Button::Middle => (PointerKind::Mouse, PTRFLAGS_BUTTON3, PTRFLAGS_DOWN),
+                    Button::X1 => (PointerKind::MouseX, PTRXFLAGS_BUTTON1, PTRXFLAGS_DOWN),
+                    Button::X2 => (PointerKind::MouseX, PTRXFLAGS_BUTTON2, PTRXFLAGS_DOWN),
+                    _ => return Vec::new(),
+                };
+                let flags = match event {
+                    MouseEvent::Press(_) => flag | down,
+                    _ => flag,
+                };
+                [PointerEvent::new(kind, flags, self.x, self.y)].into()
+            }
+            MouseEvent::Move(x, y) => {
+                self.set_position(clamp(x), clamp(y));
+                [self.motion()].into()
+            }
+            MouseEvent::RelativeMove(dx, dy) => {
+                self.set_position(
+                    clamp(i32::from(self.x).saturating_add(dx)),
+                    clamp(i32::from(self.y).saturating_add(dy)),
+                );
+                [self.motion()].into()
+            }
+            MouseEvent::Scroll(direction, amount) => {
+                let (axis, rotation) = match direction {
+                    ScrollDirection::VerticalUp => (PTRFLAGS_WHEEL, WHEEL_DELTA),
+                    ScrollDirection::VerticalDown => (PTRFLAGS_WHEEL, -WHEEL_DELTA),
+                    ScrollDirection::HorizontalRight => (PTRFLAGS_HWHEEL, WHEEL_DELTA),
+                    ScrollDirection::HorizontalLeft => (PTRFLAGS_HWHEEL, -WHEEL_DELTA),
+                };
+                // 截断为 9 位补码，负数会自动带上 PTRFLAGS_WHEEL_NEGATIVE
+                let flags = axis | (rotation as u16 & WHEEL_ROTATION_MASK);
+                let event = PointerEvent::new(PointerKind::Mouse, flags, self.x, self.y);
+                (0..amount.clamp(0, MouseEvent::MAX_SCROLL_DETENTS))
+                    .map(|_| event)
+                    .collect()
+            }
+        }
+    }
+
+    fn motion(&self) -> PointerEvent {
+        PointerEvent::new(PointerKind::Mouse, PTRFLAGS_MOVE, self.x, self.y)
+    }
+}
+
+/// Clamp a coordinate to the range of a pointer event position
+fn clamp(value: i32) -> u16 {
+    value.clamp(0, i32::from(u16::MAX)) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_round_trip() {
+        let events = [
+            MouseEvent::Move(10, 20),
+            MouseEvent::Press(Button::Left),
+            MouseEvent::Move(300, 400),
+            MouseEvent::Release(Button::Left),
+            MouseEvent::Press(Button::X1),
+            MouseEvent::Scroll(ScrollDirection::VerticalDown, 1),
+            MouseEvent::Scroll(ScrollDirection::HorizontalRight, 1),
+            MouseEvent::Press(Button::X2),
+            MouseEvent::Release(Button::X1),
+            MouseEvent::Release(Button::X2),
+        ];
+        let mut encoder = RdpEncoder::new();
+        let mut decoder = RdpDecoder::new();
+        let mut fast = Vec::new();
+        for message in events.iter().flat_map(|event| encoder.encode(event)) {
+            let parsed = PointerEvent::from_fast_path(&message.to_fast_path()).unwrap();
+            fast.extend(decoder.decode(&parsed));
+            assert_eq!(
+                PointerEvent::from_slow_path(&message.to_slow_path(7)),
+                Some((7, message))
+            );
+        }
+        assert_eq!(fast, events);
+    }
+
+    #[test]
+    fn negative_wheel_rotation() {
+        // 0x188 为 9 位补码的 -120，符号位即 PTRFLAGS_WHEEL_NEGATIVE
+        let flags = PTRFLAGS_WHEEL | PTRFLAGS_WHEEL_NEGATIVE | 0x88;
+        let event = PointerEvent::new(PointerKind::Mouse, flags, 0, 0);
+        assert_eq!(event.wheel_rotation(), Some((-120, false)));
+        assert_eq!(event.button(), None);
+        assert_eq!(
+            RdpDecoder::new().decode(&event),
+            [MouseEvent::Scroll(ScrollDirection::VerticalDown, 1)]
+        );
+        assert_eq!(
+            RdpEncoder::new().encode(&MouseEvent::Scroll(ScrollDirection::VerticalDown, 1)),
+            [event]
+        );
+        let up = PointerEvent::new(PointerKind::Mouse, PTRFLAGS_WHEEL | 0x78, 0, 0);
+        assert_eq!(up.wheel_rotation(), Some((120, false)));
+
+        // 超大的滚动量被截断
+        let huge =
+            RdpEncoder::new().encode(&MouseEvent::Scroll(ScrollDirection::VerticalUp, i32::MAX));
+        assert_eq!(huge.len(), MouseEvent::MAX_SCROLL_DETENTS as usize);
+        assert!(huge.iter().all(|event| *event == up));
+    }
+
+    #[test]
+    fn partial_wheel_rotation_carries_over() {
+        let mut decoder = RdpDecoder::new();
+        let step = PointerEvent::new(PointerKind::Mouse, PTRFLAGS_HWHEEL | 40, 0, 0);
+        assert_eq!(decoder.decode(&step), []);
+        assert_eq!(decoder.decode(&step), []);
+        assert_eq!(
+            decoder.decode(&step),
+            [MouseEvent::Scroll(ScrollDirection::HorizontalRight, 1)]
+        );
+    }
+
+    #[test]
+    fn wire_layouts() {
+        let press = PointerEvent::new(
+            PointerKind::Mouse,
+            PTRFLAGS_DOWN | PTRFLAGS_BUTTON1,
+            100,
+            200,
+        );
+        let fast = [0x20, 0x00, 0x90, 0x64, 0x00, 0xC8, 0x00];
+        assert_eq!(press.to_fast_path(), fast);
+        assert_eq!(PointerEvent::from_fast_path(&fast), Some(press));
+        assert_eq!(press.button(), Some((Button::Left, true)));
+
+        let forward = PointerEvent::new(PointerKind::MouseX, PTRXFLAGS_BUTTON2, 0, 0);
+        let slow = [0x04, 0x03, 0x02, 0x01, 0x02, 0x80, 0x02, 0x00, 0, 0, 0, 0];
+        assert_eq!(forward.to_slow_path(0x0102_0304), slow);
+        assert_eq!(
+            PointerEvent::from_slow_path(&slow),
+            Some((0x0102_0304, forward))
+        );
+        assert_eq!(forward.button(), Some((Button::X2, false)));
+
+        // 截断的输入或未知事件代码
+        assert_eq!(PointerEvent::from_fast_path(&fast[..6]), None);
+        assert_eq!(
+            PointerEvent::from_fast_path(&[0x60, 0, 0, 0, 0, 0, 0]),
+            None
+        );
+        assert_eq!(PointerEvent::from_slow_path(&slow[..11]), None);
+    }
+}
@@ -0,0 +1,403 @@
+//! RFB (VNC) PointerEvent messages
+//!
+//! A PointerEvent is a message type byte of 5, a button mask byte and the
+//! big-endian absolute X and Y position. Mask bits 0 to 2 are the left,
+//! middle and right buttons; bits 3 and 4 are the wheel up and down and bits
+//! 5 and 6 the wheel left and right, each detent sent as a press followed by
+//! a release. When the server announces the ExtendedMouseButtons
+//! pseudo-encoding a client may set bit 7 and append a seventh byte whose
+//! bits 0 and 1 are the back and forward buttons.
+//!
+//! `PointerEvent::mask` holds the combined 9-bit mask, with back and forward
+//! in bits 7 and 8.
+
+use alloc::vec::Vec;
+
+use crate::types::{Button, ButtonSet, MouseEvent, ScrollDirection};
+
+/// Message type of PointerEvent
+pub const POINTER_EVENT: u8 = 5;
+/// Pseudo-encoding number announcing ExtendedMouseButtons support
+pub const EXTENDED_MOUSE_BUTTONS: i32 = -316;
+
+/// Left button mask bit
+pub const BUTTON_LEFT: u16 = 1 << 0;
+/// Middle button mask bit
+pub const BUTTON_MIDDLE: u16 = 1 << 1;
+/// Right button mask bit
+pub const BUTTON_RIGHT: u16 = 1 << 2;
+/// Wheel up mask bit
+pub const WHEEL_UP: u16 = 1 << 3;
+/// Wheel down mask bit
+pub const WHEEL_DOWN: u16 = 1 << 4;
+/// Wheel left mask bit
+pub const WHEEL_LEFT: u16 = 1 << 5;
+/// Wheel right mask bit
+pub const WHEEL_RIGHT: u16 = 1 << 6;
+/// Back button mask bit, sent with ExtendedMouseButtons
+pub const BUTTON_BACK: u16 = 1 << 7;
+/// Forward button mask bit, sent with ExtendedMouseButtons
+pub const BUTTON_FORWARD: u16 = 1 << 8;
+
+/// Buttons that have a mask bit
+const BUTTONS: [(Button, u16); 5] = [
+    (Button::Left, BUTTON_LEFT),
+    (Button::Middle, BUTTON_MIDDLE),
+    (Button::Right, BUTTON_RIGHT),
+    (Button::X1, BUTTON_BACK),
+    (Button::X2, BUTTON_FORWARD),
+];
+
+/// Wheel directions and their mask bits
+const WHEELS: [(ScrollDirection, u16); 4] = [
+    (ScrollDirection::VerticalUp, WHEEL_UP),
+    (ScrollDirection::VerticalDown, WHEEL_DOWN),
+    (ScrollDirection::HorizontalLeft, WHEEL_LEFT),
+    (ScrollDirection::HorizontalRight, WHEEL_RIGHT),
+];
+
+/// Get the mask bit of `button`, if it has one
+const fn button_bit(button: Button) -> Option<u16> {
+    match button {
+        Button::Left => Some(BUTTON_LEFT),
+        Button::Middle => Some(BUTTON_MIDDLE),
+        Button::Right => Some(BUTTON_RIGHT),
+        Button::X1 => Some(BUTTON_BACK),
+        Button::X2 => Some(BUTTON_FORWARD),
+        _ => None,
+    }
+}
+
+/// Get the mask bit of a wheel direction
+const fn wheel_bit(direction: ScrollDirection) -> u16 {
+    match direction {
+        ScrollDirection::VerticalUp => WHEEL_UP,
+        ScrollDirection::VerticalDown => WHEEL_DOWN,
+        ScrollDirection::HorizontalLeft => WHEEL_LEFT,
+        ScrollDirection::HorizontalRight => WHEEL_RIGHT,
+    }
+}
+
+/// One PointerEvent message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PointerEvent {
+    /// Combined button mask, with back and forward in bits 7 and 8
+    pub mask: u16,
+    /// Absolute horizontal position
+    pub x: u16,
+    /// Absolute vertical position
+    pub y: u16,
+}
+
+impl PointerEvent {
+    /// Create a PointerEvent
+    pub const fn new(mask: u16, x: u16, y: u16) -> Self {
+        Self { mask, x, y }
+    }
+
+    /// Get the buttons held in this message
+    pub fn buttons(&self) -> ButtonSet {
+        BUTTONS
+            .iter()
+            .filter(|(_, bit)| self.mask & bit != 0)
+            .map(|(button, _)| *button)
+            .collect()
+    }
+
+    /// Parse a message from the start of `bytes`
+    ///
+    /// Returns the message and the number of bytes it used, or `None` when
+    /// `bytes` does not start with a complete PointerEvent. With `extended`
+    /// false, bit 7 of the mask is ignored and no seventh byte is read.
+    pub fn from_bytes(bytes: &[u8], extended: bool) -> Option<(Self, usize)> {
+        let [kind, mask, x0, x1, y0, y1, ..] = *bytes else {
+            return None;
+        };
+        if kind != POINTER_EVENT {
+            return None;
+        }
+        let x = u16::from_be_bytes([x0, x1]);
+        let y = u16::from_be_bytes([y0, y1]);
+
+        if extended && mask & 0x80 != 0 {
+            let extra = *bytes.get(6)?;
+            let mask = u16::from(mask & 0x7F) | u16::from(extra) << 7;
+            Some((Self::new(mask, x, y), 7))
+        } else {
+            Some((Self::new(u16::from(mask & 0x7F), x, y), 6))
+        }
+    }
+
+    /// Serialize the message
+    ///
+    /// With `extended` false the back and forward bits are dropped, since a
+    /// server without ExtendedMouseButtons would misread the seventh byte.
+    pub fn to_bytes(&self, extended: bool) -> Vec<u8> {
+        let [x0, x1] = self.x.to_be_bytes();
+        let [y0, y1] = self.y.to_be_bytes();
+        // 低 7 位直接写入掩码字节，扩展按钮移入第 7 个字节
+        let low = (self.mask & 0x7F) as u8;
+        let high = (self.mask >> 7) as u8;
+        if extended && high != 0 {
+            [POINTER_EVENT, 0x80 | low, x0, x1, y0, y1, high].into()
+        } else {
+            [POINTER_EVENT, low, x0, x1, y0, y1].into()
+        }
+    }
+}
+
+/// Stateful PointerEvent to `MouseEvent` translator
+///
+/// RFB only sends the current mask and position, so presses, releases,
+/// moves and wheel detents are found by comparing each message with the
+/// previous one.
+#[derive(Debug, Clone, Default)]
+pub struct RfbDecoder {
+    mask: u16,
+    position: Option<(u16, u16)>,
+}
+
+impl RfbDecoder {
+    /// Create a decoder with no buttons held and no known position
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the buttons held according to the last message
+    pub fn buttons(&self) -> ButtonSet {
+        PointerEvent::new(self.mask, 0, 0).buttons()
+    }
+
+    /// Get the position of the last message
+    pub fn position(&self) -> Option<(u16, u16)> {
+        self.position
+    }
+
+    /// Translate one message into the events it implies
+    ///
+    /// A changed position comes first as a `Move`, followed by button edges
+    /// and one `Scroll` per wheel bit going from clear to set.
+    pub fn decode(&mut self, message: &PointerEvent) -> Vec<MouseEvent> {
+        let mut events = Vec::new();
+        let position = (message.x, message.y);
+        if self.position != Some(position) {
+            events.push(MouseEvent::Move(message.x.into(), message.y.into()));
+            self.position = Some(position);
+        }
+
+        let previous = self.buttons();
+        events.extend(previous.changes(message.buttons()));
+
+        let pressed = message.mask & !self.mask;
+        for (direction, bit) in WHEELS {
+            if pressed & bit != 0 {
+                events.push(MouseEvent::Scroll(direction, 1));
+            }
+        }
+
+        self.mask = message.mask;
+        events
+    }
+}
+
+/// `MouseEvent` to PointerEvent translator tracking the mask and position
+#[derive(Debug, Clone)]
+pub struct RfbEncoder {
+    mask: u16,
+    x: u16,
+    y: u16,
+    extended: bool,
+}
+
+impl RfbEncoder {
+    /// Create an encoder at position (0, 0)
+    ///
+    /// `extended` tells whether the server announced ExtendedMouseButtons;
+    /// without it back and forward cannot be sent.
+    pub fn new(extended: bool) -> Self {
+        Self {
+            mask: 0,
+            x: 0,
+            y: 0,
+            extended,
+        }
+    }
+
+    /// Get the position of the last message
+    pub fn position(&self) -> (u16, u16) {
+        (self.x, self.y)
+    }
+
+    /// Set the position used by following messages without sending one
+    pub fn set_position(&mut self, x: u16, y: u16) {
+        self.x = x;
+        self.y = y;
+    }
+
+    /// Translate an event into messages
+    ///
+    /// Positions are clamped to the `u16` range and relative moves applied
+    /// to the current position. Each wheel detent becomes a press and a
+    /// release message, for at most `MouseEvent::MAX_SCROLL_DETENTS` detents.
+    /// Buttons without a mask bit produce no messages.
+    pub fn encode(&mut self, event: &MouseEvent) -> Vec<PointerEvent> {
+        match *event {
+            MouseEvent::Press(button) | MouseEvent::Release(button) => {
+                let Some(bit) = button_bit(button).filter(|bit| self.supports(*bit)) else {
+                    return Vec::new();
+                };
+                if matches!(event, MouseEvent::Press(_)) {
+                    self.mask |= bit;
+                } else {
+                    self.mask &= !bit;
+                }
+                [self.message(self.mask)].into()
+            }
+            MouseEvent::Move(x, y) => {
+                self.set_position(clamp(x), clamp(y));
+                [self.message(self.mask)].into()
+            }
+            MouseEvent::RelativeMove(dx, dy) => {
+                self.set_position(
+                    clamp(i32::from(self.x).saturating_add(dx)),
+                    clamp(i32::from(self.y).saturating_add(dy)),
+                );
+                [self.message(self.mask)].into()
+            }
+            MouseEvent::Scroll(direction, amount) => {
+                let bit = wheel_bit(direction);
+                let pair = [self.message(self.mask | bit), self.message(self.mask)];
+                (0..amount.clamp(0, MouseEvent::MAX_SCROLL_DETENTS))
+                    .flat_map(|_| pair)
+                    .collect()
+            }
+        }
+    }
+
+    /// Translate an event into serialized messages
+    pub fn encode_bytes(&mut self, event: &MouseEvent) -> Vec<u8> {
+        self.encode(event)
+            .iter()
+            .flat_map(|message| message.to_bytes(self.extended))
+            .collect()
+    }
+
+    fn supports(&self, bit: u16) -> bool {
+        self.extended || bit < BUTTON_BACK
+    }
+
+    fn message(&self, mask: u16) -> PointerEvent {
+        PointerEvent::new(mask, self.x, self.y)
+    }
+}
+
+/// Clamp a coordinate to the range of a PointerEvent position
+fn clamp(value: i32) -> u16 {
+    value.clamp(0, i32::from(u16::MAX)) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encode events, parse the bytes back and decode the messages
+    fn round_trip(extended: bool, events: &[MouseEvent]) -> Vec<MouseEvent> {
+        let mut encoder = RfbEncoder::new(extended);
+        let bytes: Vec<u8> = events
+            .iter()
+            .flat_map(|e| encoder.encode_bytes(e))
+            .collect();
+        let mut decoder = RfbDecoder::new();
+        let mut decoded = Vec::new();
+        let mut rest = &bytes[..];
+        while !rest.is_empty() {
+            let (message, used) = PointerEvent::from_bytes(rest, extended).unwrap();
+            decoded.extend(decoder.decode(&message));
+            rest = &rest[used..];
+        }
+        decoded
+    }
+
+    #[test]
+    fn events_round_trip() {
+        let events = [
+            MouseEvent::Move(10, 20),
+            MouseEvent::Press(Button::Left),
+            MouseEvent::Move(300, 400),
+            MouseEvent::Press(Button::X1),
+            MouseEvent::Scroll(ScrollDirection::VerticalDown, 1),
+            MouseEvent::Release(Button::Left),
+            MouseEvent::Press(Button::X2),
+            MouseEvent::Scroll(ScrollDirection::HorizontalRight, 1),
+            MouseEvent::Release(Button::X1),
+            MouseEvent::Release(Button::X2),
+        ];
+        assert_eq!(round_trip(true, &events), events);
+
+        // 未启用 ExtendedMouseButtons 时无法发送后退和前进
+        let basic: Vec<MouseEvent> = events
+            .iter()
+            .copied()
+            .filter(|event| {
+                !matches!(
+                    event,
+                    MouseEvent::Press(Button::X1 | Button::X2)
+                        | MouseEvent::Release(Button::X1 | Button::X2)
+                )
+            })
+            .collect();
+        assert_eq!(round_trip(false, &events), basic);
+    }
+
+    #[test]
+    fn extended_buttons_use_seventh_byte() {
+        let bytes = [POINTER_EVENT, 0x81, 0x01, 0x2C, 0x00, 0x14, 0x02];
+        let message = PointerEvent::new(BUTTON_LEFT | BUTTON_FORWARD, 300, 20);
+        assert_eq!(PointerEvent::from_bytes(&bytes, true), Some((message, 7)));
+        assert_eq!(message.to_bytes(true), bytes);
+        assert_eq!(
+            message.buttons().iter().collect::<Vec<_>>(),
+            [Button::Left, Button::X2]
+        );
+
+        // 服务器未声明扩展时忽略 bit 7，也不读取第 7 个字节
+        assert_eq!(
+            PointerEvent::from_bytes(&bytes, false),
+            Some((PointerEvent::new(BUTTON_LEFT, 300, 20), 6))
+        );
+        assert_eq!(
+            message.to_bytes(false),
+            [POINTER_EVENT, 0x01, 0x01, 0x2C, 0x00, 0x14]
+        );
+
+        // 扩展消息缺少第 7 个字节时不完整
+        assert_eq!(PointerEvent::from_bytes(&bytes[..6], true), None);
+    }
+
+    #[test]
+    fn wheel_detents_are_press_release_pairs() {
+        let mut encoder = RfbEncoder::new(false);
+        let messages = encoder.encode(&MouseEvent::Scroll(ScrollDirection::VerticalUp, 2));
+        let masks: Vec<u16> = messages.iter().map(|message| message.mask).collect();
+        assert_eq!(masks, [WHEEL_UP, 0, WHEEL_UP, 0]);
+        let mut decoder = RfbDecoder::new();
+        let scrolls: Vec<MouseEvent> = messages.iter().flat_map(|m| decoder.decode(m)).collect();
+        assert_eq!(
+            scrolls[1..],
+            [MouseEvent::Scroll(ScrollDirection::VerticalUp, 1); 2]
+        );
+
+        // 超大的滚动量被截断，而不是展开成数十亿条消息
+        let huge = encoder.encode(&MouseEvent::Scroll(ScrollDirection::VerticalDown, i32::MAX));
+        assert_eq!(huge.len(), 2 * MouseEvent::MAX_SCROLL_DETENTS as usize);
+        assert!(encoder
+            .encode(&MouseEvent::Scroll(ScrollDirection::VerticalDown, -3))
+            .is_empty());
+
+        // 相对移动在当前位置上累加，并限制在 u16 范围内
+        encoder.encode(&MouseEvent::RelativeMove(5, -5));
+        assert_eq!(encoder.position(), (5, 0));
+        encoder.encode(&MouseEvent::RelativeMove(i32::MAX, 3));
+        assert_eq!(encoder.position(), (u16::MAX, 3));
+    }
+}
@@ -0,0 +1,387 @@
+//! Serial mouse packets
+//!
+//! Microsoft mice send 7-bit bytes at 1200 baud. Every packet starts with a
+//! byte whose bit 6 is set, holding the left and right buttons and the top two
+//! bits of the signed 8-bit X and Y motion; the next two bytes carry the low
+//! six bits. Y grows downwards. IntelliMouse (MZ) devices append a fourth byte
+//! with the middle button in bit 4 and a 4-bit wheel value.
+//!
+//! Mouse Systems mice send five bytes: a header `0b10000LMR` with active-low
+//! buttons, then two signed X/Y pairs whose sums are the packet motion. Y
+//! grows upwards.
+
+use alloc::vec::Vec;
+
+use super::{sign_extend, Packet};
+use crate::types::{Button, ButtonSet, MouseEvent, ScrollDirection};
+
+/// Largest wheel value per IntelliMouse extension byte
+const WHEEL_LIMIT: i32 = 7;
+
+/// Serial mouse protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SerialProtocol {
+    /// Microsoft 3-byte packets, with the optional IntelliMouse fourth byte
+    Microsoft,
+    /// Mouse Systems 5-byte packets
+    MouseSystems,
+}
+
+impl SerialProtocol {
+    /// Size in bytes of one packet, without optional extension bytes
+    pub const fn packet_size(&self) -> usize {
+        match self {
+            SerialProtocol::Microsoft => 3,
+            SerialProtocol::MouseSystems => 5,
+        }
+    }
+
+    /// Largest motion per axis in one packet
+    const fn motion_limit(&self) -> i32 {
+        match self {
+            SerialProtocol::Microsoft => 127,
+            SerialProtocol::MouseSystems => 254,
+        }
+    }
+
+    /// Check whether packets of this protocol can carry `event`
+    pub fn supports(&self, event: &MouseEvent) -> bool {
+        match event {
+            MouseEvent::Press(button) | MouseEvent::Release(button) => {
+                matches!(button, Button::Left | Button::Right | Button::Middle)
+            }
+            MouseEvent::RelativeMove(..) => true,
+            MouseEvent::Scroll(direction, _) => {
+                *self == SerialProtocol::Microsoft
+                    && matches!(
+                        direction,
+                        ScrollDirection::VerticalUp | ScrollDirection::VerticalDown
+                    )
+            }
+            MouseEvent::Move(..) => false,
+        }
+    }
+
+    const fn is_header(&self, byte: u8) -> bool {
+        match self {
+            SerialProtocol::Microsoft => byte & 0x40 != 0,
+            SerialProtocol::MouseSystems => byte & 0xF8 == 0x80,
+        }
+    }
+}
+
+/// Byte-by-byte serial mouse decoder with sync recovery
+///
+/// Bytes that cannot start a packet are skipped. For the Microsoft protocol a
+/// header byte always starts a new packet, dropping any partial one, and a
+/// byte following a complete packet is read as the IntelliMouse extension.
+#[derive(Debug, Clone)]
+pub struct SerialDecoder {
+    protocol: SerialProtocol,
+    buffer: [u8; 5],
+    len: usize,
+    buttons: ButtonSet,
+    // 上一个 Microsoft 数据包完整接收后，可能跟随一个扩展字节
+    extension: bool,
+}
+
+impl SerialDecoder {
+    /// Create a decoder for `protocol`
+    pub fn new(protocol: SerialProtocol) -> Self {
+        Self {
+            protocol,
+            buffer: [0; 5],
+            len: 0,
+            buttons: ButtonSet::EMPTY,
+            extension: false,
+        }
+    }
+
+    /// Get the protocol being decoded
+    pub fn protocol(&self) -> SerialProtocol {
+        self.protocol
+    }
+
+    /// Get the buttons held according to the last packet
+    pub fn buttons(&self) -> ButtonSet {
+        self.buttons
+    }
+
+    /// Drop any partially received packet
+    pub fn resync(&mut self) {
+        self.len = 0;
+        self.extension = false;
+    }
+
+    /// Feed one byte, returning the packet it completes
+    ///
+    /// A Microsoft extension byte completes a packet of its own carrying the
+    /// middle button and the wheel.
+    pub fn push(&mut self, byte: u8) -> Option<Packet> {
+        let header = self.protocol.is_header(byte);
+        if self.protocol == SerialProtocol::Microsoft && header {
+            self.len = 0;
+        }
+        if self.len == 0 && !header {
+            if self.extension {
+                self.extension = false;
+                return Some(self.extension_packet(byte));
+            }
+            return None;
+        }
+
+        self.buffer[self.len] = byte;
+        self.len += 1;
+        if self.len < self.protocol.packet_size() {
+            return None;
+        }
+        self.len = 0;
+        let packet = match self.protocol {
+            SerialProtocol::Microsoft => {
+                self.extension = true;
+                self.microsoft_packet()
+            }
+            SerialProtocol::MouseSystems => self.mouse_systems_packet(),
+        };
+        self.buttons = packet.buttons;
+        Some(packet)
+    }
+
+    /// Feed bytes, returning the events of every completed packet
+    pub fn decode(&mut self, bytes: &[u8]) -> Vec<MouseEvent> {
+        let mut events = Vec::new();
+        for &byte in bytes {
+            let previous = self.buttons;
+            if let Some(packet) = self.push(byte) {
+                events.extend(packet.events(previous));
+            }
+        }
+        events
+    }
+
+    fn microsoft_packet(&self) -> Packet {
+        let [header, x, y, ..] = self.buffer.map(u32::from);
+        let mut buttons = self.buttons;
+        buttons.set(Button::Left, header & 0x20 != 0);
+        buttons.set(Button::Right, header & 0x10 != 0);
+
+        Packet {
+            buttons,
+            dx: sign_extend((header & 0x03) << 6 | x & 0x3F, 8),
+            dy: sign_extend((header & 0x0C) << 4 | y & 0x3F, 8),
+            ..Packet::default()
+        }
+    }
+
+    fn extension_packet(&mut self, byte: u8) -> Packet {
+        let byte = u32::from(byte);
+        self.buttons.set(Button::Middle, byte & 0x10 != 0);
+        // 滚轮值向下为正
+        Packet {
+            buttons: self.buttons,
+            wheel: -sign_extend(byte & 0x0F, 4),
+            ..Packet::default()
+        }
+    }
+
+    fn mouse_systems_packet(&self) -> Packet {
+        let [header, x1, y1, x2, y2] = self.buffer;
+        let mut buttons = self.buttons;
+        // 按钮位为低电平有效
+        buttons.set(Button::Left, header & 0x04 == 0);
+        buttons.set(Button::Middle, header & 0x02 == 0);
+        buttons.set(Button::Right, header & 0x01 == 0);
+
+        Packet {
+            buttons,
+            dx: i32::from(x1 as i8) + i32::from(x2 as i8),
+            dy: -(i32::from(y1 as i8) + i32::from(y2 as i8)),
+            ..Packet::default()
+        }
+    }
+}
+
+/// Serial mouse encoder tracking the button state
+#[derive(Debug, Clone)]
+pub struct SerialEncoder {
+    protocol: SerialProtocol,
+    buttons: ButtonSet,
+    // 最近一次发送给主机的中键状态
+    middle: bool,
+}
+
+impl SerialEncoder {
+    /// Create an encoder for `protocol`
+    pub fn new(protocol: SerialProtocol) -> Self {
+        Self {
+            protocol,
+            buttons: ButtonSet::EMPTY,
+            middle: false,
+        }
+    }
+
+    /// Get the protocol being encoded
+    pub fn protocol(&self) -> SerialProtocol {
+        self.protocol
+    }
+
+    /// Encode an event into as many packets as its values need
+    ///
+    /// Events the protocol cannot carry produce no bytes.
+    pub fn encode(&mut self, event: &MouseEvent) -> Vec<u8> {
+        if !self.protocol.supports(event) {
+            return Vec::new();
+        }
+        let packets = Packet::split(
+            &mut self.buttons,
+            event,
+            self.protocol.motion_limit(),
+            WHEEL_LIMIT,
+        );
+        packets
+            .iter()
+            .flat_map(|packet| self.encode_packet(packet))
+            .collect()
+    }
+
+    /// Encode one packet, clamping values that do not fit
+    ///
+    /// Microsoft packets get the IntelliMouse extension byte only while the
+    /// middle button is held or changes, or the wheel moves, so plain
+    /// two-button hosts see ordinary 3-byte packets otherwise.
+    pub fn encode_packet(&mut self, packet: &Packet) -> Vec<u8> {
+        let buttons = packet.buttons;
+        match self.protocol {
+            SerialProtocol::Microsoft => {
+                let x = packet.dx.clamp(-128, 127) as u8;
+                let y = packet.dy.clamp(-128, 127) as u8;
+                let mut header = 0x40;
+                header |= u8::from(buttons.contains(Button::Left)) << 5;
+                header |= u8::from(buttons.contains(Button::Right)) << 4;
+                header |= (y >> 6) << 2 | x >> 6;
+
+                let mut bytes = Vec::with_capacity(4);
+                bytes.extend_from_slice(&[header, x & 0x3F, y & 0x3F]);
+
+                let middle = buttons.contains(Button::Middle);
+                if middle || middle != self.middle || packet.wheel != 0 {
+                    let wheel = (-packet.wheel).clamp(-8, 7) as u8 & 0x0F;
+                    bytes.push(u8::from(middle) << 4 | wheel);
+                    self.middle = middle;
+                }
+                bytes
+            }
+            SerialProtocol::MouseSystems => {
+                let x = packet.dx.clamp(-254, 254);
+                let y = (-packet.dy).clamp(-254, 254);
+                // 拆成两组，每组都在 i8 范围内
+                let (x1, y1) = (x / 2, y / 2);
+                let (x2, y2) = (x - x1, y - y1);
+
+                let mut header = 0x87;
+                header &= !(u8::from(buttons.contains(Button::Left)) << 2);
+                header &= !(u8::from(buttons.contains(Button::Middle)) << 1);
+                header &= !u8::from(buttons.contains(Button::Right));
+                [header, x1 as u8, y1 as u8, x2 as u8, y2 as u8].into()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(protocol: SerialProtocol, events: &[MouseEvent]) {
+        let mut encoder = SerialEncoder::new(protocol);
+        let bytes: Vec<u8> = events.iter().flat_map(|e| encoder.encode(e)).collect();
+        assert_eq!(
+            SerialDecoder::new(protocol).decode(&bytes),
+            events,
+            "{:?}",
+            protocol
+        );
+    }
+
+    #[test]
+    fn events_round_trip() {
+        let common = [
+            MouseEvent::Press(Button::Left),
+            MouseEvent::RelativeMove(127, -127),
+            MouseEvent::Press(Button::Right),
+            MouseEvent::RelativeMove(-1, 64),
+            MouseEvent::Press(Button::Middle),
+            MouseEvent::Release(Button::Left),
+            MouseEvent::Release(Button::Middle),
+            MouseEvent::Release(Button::Right),
+        ];
+        round_trip(SerialProtocol::MouseSystems, &common);
+
+        let mut microsoft = common.to_vec();
+        microsoft.extend([
+            MouseEvent::Scroll(ScrollDirection::VerticalUp, 7),
+            MouseEvent::Scroll(ScrollDirection::VerticalDown, 2),
+        ]);
+        round_trip(SerialProtocol::Microsoft, &microsoft);
+    }
+
+    #[test]
+    fn microsoft_packs_top_bits_in_header() {
+        // 包头 bit 5/4 为左/右键，bit 3-2 为 Y 的高两位，bit 1-0 为 X 的高两位
+        let bytes = [0x67, 0x3F, 0x00];
+        assert_eq!(
+            SerialDecoder::new(SerialProtocol::Microsoft).decode(&bytes),
+            [
+                MouseEvent::Press(Button::Left),
+                MouseEvent::RelativeMove(-1, 64),
+            ]
+        );
+        let mut encoder = SerialEncoder::new(SerialProtocol::Microsoft);
+        encoder.encode(&MouseEvent::Press(Button::Left));
+        assert_eq!(encoder.encode(&MouseEvent::RelativeMove(-1, 64)), bytes);
+        assert_eq!(
+            encoder.encode(&MouseEvent::RelativeMove(-127, 127)),
+            [0x66, 0x01, 0x3F]
+        );
+    }
+
+    #[test]
+    fn intellimouse_extension_byte() {
+        let mut decoder = SerialDecoder::new(SerialProtocol::Microsoft);
+        // 第四字节 bit 4 为中键，低 4 位为滚轮（向下为正）
+        assert_eq!(
+            decoder.decode(&[0x40, 0x00, 0x00, 0x1F]),
+            [
+                MouseEvent::Press(Button::Middle),
+                MouseEvent::Scroll(ScrollDirection::VerticalUp, 1),
+            ]
+        );
+        assert_eq!(
+            decoder.decode(&[0x40, 0x00, 0x00, 0x02]),
+            [
+                MouseEvent::Release(Button::Middle),
+                MouseEvent::Scroll(ScrollDirection::VerticalDown, 2),
+            ]
+        );
+        // 两键主机只看到 3 字节的数据包
+        let mut encoder = SerialEncoder::new(SerialProtocol::Microsoft);
+        assert_eq!(encoder.encode(&MouseEvent::RelativeMove(1, 1)).len(), 3);
+        assert_eq!(
+            encoder.encode(&MouseEvent::Press(Button::Middle)),
+            [0x40, 0x00, 0x00, 0x10]
+        );
+    }
+
+    #[test]
+    fn mouse_systems_sums_both_pairs() {
+        // 按钮为低电平有效；Y 向上为正
+        assert_eq!(
+            SerialDecoder::new(SerialProtocol::MouseSystems)
+                .decode(&[0x83, 0x02, 0xFF, 0x03, 0xFE]),
+            [
+                MouseEvent::Press(Button::Left),
+                MouseEvent::RelativeMove(5, 3),
+            ]
+        );
+    }
+}
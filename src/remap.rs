@@ -0,0 +1,561 @@
+//! Button remapping
+//!
+//! A `Remapper` rewrites the button events of a stream according to an
+//! ordered list of rules. A rule has a trigger, one button or a chord of
+//! several, and an action: press other buttons while the trigger is held, or
+//! scroll once when it is pressed. When a button is pressed the first rule
+//! whose trigger it completes applies; buttons no rule matches pass through.
+//!
+//! Every activation remembers the buttons it pressed and releases exactly
+//! those when its trigger is released, even if the rules changed in between,
+//! so remapped buttons never get stuck. A button pressed by two activations
+//! is pressed once and released with the last of them.
+//!
+//! Rules can be written as text, one per line, with buttons named as
+//! `parse_button_with_aliases` accepts:
+//!
+//! ```text
+//! # one-to-one, one-to-many, chord and button-to-scroll
+//! x1 = middle
+//! x2 = left + right
+//! right + left = x2
+//! middle = Scroll(VerticalDown, 3)
+//! # left-handed mode: two rules swapping the buttons
+//! left <-> right
+//! ```
+
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::error::MouseParseError;
+use crate::parser::{parse_button_with_aliases, parse_mouse_input};
+use crate::types::{Button, ButtonSet, MouseEvent, ScrollDirection};
+
+/// What a rule does when its trigger is pressed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// Press the buttons in order while the trigger is held, releasing them in reverse
+    Buttons(Vec<Button>),
+    /// Scroll once when the trigger is pressed
+    Scroll(ScrollDirection, i32),
+}
+
+/// Remapping rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    /// Buttons that must all be held; more than one makes a chord
+    pub trigger: ButtonSet,
+    /// Action taken when the last button of the trigger is pressed
+    pub action: Action,
+}
+
+impl Rule {
+    /// Make `from` act as `to`
+    pub fn map(from: Button, to: Button) -> Self {
+        Self::expand(from, [to])
+    }
+
+    /// Make `from` press several buttons
+    ///
+    /// # Panics
+    ///
+    /// Panics if `to` is empty, which `parse_rules` could not read back; use
+    /// `try_expand` for buttons read from elsewhere.
+    pub fn expand(from: Button, to: impl IntoIterator<Item = Button>) -> Self {
+        Self::try_expand(from, to).expect("Rule without output buttons")
+    }
+
+    /// Make `from` press several buttons, if there is at least one
+    pub fn try_expand(from: Button, to: impl IntoIterator<Item = Button>) -> Option<Self> {
+        let outputs: Vec<Button> = to.into_iter().collect();
+        if outputs.is_empty() {
+            return None;
+        }
+        Some(Self {
+            trigger: ButtonSet::new().with(from),
+            action: Action::Buttons(outputs),
+        })
+    }
+
+    /// Make holding all of `buttons` act as `to`
+    ///
+    /// The buttons of the chord pressed before the last one act as usual
+    /// until the chord completes, then they are released.
+    pub fn chord(buttons: impl IntoIterator<Item = Button>, to: Button) -> Self {
+        Self {
+            trigger: buttons.into_iter().collect(),
+            action: Action::Buttons([to].into()),
+        }
+    }
+
+    /// Make pressing `from` scroll
+    pub fn scroll(from: Button, direction: ScrollDirection, amount: i32) -> Self {
+        Self {
+            trigger: ButtonSet::new().with(from),
+            action: Action::Scroll(direction, amount),
+        }
+    }
+
+    /// Make `a` and `b` act as each other
+    pub fn swap(a: Button, b: Button) -> [Self; 2] {
+        [Self::map(a, b), Self::map(b, a)]
+    }
+
+    /// Check whether pressing `button` with `held` down triggers the rule
+    fn matches(&self, button: Button, held: ButtonSet) -> bool {
+        self.trigger.contains(button) && self.trigger.iter().all(|b| held.contains(b))
+    }
+}
+
+/// Parse buttons joined by `+`
+fn parse_buttons(s: &str) -> Result<Vec<Button>, MouseParseError> {
+    s.split('+')
+        .map(|name| parse_button_with_aliases(name.trim()))
+        .collect()
+}
+
+impl FromStr for Rule {
+    type Err = MouseParseError;
+
+    /// Parse a rule written as `trigger = action`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (trigger, action) = s
+            .split_once('=')
+            .ok_or_else(|| MouseParseError::InvalidRule(s.trim().to_string()))?;
+        let trigger: ButtonSet = parse_buttons(trigger)?.into_iter().collect();
+        let action = action.trim();
+
+        let action = if action
+            .get(..6)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("scroll"))
+        {
+            match parse_mouse_input(action)? {
+                MouseEvent::Scroll(direction, amount) => Action::Scroll(direction, amount),
+                _ => return Err(MouseParseError::InvalidRule(s.trim().to_string())),
+            }
+        } else {
+            Action::Buttons(parse_buttons(action)?)
+        };
+        Ok(Self { trigger, action })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |f: &mut fmt::Formatter<'_>, buttons: &mut dyn Iterator<Item = Button>| {
+            for (i, button) in buttons.enumerate() {
+                if i > 0 {
+                    f.write_str(" + ")?;
+                }
+                write!(f, "{}", button)?;
+            }
+            Ok(())
+        };
+        join(f, &mut self.trigger.iter())?;
+        f.write_str(" = ")?;
+        match &self.action {
+            Action::Buttons(buttons) => join(f, &mut buttons.iter().copied()),
+            Action::Scroll(direction, amount) => write!(f, "Scroll({}, {})", direction, amount),
+        }
+    }
+}
+
+/// Parse rules written one per line
+///
+/// Blank lines and lines starting with `#` are skipped, and `a <-> b`
+/// expands to the two rules of `Rule::swap`.
+pub fn parse_rules(text: &str) -> Result<Vec<Rule>, MouseParseError> {
+    let mut rules = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.split_once("<->") {
+            Some((a, b)) => rules.extend(Rule::swap(
+                parse_button_with_aliases(a.trim())?,
+                parse_button_with_aliases(b.trim())?,
+            )),
+            None => rules.push(line.parse()?),
+        }
+    }
+    Ok(rules)
+}
+
+/// Buttons pressed on behalf of a held trigger
+#[derive(Debug, Clone)]
+struct Activation {
+    trigger: ButtonSet,
+    outputs: Vec<Button>,
+}
+
+/// Applies remapping rules to a stream of events
+#[derive(Debug, Clone, Default)]
+pub struct Remapper {
+    rules: Vec<Rule>,
+    held: ButtonSet,
+    active: Vec<Activation>,
+    // reset 时仍按住的物理按钮，其后到来的释放事件被吞掉
+    forgotten: ButtonSet,
+}
+
+impl Remapper {
+    /// Create a remapper with rules in priority order
+    pub fn new(rules: impl IntoIterator<Item = Rule>) -> Self {
+        Self {
+            rules: rules.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Create a remapper from rules written as text, see `parse_rules`
+    pub fn from_config(text: &str) -> Result<Self, MouseParseError> {
+        parse_rules(text).map(Self::new)
+    }
+
+    /// Get the rules
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Replace the rules
+    ///
+    /// Buttons held keep the remapping they were pressed with until released.
+    pub fn set_rules(&mut self, rules: impl IntoIterator<Item = Rule>) {
+        self.rules = rules.into_iter().collect();
+    }
+
+    /// Get the physical buttons held
+    pub fn held(&self) -> ButtonSet {
+        self.held
+    }
+
+    /// Get the remapped buttons held
+    pub fn pressed(&self) -> ButtonSet {
+        self.active
+            .iter()
+            .flat_map(|activation| activation.outputs.iter().copied())
+            .collect()
+    }
+
+    /// Remap an event
+    ///
+    /// Motion and scroll events pass through unchanged. A release of a
+    /// button that was not seen pressed passes through too, unless the button
+    /// was held when `reset` was called.
+    pub fn process(&mut self, event: &MouseEvent) -> Vec<MouseEvent> {
+        match *event {
+            MouseEvent::Press(button) => {
+                // 按下说明 reset 之后的释放已经丢失
+                self.forgotten.remove(button);
+                self.press(button)
+            }
+            MouseEvent::Release(button) if self.forgotten.remove(button) => Vec::new(),
+            MouseEvent::Release(button) if self.held.remove(button) => {
+                let mut events = Vec::new();
+                self.end(|trigger| trigger.contains(button), &mut events);
+                events
+            }
+            event => [event].into(),
+        }
+    }
+
+    /// Release every remapped button held, forgetting the physical buttons
+    ///
+    /// The later physical releases of the buttons held now produce no events,
+    /// since their remapped buttons have already been released.
+    pub fn reset(&mut self) -> Vec<MouseEvent> {
+        let mut events = Vec::new();
+        self.end(|_| true, &mut events);
+        self.forgotten = self.held;
+        self.held = ButtonSet::EMPTY;
+        events
+    }
+
+    fn press(&mut self, button: Button) -> Vec<MouseEvent> {
+        if !self.held.insert(button) {
+            // 重复的按下事件
+            return Vec::new();
+        }
+        let rule = self
+            .rules
+            .iter()
+            .find(|rule| rule.matches(button, self.held))
+            .cloned()
+            .unwrap_or_else(|| Rule::map(button, button));
+
+        let mut events = Vec::new();
+        if rule.trigger.len() > 1 {
+            // 和弦完成：先释放和弦中其他按钮已经产生的按键
+            let others = rule.trigger.without(button);
+            self.end(
+                |trigger| trigger.iter().any(|b| others.contains(b)),
+                &mut events,
+            );
+        }
+        match rule.action {
+            Action::Buttons(outputs) => {
+                let pressed = self.pressed();
+                events.extend(
+                    outputs
+                        .iter()
+                        .filter(|output| !pressed.contains(**output))
+                        .map(|output| MouseEvent::Press(*output)),
+                );
+                self.active.push(Activation {
+                    trigger: rule.trigger,
+                    outputs,
+                });
+            }
+            Action::Scroll(direction, amount) => {
+                events.push(MouseEvent::Scroll(direction, amount));
+            }
+        }
+        events
+    }
+
+    /// End the activations whose trigger satisfies `ends`, releasing their buttons
+    fn end(&mut self, ends: impl Fn(ButtonSet) -> bool, events: &mut Vec<MouseEvent>) {
+        let (ended, active): (Vec<_>, Vec<_>) = self
+            .active
+            .drain(..)
+            .partition(|activation| ends(activation.trigger));
+        self.active = active;
+        let still_pressed = self.pressed();
+        let mut released = ButtonSet::EMPTY;
+        for activation in ended.iter().rev() {
+            for output in activation.outputs.iter().rev() {
+                if !still_pressed.contains(*output) && released.insert(*output) {
+                    events.push(MouseEvent::Release(*output));
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Remapper {
+    /// Write the rules one per line, in the format `parse_rules` reads
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for rule in &self.rules {
+            writeln!(f, "{}", rule)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+
+    /// Feed events and check that the output never releases a button it did
+    /// not press, nor presses one twice
+    fn run(
+        remapper: &mut Remapper,
+        downstream: &mut ButtonSet,
+        input: &[MouseEvent],
+    ) -> Vec<MouseEvent> {
+        let mut output = Vec::new();
+        for event in input {
+            for remapped in remapper.process(event) {
+                match remapped {
+                    MouseEvent::Press(button) => {
+                        assert!(downstream.insert(button), "{} pressed twice", button)
+                    }
+                    MouseEvent::Release(button) => {
+                        assert!(downstream.remove(button), "{} released unpressed", button)
+                    }
+                    _ => {}
+                }
+                output.push(remapped);
+            }
+        }
+        output
+    }
+
+    fn press(button: Button) -> MouseEvent {
+        MouseEvent::Press(button)
+    }
+
+    fn release(button: Button) -> MouseEvent {
+        MouseEvent::Release(button)
+    }
+
+    #[test]
+    fn overlapping_activations_release_every_button() {
+        let mut remapper = Remapper::new([
+            Rule::expand(Button::X1, [Button::Left, Button::Right]),
+            Rule::map(Button::X2, Button::Left),
+        ]);
+        let mut downstream = ButtonSet::EMPTY;
+        let output = run(
+            &mut remapper,
+            &mut downstream,
+            &[
+                press(Button::X1),
+                press(Button::X2),
+                release(Button::X1),
+                release(Button::X2),
+            ],
+        );
+        // Left 被两个激活共享，只按下一次并随最后一个激活释放
+        assert_eq!(
+            output,
+            [
+                press(Button::Left),
+                press(Button::Right),
+                release(Button::Right),
+                release(Button::Left),
+            ]
+        );
+        assert!(downstream.is_empty());
+        assert!(remapper.pressed().is_empty());
+    }
+
+    #[test]
+    fn chords_release_the_buttons_pressed_before_completion() {
+        let mut remapper = Remapper::new([Rule::chord([Button::Right, Button::Left], Button::X2)]);
+        let mut downstream = ButtonSet::EMPTY;
+        let output = run(
+            &mut remapper,
+            &mut downstream,
+            &[
+                press(Button::Right),
+                press(Button::Left),
+                release(Button::Left),
+                release(Button::Right),
+            ],
+        );
+        assert_eq!(
+            output,
+            [
+                press(Button::Right),
+                release(Button::Right),
+                press(Button::X2),
+                release(Button::X2),
+            ]
+        );
+        assert!(downstream.is_empty());
+    }
+
+    #[test]
+    fn swaps_exchange_buttons() {
+        let mut remapper = Remapper::from_config("left <-> right").unwrap();
+        let mut downstream = ButtonSet::EMPTY;
+        let output = run(
+            &mut remapper,
+            &mut downstream,
+            &[
+                press(Button::Left),
+                press(Button::Right),
+                release(Button::Left),
+                release(Button::Right),
+            ],
+        );
+        assert_eq!(
+            output,
+            [
+                press(Button::Right),
+                press(Button::Left),
+                release(Button::Right),
+                release(Button::Left),
+            ]
+        );
+    }
+
+    #[test]
+    fn rule_changes_keep_held_buttons_paired() {
+        let mut remapper = Remapper::new([Rule::map(Button::X1, Button::Middle)]);
+        let mut downstream = ButtonSet::EMPTY;
+        run(&mut remapper, &mut downstream, &[press(Button::X1)]);
+        remapper.set_rules([Rule::map(Button::X1, Button::Left)]);
+
+        // 释放沿用按下时的映射，新规则只影响之后的按下
+        let output = run(
+            &mut remapper,
+            &mut downstream,
+            &[release(Button::X1), press(Button::X1), release(Button::X1)],
+        );
+        assert_eq!(
+            output,
+            [
+                release(Button::Middle),
+                press(Button::Left),
+                release(Button::Left),
+            ]
+        );
+        assert!(downstream.is_empty());
+    }
+
+    #[test]
+    fn reset_swallows_the_later_physical_release() {
+        let mut remapper = Remapper::new([Rule::map(Button::X1, Button::Middle)]);
+        let mut downstream = ButtonSet::EMPTY;
+        run(
+            &mut remapper,
+            &mut downstream,
+            &[press(Button::X1), press(Button::Left)],
+        );
+        for event in remapper.reset() {
+            downstream.apply(&event);
+        }
+        assert!(downstream.is_empty());
+
+        let output = run(
+            &mut remapper,
+            &mut downstream,
+            &[release(Button::X1), release(Button::Left)],
+        );
+        assert_eq!(output, []);
+        // 未见过按下的按钮的释放仍然透传
+        assert_eq!(
+            remapper.process(&release(Button::Right)),
+            [release(Button::Right)]
+        );
+
+        // reset 后释放丢失时，再次按下的按钮正常工作
+        run(&mut remapper, &mut downstream, &[press(Button::X1)]);
+        for event in remapper.reset() {
+            downstream.apply(&event);
+        }
+        let output = run(
+            &mut remapper,
+            &mut downstream,
+            &[press(Button::X1), release(Button::X1)],
+        );
+        assert_eq!(output, [press(Button::Middle), release(Button::Middle)]);
+    }
+
+    #[test]
+    fn rules_round_trip_through_text() {
+        let text = "x1 = middle\nx2 = left + right\nright + left = x2\nmiddle = Scroll(VerticalDown, 3)\n# comment\n\nleft <-> right";
+        let remapper = Remapper::from_config(text).unwrap();
+        assert_eq!(remapper.rules().len(), 6);
+        assert_eq!(
+            remapper.rules()[1],
+            Rule::expand(Button::X2, [Button::Left, Button::Right])
+        );
+        assert_eq!(
+            remapper.rules()[3],
+            Rule::scroll(Button::Middle, ScrollDirection::VerticalDown, 3)
+        );
+
+        let written = format!("{}", remapper);
+        assert_eq!(parse_rules(&written).unwrap(), remapper.rules());
+    }
+
+    #[test]
+    fn rules_without_outputs_are_rejected() {
+        assert_eq!(Rule::try_expand(Button::X1, []), None);
+        for line in ["x1 = ", "x1", "x1 = Scroll(Up)", "thumb = left"] {
+            assert!(parse_rules(line).is_err(), "{}", line);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Rule without output buttons")]
+    fn expand_panics_without_outputs() {
+        Rule::expand(Button::X1, []);
+    }
+}
@@ -0,0 +1,197 @@
+//! Serde adapters for human-readable mouse types
+//!
+//! The derived serde implementations encode `Button` by variant name and
+//! `MouseEvent` as externally tagged objects. The modules below are meant to
+//! be used with `#[serde(with = "...")]` on individual fields when a more
+//! config-friendly representation is wanted.
+
+use alloc::string::String;
+use serde::{de::Error as _, Deserialize, Deserializer, Serializer};
+
+use crate::{
+    error::MouseParseError,
+    parser::{parse_button_with_aliases, parse_mouse_input, parse_scroll_direction},
+    types::{Button, MouseEvent, Platform, ScrollDirection},
+};
+
+/// Serialize a `Button` via `as_str` and accept any known alias
+pub mod button {
+    use super::*;
+
+    /// Serialize a button as its canonical name
+    pub fn serialize<S: Serializer>(button: &Button, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(button.as_str())
+    }
+
+    /// Deserialize a button from its name or any alias
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Button, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        parse_button_with_aliases(&s).map_err(D::Error::custom)
+    }
+}
+
+/// Serialize a `ScrollDirection` as its `Display` string
+pub mod scroll_direction {
+    use super::*;
+
+    /// Serialize a scroll direction as its name
+    pub fn serialize<S: Serializer>(
+        direction: &ScrollDirection,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(direction)
+    }
+
+    /// Deserialize a scroll direction from its name or a short alias
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<ScrollDirection, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        parse_scroll_direction(&s).map_err(D::Error::custom)
+    }
+}
+
+/// Serialize a `MouseEvent` as its `Display` string, e.g. `Scroll(VerticalUp, 3)`
+pub mod mouse_event {
+    use super::*;
+
+    /// Serialize a mouse event as its textual form
+    pub fn serialize<S: Serializer>(event: &MouseEvent, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(event)
+    }
+
+    /// Deserialize a mouse event using `parse_mouse_input`
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<MouseEvent, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        parse_mouse_input(&s).map_err(D::Error::custom)
+    }
+}
+
+fn serialize_code<S: Serializer>(
+    button: &Button,
+    platform: Platform,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(button.to_code(platform) as u64)
+}
+
+fn deserialize_code<'de, D: Deserializer<'de>>(
+    platform: Platform,
+    deserializer: D,
+) -> Result<Button, D::Error> {
+    let code = usize::deserialize(deserializer)?;
+    Button::from_code(code, platform)
+        .ok_or_else(|| D::Error::custom(MouseParseError::InvalidButtonCode(code)))
+}
+
+macro_rules! platform_code_module {
+    ($name:ident, $platform:expr, $label:literal) => {
+        #[doc = concat!("Serialize a `Button` as its numeric ", $label, " code")]
+        pub mod $name {
+            use super::*;
+
+            #[doc = concat!("Serialize a button as its ", $label, " code")]
+            pub fn serialize<S: Serializer>(
+                button: &Button,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                serialize_code(button, $platform, serializer)
+            }
+
+            #[doc = concat!("Deserialize a button from a ", $label, " code")]
+            pub fn deserialize<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<Button, D::Error> {
+                deserialize_code($platform, deserializer)
+            }
+        }
+    };
+}
+
+platform_code_module!(windows_code, Platform::Windows, "Windows");
+platform_code_module!(linux_code, Platform::Linux, "Linux");
+platform_code_module!(macos_code, Platform::MacOS, "macOS");
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use serde::Serialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Config {
+        #[serde(with = "button")]
+        button: Button,
+        #[serde(with = "scroll_direction")]
+        direction: ScrollDirection,
+        #[serde(with = "mouse_event")]
+        event: MouseEvent,
+        #[serde(with = "windows_code")]
+        windows: Button,
+        #[serde(with = "linux_code")]
+        linux: Button,
+        #[serde(with = "macos_code")]
+        macos: Button,
+    }
+
+    #[test]
+    fn adapters_round_trip() {
+        let config = Config {
+            button: Button::X1,
+            direction: ScrollDirection::HorizontalLeft,
+            event: MouseEvent::Scroll(ScrollDirection::VerticalUp, 3),
+            windows: Button::X2,
+            linux: Button::X1,
+            macos: Button::Middle,
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(
+            json,
+            r#"{"button":"X1","direction":"HorizontalLeft","event":"Scroll(VerticalUp, 3)","windows":6,"linux":8,"macos":2}"#
+        );
+        assert_eq!(serde_json::from_str::<Config>(&json).unwrap(), config);
+    }
+
+    #[test]
+    fn aliases_are_accepted() {
+        let json = r#"{"button":"lmb","direction":"down","event":"Press(back)","windows":1,"linux":3,"macos":1}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(config.button, Button::Left);
+        assert_eq!(config.direction, ScrollDirection::VerticalDown);
+        assert_eq!(config.event, MouseEvent::Press(Button::X1));
+        assert_eq!(config.linux, Button::Right);
+        assert_eq!(config.macos, Button::Right);
+    }
+
+    #[test]
+    fn unknown_names_and_codes_are_errors() {
+        let error_for = |field: &str, value: &str| {
+            let mut fields = [
+                ("button", r#""Left""#),
+                ("direction", r#""up""#),
+                ("event", r#""Move(1, 2)""#),
+                ("windows", "1"),
+                ("linux", "1"),
+                ("macos", "0"),
+            ];
+            for entry in fields.iter_mut() {
+                if entry.0 == field {
+                    entry.1 = value;
+                }
+            }
+            let body: alloc::vec::Vec<String> = fields
+                .iter()
+                .map(|(name, value)| alloc::format!(r#""{}":{}"#, name, value))
+                .collect();
+            serde_json::from_str::<Config>(&alloc::format!("{{{}}}", body.join(",")))
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert!(error_for("button", r#""Thumb""#).starts_with("Unknown mouse button: thumb"));
+        assert!(error_for("direction", r#""sideways""#).starts_with("Unknown mouse button"));
+        assert!(error_for("event", r#""Press(Thumb)""#).starts_with("Unknown mouse button"));
+        assert!(error_for("windows", "99").starts_with("Invalid button code 99"));
+        assert!(error_for("linux", "0").starts_with("Invalid button code 0"));
+    }
+}
@@ -0,0 +1,179 @@
+//! Conversions between this crate's events and crossterm mouse events
+
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use ::crossterm::event::{KeyModifiers, MouseButton, MouseEvent as CrosstermEvent, MouseEventKind};
+
+use super::TerminalPointer;
+use crate::error::MouseParseError;
+use crate::types::{Button, MouseEvent, ScrollDirection};
+
+impl From<MouseButton> for Button {
+    fn from(button: MouseButton) -> Self {
+        match button {
+            MouseButton::Left => Button::Left,
+            MouseButton::Right => Button::Right,
+            MouseButton::Middle => Button::Middle,
+        }
+    }
+}
+
+impl TryFrom<Button> for MouseButton {
+    type Error = MouseParseError;
+
+    fn try_from(button: Button) -> Result<Self, Self::Error> {
+        match button {
+            Button::Left => Ok(MouseButton::Left),
+            Button::Right => Ok(MouseButton::Right),
+            Button::Middle => Ok(MouseButton::Middle),
+            button => Err(MouseParseError::UnmappedButton(button.to_string())),
+        }
+    }
+}
+
+impl TerminalPointer {
+    /// Translate a crossterm mouse event
+    ///
+    /// `Drag` and `Moved` only produce the `Move` to their cell; presses,
+    /// releases and scrolls are preceded by one when the cell changed.
+    pub fn from_crossterm(&mut self, event: &CrosstermEvent) -> Vec<MouseEvent> {
+        let event_at = match event.kind {
+            MouseEventKind::Down(button) => Some(MouseEvent::Press(button.into())),
+            MouseEventKind::Up(button) => Some(MouseEvent::Release(button.into())),
+            MouseEventKind::Drag(_) | MouseEventKind::Moved => None,
+            MouseEventKind::ScrollUp => Some(MouseEvent::Scroll(ScrollDirection::VerticalUp, 1)),
+            MouseEventKind::ScrollDown => {
+                Some(MouseEvent::Scroll(ScrollDirection::VerticalDown, 1))
+            }
+            MouseEventKind::ScrollLeft => {
+                Some(MouseEvent::Scroll(ScrollDirection::HorizontalLeft, 1))
+            }
+            MouseEventKind::ScrollRight => {
+                Some(MouseEvent::Scroll(ScrollDirection::HorizontalRight, 1))
+            }
+        };
+        self.report(event.column, event.row, event_at)
+    }
+
+    /// Translate an event into crossterm mouse events
+    ///
+    /// Moves become `Drag` while a crossterm button is held and `Moved`
+    /// otherwise, and scrolls one event per detent, for at most
+    /// `MouseEvent::MAX_SCROLL_DETENTS` detents. Buttons crossterm has no name
+    /// for produce no events.
+    pub fn to_crossterm(&mut self, event: &MouseEvent) -> Vec<CrosstermEvent> {
+        let kind = match *event {
+            MouseEvent::Press(button) | MouseEvent::Release(button) => {
+                let Ok(button) = MouseButton::try_from(button) else {
+                    return Vec::new();
+                };
+                match event {
+                    MouseEvent::Press(_) => MouseEventKind::Down(button),
+                    _ => MouseEventKind::Up(button),
+                }
+            }
+            MouseEvent::Move(..) | MouseEvent::RelativeMove(..) => {
+                match self.dragging(|button| MouseButton::try_from(button).ok()) {
+                    Some(button) => MouseEventKind::Drag(button),
+                    None => MouseEventKind::Moved,
+                }
+            }
+            MouseEvent::Scroll(direction, _) => match direction {
+                ScrollDirection::VerticalUp => MouseEventKind::ScrollUp,
+                ScrollDirection::VerticalDown => MouseEventKind::ScrollDown,
+                ScrollDirection::HorizontalLeft => MouseEventKind::ScrollLeft,
+                ScrollDirection::HorizontalRight => MouseEventKind::ScrollRight,
+            },
+        };
+
+        let (column, row) = self.apply(event);
+        let reported = CrosstermEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        };
+        let count = match *event {
+            MouseEvent::Scroll(_, amount) => {
+                amount.clamp(0, MouseEvent::MAX_SCROLL_DETENTS) as usize
+            }
+            _ => 1,
+        };
+        (0..count).map(|_| reported).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(kind: MouseEventKind, column: u16, row: u16) -> CrosstermEvent {
+        CrosstermEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn events_round_trip() {
+        let events = [
+            MouseEvent::Move(3, 4),
+            MouseEvent::Press(Button::Left),
+            MouseEvent::Move(5, 4),
+            MouseEvent::Release(Button::Left),
+            MouseEvent::Scroll(ScrollDirection::HorizontalRight, 1),
+            MouseEvent::Move(0, 0),
+            MouseEvent::Press(Button::Middle),
+            MouseEvent::Release(Button::Middle),
+        ];
+        let mut encoder = TerminalPointer::new();
+        let mut decoder = TerminalPointer::new();
+        let decoded: Vec<MouseEvent> = events
+            .iter()
+            .flat_map(|event| encoder.to_crossterm(event))
+            .flat_map(|event| decoder.from_crossterm(&event))
+            .collect();
+        assert_eq!(decoded, events);
+    }
+
+    #[test]
+    fn moves_become_drags_while_a_button_is_held() {
+        let mut pointer = TerminalPointer::new();
+        pointer.to_crossterm(&MouseEvent::Press(Button::Right));
+        assert_eq!(
+            pointer.to_crossterm(&MouseEvent::RelativeMove(2, 1)),
+            [at(MouseEventKind::Drag(MouseButton::Right), 2, 1)]
+        );
+        pointer.to_crossterm(&MouseEvent::Release(Button::Right));
+        assert_eq!(
+            pointer.to_crossterm(&MouseEvent::Move(7, 1)),
+            [at(MouseEventKind::Moved, 7, 1)]
+        );
+    }
+
+    #[test]
+    fn scrolls_are_one_event_per_detent() {
+        let mut pointer = TerminalPointer::new();
+        assert_eq!(
+            pointer.to_crossterm(&MouseEvent::Scroll(ScrollDirection::VerticalUp, 2)),
+            [at(MouseEventKind::ScrollUp, 0, 0); 2]
+        );
+        let huge =
+            pointer.to_crossterm(&MouseEvent::Scroll(ScrollDirection::VerticalDown, i32::MAX));
+        assert_eq!(huge.len(), MouseEvent::MAX_SCROLL_DETENTS as usize);
+    }
+
+    #[test]
+    fn buttons_without_a_crossterm_name_are_unmapped() {
+        assert_eq!(
+            MouseButton::try_from(Button::X1),
+            Err(MouseParseError::UnmappedButton("X1".to_string()))
+        );
+        assert!(TerminalPointer::new()
+            .to_crossterm(&MouseEvent::Press(Button::X2))
+            .is_empty());
+    }
+}
@@ -0,0 +1,99 @@
+//! Terminal mouse reporting
+//!
+//! Terminals report mouse input as events at a character cell rather than
+//! as pointer motion, and some report releases without naming the button.
+//! `TerminalPointer` keeps the cell and the held buttons so that terminal
+//! reports can be turned into `MouseEvent`s, synthesizing a `Move` whenever
+//! the cell changes, and `MouseEvent`s turned back into terminal reports.
+//!
+//! Cells are zero-based `(column, row)` pairs.
+
+use alloc::vec::Vec;
+
+use crate::types::{Button, ButtonSet, MouseEvent};
+
+/// xterm mouse reporting escape sequences
+pub mod xterm;
+
+#[cfg(feature = "crossterm")]
+mod crossterm_impl;
+#[cfg(feature = "termion")]
+mod termion_impl;
+
+/// Cell and button state of a terminal mouse
+#[derive(Debug, Clone, Default)]
+pub struct TerminalPointer {
+    position: Option<(u16, u16)>,
+    buttons: ButtonSet,
+}
+
+impl TerminalPointer {
+    /// Create a pointer with no known cell and no buttons held
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the cell of the last report or event
+    pub fn position(&self) -> Option<(u16, u16)> {
+        self.position
+    }
+
+    /// Get the buttons held
+    pub fn buttons(&self) -> ButtonSet {
+        self.buttons
+    }
+
+    /// Record a terminal report at a cell
+    ///
+    /// Returns a `Move` to the cell when it differs from the previous one,
+    /// followed by `events`, whose presses and releases update the held
+    /// buttons.
+    pub fn report(
+        &mut self,
+        column: u16,
+        row: u16,
+        events: impl IntoIterator<Item = MouseEvent>,
+    ) -> Vec<MouseEvent> {
+        let mut reported = Vec::new();
+        if self.position != Some((column, row)) {
+            self.position = Some((column, row));
+            reported.push(MouseEvent::Move(column.into(), row.into()));
+        }
+        for event in events {
+            self.buttons.apply(&event);
+            reported.push(event);
+        }
+        reported
+    }
+
+    /// Apply an event, returning the cell the pointer is left at
+    ///
+    /// `Move` positions are clamped to the `u16` range and `RelativeMove`
+    /// counts cells from the current one, starting from (0, 0).
+    pub fn apply(&mut self, event: &MouseEvent) -> (u16, u16) {
+        let (column, row) = self.position.unwrap_or_default();
+        let position = match *event {
+            MouseEvent::Move(x, y) => (clamp(x), clamp(y)),
+            MouseEvent::RelativeMove(dx, dy) => {
+                (clamp(i32::from(column) + dx), clamp(i32::from(row) + dy))
+            }
+            _ => (column, row),
+        };
+        self.buttons.apply(event);
+        self.position = Some(position);
+        position
+    }
+
+    /// Get the held button reported by drags
+    ///
+    /// Returns the first held button, in declaration order, that `convert`
+    /// maps to a terminal button.
+    pub fn dragging<T>(&self, convert: impl Fn(Button) -> Option<T>) -> Option<T> {
+        self.buttons.iter().find_map(convert)
+    }
+}
+
+/// Clamp a coordinate to the range of a terminal cell
+fn clamp(value: i32) -> u16 {
+    value.clamp(0, i32::from(u16::MAX)) as u16
+}
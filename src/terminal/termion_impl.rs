@@ -0,0 +1,196 @@
+//! Conversions between this crate's events and termion mouse events
+//!
+//! termion counts cells from one and reports releases without a button, like
+//! the X10 and normal xterm encodings it parses.
+
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use ::termion::event::{MouseButton, MouseEvent as TermionEvent};
+
+use super::TerminalPointer;
+use crate::error::MouseParseError;
+use crate::types::{Button, MouseEvent, ScrollDirection};
+
+impl TryFrom<MouseButton> for Button {
+    type Error = MouseParseError;
+
+    /// Convert a button, rejecting the wheel pseudo-buttons
+    fn try_from(button: MouseButton) -> Result<Self, Self::Error> {
+        match button {
+            MouseButton::Left => Ok(Button::Left),
+            MouseButton::Right => Ok(Button::Right),
+            MouseButton::Middle => Ok(Button::Middle),
+            wheel => Err(MouseParseError::UnmappedButton(alloc::format!(
+                "{:?}", wheel
+            ))),
+        }
+    }
+}
+
+impl TryFrom<Button> for MouseButton {
+    type Error = MouseParseError;
+
+    fn try_from(button: Button) -> Result<Self, Self::Error> {
+        match button {
+            Button::Left => Ok(MouseButton::Left),
+            Button::Right => Ok(MouseButton::Right),
+            Button::Middle => Ok(MouseButton::Middle),
+            button => Err(MouseParseError::UnmappedButton(button.to_string())),
+        }
+    }
+}
+
+impl TerminalPointer {
+    /// Translate a termion mouse event
+    ///
+    /// Wheel presses become scrolls, a release releases every held button
+    /// and `Hold` only produces the `Move` to its cell.
+    pub fn from_termion(&mut self, event: &TermionEvent) -> Vec<MouseEvent> {
+        let (x, y, events) = match *event {
+            TermionEvent::Press(button, x, y) => {
+                let event = match button {
+                    MouseButton::WheelUp => MouseEvent::Scroll(ScrollDirection::VerticalUp, 1),
+                    MouseButton::WheelDown => MouseEvent::Scroll(ScrollDirection::VerticalDown, 1),
+                    MouseButton::WheelLeft => {
+                        MouseEvent::Scroll(ScrollDirection::HorizontalLeft, 1)
+                    }
+                    MouseButton::WheelRight => {
+                        MouseEvent::Scroll(ScrollDirection::HorizontalRight, 1)
+                    }
+                    MouseButton::Left => MouseEvent::Press(Button::Left),
+                    MouseButton::Right => MouseEvent::Press(Button::Right),
+                    MouseButton::Middle => MouseEvent::Press(Button::Middle),
+                };
+                (x, y, [event].into())
+            }
+            TermionEvent::Release(x, y) => {
+                let released = self.buttons().iter().map(MouseEvent::Release).collect();
+                (x, y, released)
+            }
+            TermionEvent::Hold(x, y) => (x, y, Vec::new()),
+        };
+        // termion 的坐标从 1 开始
+        self.report(x.saturating_sub(1), y.saturating_sub(1), events)
+    }
+
+    /// Translate an event into termion mouse events
+    ///
+    /// Moves become `Hold` while a termion button is held and produce no
+    /// events otherwise, since termion has no plain motion event. Scrolls
+    /// become one wheel press per detent, for at most
+    /// `MouseEvent::MAX_SCROLL_DETENTS` detents. Buttons termion has no name
+    /// for produce no events.
+    pub fn to_termion(&mut self, event: &MouseEvent) -> Vec<TermionEvent> {
+        let dragging = self
+            .dragging(|button| MouseButton::try_from(button).ok())
+            .is_some();
+        let button = match *event {
+            MouseEvent::Press(button) | MouseEvent::Release(button) => {
+                match MouseButton::try_from(button) {
+                    Ok(button) => Some(button),
+                    Err(_) => return Vec::new(),
+                }
+            }
+            _ => None,
+        };
+
+        let (column, row) = self.apply(event);
+        let (x, y) = (column.saturating_add(1), row.saturating_add(1));
+        match *event {
+            MouseEvent::Press(_) => button
+                .map(|button| TermionEvent::Press(button, x, y))
+                .into_iter()
+                .collect(),
+            MouseEvent::Release(_) => [TermionEvent::Release(x, y)].into(),
+            MouseEvent::Move(..) | MouseEvent::RelativeMove(..) if dragging => {
+                [TermionEvent::Hold(x, y)].into()
+            }
+            MouseEvent::Move(..) | MouseEvent::RelativeMove(..) => Vec::new(),
+            MouseEvent::Scroll(direction, amount) => {
+                let wheel = match direction {
+                    ScrollDirection::VerticalUp => MouseButton::WheelUp,
+                    ScrollDirection::VerticalDown => MouseButton::WheelDown,
+                    ScrollDirection::HorizontalLeft => MouseButton::WheelLeft,
+                    ScrollDirection::HorizontalRight => MouseButton::WheelRight,
+                };
+                let press = TermionEvent::Press(wheel, x, y);
+                (0..amount.clamp(0, MouseEvent::MAX_SCROLL_DETENTS))
+                    .map(|_| press)
+                    .collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_round_trip() {
+        let events = [
+            MouseEvent::Move(3, 4),
+            MouseEvent::Press(Button::Left),
+            MouseEvent::Move(5, 4),
+            MouseEvent::Release(Button::Left),
+            MouseEvent::Scroll(ScrollDirection::VerticalDown, 1),
+            MouseEvent::Move(0, 0),
+            MouseEvent::Press(Button::Right),
+            MouseEvent::Release(Button::Right),
+        ];
+        let mut encoder = TerminalPointer::new();
+        let mut decoder = TerminalPointer::new();
+        // termion 没有单纯的移动事件，第一个 Move 只体现在按下的位置上
+        let decoded: Vec<MouseEvent> = events
+            .iter()
+            .flat_map(|event| encoder.to_termion(event))
+            .flat_map(|event| decoder.from_termion(&event))
+            .collect();
+        assert_eq!(decoded, events);
+    }
+
+    #[test]
+    fn cells_count_from_one() {
+        let mut pointer = TerminalPointer::new();
+        pointer.to_termion(&MouseEvent::Move(9, 2));
+        assert_eq!(
+            pointer.to_termion(&MouseEvent::Press(Button::Middle)),
+            [TermionEvent::Press(MouseButton::Middle, 10, 3)]
+        );
+        assert_eq!(
+            pointer.to_termion(&MouseEvent::RelativeMove(1, 0)),
+            [TermionEvent::Hold(11, 3)]
+        );
+        assert_eq!(
+            TerminalPointer::new().from_termion(&TermionEvent::Hold(1, 1)),
+            [MouseEvent::Move(0, 0)]
+        );
+    }
+
+    #[test]
+    fn scrolls_are_one_wheel_press_per_detent() {
+        let mut pointer = TerminalPointer::new();
+        assert_eq!(
+            pointer.to_termion(&MouseEvent::Scroll(ScrollDirection::HorizontalLeft, 2)),
+            [TermionEvent::Press(MouseButton::WheelLeft, 1, 1); 2]
+        );
+        let huge = pointer.to_termion(&MouseEvent::Scroll(ScrollDirection::VerticalUp, i32::MAX));
+        assert_eq!(huge.len(), MouseEvent::MAX_SCROLL_DETENTS as usize);
+    }
+
+    #[test]
+    fn buttons_without_an_equivalent_are_unmapped() {
+        assert_eq!(
+            MouseButton::try_from(Button::Extra3),
+            Err(MouseParseError::UnmappedButton("Extra3".to_string()))
+        );
+        assert_eq!(
+            Button::try_from(MouseButton::WheelUp),
+            Err(MouseParseError::UnmappedButton("WheelUp".to_string()))
+        );
+        assert!(TerminalPointer::new()
+            .to_termion(&MouseEvent::Release(Button::X1))
+            .is_empty());
+    }
+}
@@ -0,0 +1,558 @@
+//! xterm mouse reporting escape sequences
+//!
+//! Every encoding packs the button, modifiers and motion into one button
+//! code: the low two bits select button 1 to 3 (3 meaning release in the
+//! encodings that cannot name the button), 4, 8 and 16 are Shift, Meta and
+//! Control, 32 marks motion, 64 selects the wheel buttons 4 to 7 and 128 the
+//! extra buttons 8 to 11. Button numbers are X11 core button numbers.
+//!
+//! - X10 (`CSI M Cb Cx Cy`) sends the code and the one-based coordinates as
+//!   single bytes offset by 32, so coordinates stop at 223.
+//! - URXVT (mode 1015, `CSI Cb ; Cx ; Cy M`) sends the same values in decimal.
+//! - SGR (mode 1006, `CSI < Cb ; Cx ; Cy M`) sends the code without offset
+//!   and ends releases with `m`, keeping the button. SGR-pixel (mode 1016)
+//!   has the same syntax with pixel coordinates.
+
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use thiserror::Error;
+
+use super::TerminalPointer;
+use crate::mapping::CodeSpace;
+use crate::types::{Button, MouseEvent, ScrollDirection};
+
+/// Control Sequence Introducer starting every report
+const CSI: &[u8] = b"\x1b[";
+/// Longest SGR or URXVT report accepted while waiting for its final byte
+const MAX_REPORT_LEN: usize = 32;
+
+/// Shift modifier bit of the button code
+const SHIFT: u32 = 4;
+/// Meta modifier bit of the button code
+const META: u32 = 8;
+/// Control modifier bit of the button code
+const CONTROL: u32 = 16;
+/// Motion bit of the button code
+const MOTION: u32 = 32;
+/// Low bits of the button code meaning "no button" or "released"
+const NO_BUTTON: u32 = 3;
+
+/// Mouse reporting encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// X10-compatible bytes, `CSI M Cb Cx Cy`
+    X10,
+    /// URXVT decimal parameters (mode 1015)
+    Urxvt,
+    /// SGR parameters with cell coordinates (mode 1006)
+    Sgr,
+    /// SGR parameters with pixel coordinates (mode 1016)
+    SgrPixels,
+}
+
+/// Modifier keys held during a report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Modifiers {
+    /// Shift key
+    pub shift: bool,
+    /// Meta (usually Alt) key
+    pub meta: bool,
+    /// Control key
+    pub control: bool,
+}
+
+impl Modifiers {
+    const fn from_code(code: u32) -> Self {
+        Self {
+            shift: code & SHIFT != 0,
+            meta: code & META != 0,
+            control: code & CONTROL != 0,
+        }
+    }
+
+    const fn code(&self) -> u32 {
+        (self.shift as u32 * SHIFT) | (self.meta as u32 * META) | (self.control as u32 * CONTROL)
+    }
+}
+
+/// What a report describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportKind {
+    /// A button was pressed
+    Press(Button),
+    /// A button was released; X10 and URXVT do not say which
+    Release(Option<Button>),
+    /// The pointer moved, with the button held, if any
+    Motion(Option<Button>),
+    /// The wheel turned one detent
+    Scroll(ScrollDirection),
+}
+
+/// One terminal mouse report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MouseReport {
+    /// What happened
+    pub kind: ReportKind,
+    /// One-based column, or horizontal pixel with SGR-pixel
+    pub x: u16,
+    /// One-based row, or vertical pixel with SGR-pixel
+    pub y: u16,
+    /// Modifier keys held
+    pub modifiers: Modifiers,
+}
+
+/// Error parsing a report
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceError {
+    /// The input is a prefix of a report; more bytes are needed
+    #[error("Incomplete mouse report")]
+    Incomplete,
+    /// The input does not start with a mouse report
+    #[error("Invalid mouse report")]
+    Invalid,
+}
+
+/// Get the X11 button number of a button that has a button code
+fn button_number(button: Button) -> Option<u32> {
+    match CodeSpace::Linux.to_code(button)? {
+        number @ (1..=3 | 8..=11) => Some(number as u32),
+        _ => None,
+    }
+}
+
+/// Get the button code bits of an X11 button number
+const fn number_code(number: u32) -> u32 {
+    match number {
+        1..=3 => number - 1,
+        4..=7 => 64 + number - 4,
+        _ => 128 + number - 8,
+    }
+}
+
+impl MouseReport {
+    /// Create a report without modifiers
+    pub const fn new(kind: ReportKind, x: u16, y: u16) -> Self {
+        Self {
+            kind,
+            x,
+            y,
+            modifiers: Modifiers {
+                shift: false,
+                meta: false,
+                control: false,
+            },
+        }
+    }
+
+    /// Check whether `button` can be reported
+    ///
+    /// Only buttons 1 to 3 and 8 to 11 (left, middle, right, X1, X2, Extra3
+    /// and Extra4) have button codes.
+    pub fn supports(button: Button) -> bool {
+        button_number(button).is_some()
+    }
+
+    /// Parse a report at the start of `input`
+    ///
+    /// Returns the report, the encoding it was sent in and its length in
+    /// bytes. SGR and SGR-pixel reports look the same; they are returned as
+    /// `Encoding::Sgr` and the caller, knowing which mode it enabled, decides
+    /// whether the coordinates are cells or pixels.
+    pub fn parse(input: &[u8]) -> Result<(Self, Encoding, usize), SequenceError> {
+        let prefix = input.len().min(CSI.len());
+        if input[..prefix] != CSI[..prefix] {
+            return Err(SequenceError::Invalid);
+        }
+        let body = &input[prefix..];
+        match body.first() {
+            None => Err(SequenceError::Incomplete),
+            Some(b'M') => {
+                let [_, code, x, y, ..] = *body else {
+                    return Err(SequenceError::Incomplete);
+                };
+                let [code, x, y] = [code, x, y].map(|byte| u32::from(byte).checked_sub(32));
+                let (Some(code), Some(x), Some(y)) = (code, x, y) else {
+                    return Err(SequenceError::Invalid);
+                };
+                let report = Self::from_code(code, x, y, false)?;
+                Ok((report, Encoding::X10, CSI.len() + 4))
+            }
+            Some(b'<') => {
+                let ([code, x, y], last, len) = parameters(&body[1..])?;
+                let report = Self::from_code(code, x, y, last == b'm')?;
+                Ok((report, Encoding::Sgr, CSI.len() + 1 + len))
+            }
+            Some(b'0'..=b'9') => {
+                let ([code, x, y], last, len) = parameters(body)?;
+                if last != b'M' {
+                    return Err(SequenceError::Invalid);
+                }
+                let code = code.checked_sub(32).ok_or(SequenceError::Invalid)?;
+                let report = Self::from_code(code, x, y, false)?;
+                Ok((report, Encoding::Urxvt, CSI.len() + len))
+            }
+            Some(_) => Err(SequenceError::Invalid),
+        }
+    }
+
+    /// Serialize the report
+    ///
+    /// X10 clamps coordinates to 223, and X10 and URXVT send every release
+    /// as button code 3.
+    pub fn encode(&self, encoding: Encoding) -> Vec<u8> {
+        let sgr = matches!(encoding, Encoding::Sgr | Encoding::SgrPixels);
+        let code = self.code(sgr);
+        let mut out = Vec::from(CSI);
+        match encoding {
+            Encoding::X10 => {
+                let byte = |value: u32| (value.min(223) + 32) as u8;
+                out.extend_from_slice(&[
+                    b'M',
+                    byte(code),
+                    byte(self.x.into()),
+                    byte(self.y.into()),
+                ]);
+            }
+            Encoding::Urxvt => {
+                let mut text = alloc::string::String::new();
+                let _ = write!(text, "{};{};{}M", code + 32, self.x, self.y);
+                out.extend_from_slice(text.as_bytes());
+            }
+            Encoding::Sgr | Encoding::SgrPixels => {
+                let last = match self.kind {
+                    ReportKind::Release(_) => 'm',
+                    _ => 'M',
+                };
+                let mut text = alloc::string::String::new();
+                let _ = write!(text, "<{};{};{}{}", code, self.x, self.y, last);
+                out.extend_from_slice(text.as_bytes());
+            }
+        }
+        out
+    }
+
+    /// Build a report from a button code without offset
+    fn from_code(code: u32, x: u32, y: u32, released: bool) -> Result<Self, SequenceError> {
+        let low = code & 3;
+        let number = match code & 0xC0 {
+            0x00 if low == NO_BUTTON => None,
+            0x00 => Some(low + 1),
+            0x40 => Some(low + 4),
+            0x80 => Some(low + 8),
+            _ => return Err(SequenceError::Invalid),
+        };
+        let motion = code & MOTION != 0;
+
+        let kind = match number {
+            Some(4..=7) if released => return Err(SequenceError::Invalid),
+            Some(number @ 4..=7) => ReportKind::Scroll(match number {
+                4 => ScrollDirection::VerticalUp,
+                5 => ScrollDirection::VerticalDown,
+                6 => ScrollDirection::HorizontalLeft,
+                _ => ScrollDirection::HorizontalRight,
+            }),
+            Some(number) => {
+                // 按钮 1-3、8-11 在 X11 代码空间中都有对应按钮
+                let button = CodeSpace::Linux
+                    .from_code(number as usize)
+                    .ok_or(SequenceError::Invalid)?;
+                match (released, motion) {
+                    (true, _) => ReportKind::Release(Some(button)),
+                    (false, true) => ReportKind::Motion(Some(button)),
+                    (false, false) => ReportKind::Press(button),
+                }
+            }
+            None if motion && !released => ReportKind::Motion(None),
+            None => ReportKind::Release(None),
+        };
+
+        let coordinate = |value: u32| u16::try_from(value).map_err(|_| SequenceError::Invalid);
+        Ok(Self {
+            kind,
+            x: coordinate(x)?,
+            y: coordinate(y)?,
+            modifiers: Modifiers::from_code(code),
+        })
+    }
+
+    /// Get the button code without offset
+    fn code(&self, sgr: bool) -> u32 {
+        let button = match self.kind {
+            ReportKind::Press(button) => button_number(button).map(number_code),
+            // 只有 SGR 能在释放时给出按钮
+            ReportKind::Release(Some(button)) if sgr => button_number(button).map(number_code),
+            ReportKind::Release(_) => None,
+            ReportKind::Motion(button) => Some(
+                button
+                    .and_then(button_number)
+                    .map_or(NO_BUTTON, number_code)
+                    | MOTION,
+            ),
+            ReportKind::Scroll(direction) => Some(number_code(match direction {
+                ScrollDirection::VerticalUp => 4,
+                ScrollDirection::VerticalDown => 5,
+                ScrollDirection::HorizontalLeft => 6,
+                ScrollDirection::HorizontalRight => 7,
+            })),
+        };
+        button.unwrap_or(NO_BUTTON) | self.modifiers.code()
+    }
+}
+
+/// Parse `Cb ; Cx ; Cy` followed by `M` or `m`
+///
+/// Returns the parameters, the final byte and the number of bytes used.
+fn parameters(input: &[u8]) -> Result<([u32; 3], u8, usize), SequenceError> {
+    let mut values = [0u32; 3];
+    let mut index = 0;
+    let mut digits = 0;
+    for (len, &byte) in input.iter().enumerate() {
+        if len >= MAX_REPORT_LEN {
+            return Err(SequenceError::Invalid);
+        }
+        match byte {
+            b'0'..=b'9' => {
+                values[index] = values[index]
+                    .checked_mul(10)
+                    .and_then(|value| value.checked_add(u32::from(byte - b'0')))
+                    .ok_or(SequenceError::Invalid)?;
+                digits += 1;
+            }
+            b';' if digits > 0 && index < 2 => {
+                index += 1;
+                digits = 0;
+            }
+            b'M' | b'm' if digits > 0 && index == 2 => return Ok((values, byte, len + 1)),
+            _ => return Err(SequenceError::Invalid),
+        }
+    }
+    Err(SequenceError::Incomplete)
+}
+
+impl TerminalPointer {
+    /// Translate a report
+    ///
+    /// The pointer cell is the report's coordinates made zero-based, so
+    /// SGR-pixel reports move it in pixels. A release that does not name its
+    /// button releases every held button.
+    pub fn from_xterm(&mut self, report: &MouseReport) -> Vec<MouseEvent> {
+        let events: Vec<MouseEvent> = match report.kind {
+            ReportKind::Press(button) => [MouseEvent::Press(button)].into(),
+            ReportKind::Release(Some(button)) => [MouseEvent::Release(button)].into(),
+            ReportKind::Release(None) => self.buttons().iter().map(MouseEvent::Release).collect(),
+            ReportKind::Motion(_) => Vec::new(),
+            ReportKind::Scroll(direction) => [MouseEvent::Scroll(direction, 1)].into(),
+        };
+        self.report(
+            report.x.saturating_sub(1),
+            report.y.saturating_sub(1),
+            events,
+        )
+    }
+
+    /// Translate an event into reports
+    ///
+    /// Moves become motion reports naming the first held button that has a
+    /// button code; terminals send those without a button only in
+    /// any-motion mode (1003), which is left to the caller. Scrolls become
+    /// one report per detent, for at most `MouseEvent::MAX_SCROLL_DETENTS`
+    /// detents. Buttons without a button code produce no reports.
+    pub fn to_xterm(&mut self, event: &MouseEvent) -> Vec<MouseReport> {
+        if let MouseEvent::Press(button) | MouseEvent::Release(button) = *event {
+            if !MouseReport::supports(button) {
+                return Vec::new();
+            }
+        }
+        let held = self.dragging(|button| MouseReport::supports(button).then_some(button));
+
+        let (column, row) = self.apply(event);
+        let (x, y) = (column.saturating_add(1), row.saturating_add(1));
+        let kind = match *event {
+            MouseEvent::Press(button) => ReportKind::Press(button),
+            MouseEvent::Release(button) => ReportKind::Release(Some(button)),
+            MouseEvent::Move(..) | MouseEvent::RelativeMove(..) => ReportKind::Motion(held),
+            MouseEvent::Scroll(direction, amount) => {
+                let report = MouseReport::new(ReportKind::Scroll(direction), x, y);
+                return (0..amount.clamp(0, MouseEvent::MAX_SCROLL_DETENTS))
+                    .map(|_| report)
+                    .collect();
+            }
+        };
+        [MouseReport::new(kind, x, y)].into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENCODINGS: [Encoding; 3] = [Encoding::X10, Encoding::Urxvt, Encoding::Sgr];
+
+    #[test]
+    fn parses_sgr_reports() {
+        let (report, encoding, len) = MouseReport::parse(b"\x1b[<64;10;5M").unwrap();
+        assert_eq!(
+            report,
+            MouseReport::new(ReportKind::Scroll(ScrollDirection::VerticalUp), 10, 5)
+        );
+        assert_eq!((encoding, len), (Encoding::Sgr, 11));
+
+        // SGR 的释放以 m 结尾并给出按钮
+        let (report, encoding, len) = MouseReport::parse(b"\x1b[<0;1;1mrest").unwrap();
+        assert_eq!(
+            report,
+            MouseReport::new(ReportKind::Release(Some(Button::Left)), 1, 1)
+        );
+        assert_eq!((encoding, len), (Encoding::Sgr, 9));
+    }
+
+    #[test]
+    fn parses_x10_and_urxvt_reports() {
+        let press = MouseReport::new(ReportKind::Press(Button::Left), 1, 1);
+        assert_eq!(
+            MouseReport::parse(b"\x1b[M !!"),
+            Ok((press, Encoding::X10, 6))
+        );
+        assert_eq!(
+            MouseReport::parse(b"\x1b[32;1;1M"),
+            Ok((press, Encoding::Urxvt, 9))
+        );
+        // 按钮代码 3 表示释放，但不指明按钮
+        let release = MouseReport::new(ReportKind::Release(None), 1, 1);
+        assert_eq!(
+            MouseReport::parse(b"\x1b[M#!!"),
+            Ok((release, Encoding::X10, 6))
+        );
+        assert_eq!(
+            MouseReport::parse(b"\x1b[35;1;1M"),
+            Ok((release, Encoding::Urxvt, 9))
+        );
+    }
+
+    #[test]
+    fn reports_round_trip() {
+        let modifiers = Modifiers {
+            shift: true,
+            meta: false,
+            control: true,
+        };
+        let mut reports: Vec<MouseReport> = [
+            Button::Left,
+            Button::Middle,
+            Button::Right,
+            Button::X1,
+            Button::X2,
+            Button::Extra3,
+            Button::Extra4,
+        ]
+        .into_iter()
+        .flat_map(|button| {
+            [
+                ReportKind::Press(button),
+                ReportKind::Motion(Some(button)),
+                ReportKind::Release(Some(button)),
+            ]
+        })
+        .chain([
+            ReportKind::Motion(None),
+            ReportKind::Scroll(ScrollDirection::VerticalUp),
+            ReportKind::Scroll(ScrollDirection::VerticalDown),
+            ReportKind::Scroll(ScrollDirection::HorizontalLeft),
+            ReportKind::Scroll(ScrollDirection::HorizontalRight),
+        ])
+        .map(|kind| MouseReport::new(kind, 12, 223))
+        .collect();
+        reports.extend(reports.clone().into_iter().map(|report| MouseReport {
+            modifiers,
+            ..report
+        }));
+
+        for encoding in ENCODINGS {
+            for report in &reports {
+                let bytes = report.encode(encoding);
+                let (parsed, parsed_encoding, len) = MouseReport::parse(&bytes).unwrap();
+                // X10 和 URXVT 的释放不指明按钮
+                let expected = match report.kind {
+                    ReportKind::Release(_) if encoding != Encoding::Sgr => MouseReport {
+                        kind: ReportKind::Release(None),
+                        ..*report
+                    },
+                    _ => *report,
+                };
+                assert_eq!(parsed, expected, "{:?} {:?}", encoding, bytes);
+                assert_eq!((parsed_encoding, len), (encoding, bytes.len()));
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_partial_and_malformed_reports() {
+        for input in [
+            &b"\x1b"[..],
+            b"\x1b[",
+            b"\x1b[M !",
+            b"\x1b[<0;1",
+            b"\x1b[32;1;",
+        ] {
+            assert_eq!(
+                MouseReport::parse(input),
+                Err(SequenceError::Incomplete),
+                "{:?}",
+                input
+            );
+        }
+        for input in [
+            &b"x"[..],
+            b"\x1b[<0;1;1x",
+            b"\x1b[32;1;1m",
+            b"\x1b[<;1;1M",
+            b"\x1b[<68;1;1m",
+            b"\x1b[<0;1;70000M",
+        ] {
+            assert_eq!(
+                MouseReport::parse(input),
+                Err(SequenceError::Invalid),
+                "{:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn unnamed_release_releases_every_button() {
+        let mut pointer = TerminalPointer::new();
+        let parse = |input: &[u8]| MouseReport::parse(input).unwrap().0;
+        assert_eq!(
+            pointer.from_xterm(&parse(b"\x1b[M !!")),
+            [MouseEvent::Move(0, 0), MouseEvent::Press(Button::Left)]
+        );
+        pointer.from_xterm(&parse(b"\x1b[M\"!!"));
+        assert_eq!(
+            pointer.from_xterm(&parse(b"\x1b[M#\"!")),
+            [
+                MouseEvent::Move(1, 0),
+                MouseEvent::Release(Button::Left),
+                MouseEvent::Release(Button::Right),
+            ]
+        );
+        assert!(pointer.buttons().is_empty());
+    }
+
+    #[test]
+    fn scrolls_are_one_report_per_detent() {
+        let mut pointer = TerminalPointer::new();
+        let reports = pointer.to_xterm(&MouseEvent::Scroll(ScrollDirection::VerticalDown, 3));
+        assert_eq!(
+            reports,
+            [MouseReport::new(ReportKind::Scroll(ScrollDirection::VerticalDown), 1, 1); 3]
+        );
+
+        // 超大的滚动量被截断，负数不产生报告
+        let huge = pointer.to_xterm(&MouseEvent::Scroll(ScrollDirection::VerticalUp, i32::MAX));
+        assert_eq!(huge.len(), MouseEvent::MAX_SCROLL_DETENTS as usize);
+        assert!(pointer
+            .to_xterm(&MouseEvent::Scroll(ScrollDirection::VerticalUp, -1))
+            .is_empty());
+    }
+}
@@ -1,81 +1,112 @@
-use std::fmt;
-use std::str::FromStr;
+use core::fmt;
+#[cfg(feature = "alloc")]
+use core::str::FromStr;
 
+#[cfg(feature = "alloc")]
 use crate::error::MouseParseError;
+#[cfg(feature = "alloc")]
 use crate::mapping::standard::parse_button_from_str;
-use crate::types::{CodeMapper, Platform};
+use crate::mapping::standard::table::standard_button_table;
+use crate::types::Platform;
+
+#[cfg(feature = "const-table")]
+use crate::mapping::standard::const_impl;
+#[cfg(not(feature = "const-table"))]
+use crate::types::CodeMapper;
 
 #[cfg(feature = "phf")]
 use phf::PhfHash;
 #[cfg(feature = "phf")]
 use phf_shared::PhfBorrow;
 
-/// Mouse button enumeration
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub enum Button {
-    /// Left mouse button
-    Left,
-    /// Right mouse button
-    Right,
-    /// Middle mouse button (scroll wheel press)
-    Middle,
-    /// X1 button (usually back)
-    X1,
-    /// X2 button (usually forward)
-    X2,
-    /// Extra button 3
-    Extra3,
-    /// Extra button 4
-    Extra4,
-    /// Extra button 5
-    Extra5,
-    /// Extra button 6
-    Extra6,
-    /// Extra button 7
-    Extra7,
-    /// Extra button 8
-    Extra8,
-    #[cfg(feature = "extended")]
-    /// Extra button 9 (extended feature)
-    Extra9,
-    #[cfg(feature = "extended")]
-    /// Extra button 10 (extended feature)
-    Extra10,
+macro_rules! define_button {
+    (
+        code_spaces: [$($(#[$space_attr:meta])* $space:ident($module:ident) => $space_meta:tt;)*];
+        buttons: [$(
+            $(#[cfg($cfg:meta)])*
+            #[doc = $doc:literal]
+            $name:ident => $str:literal, [$($alias:literal),*], [$($code:tt),*];
+        )*];
+    ) => {
+        /// Mouse button enumeration
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub enum Button {
+            $(
+                $(#[cfg($cfg)])*
+                #[doc = $doc]
+                $name,
+            )*
+        }
+
+        impl Button {
+            /// Number of standard buttons available in this build
+            pub const COUNT: usize = [$($(#[cfg($cfg)])* Button::$name,)*].len();
+
+            /// All standard buttons in declaration order
+            pub const ALL: [Button; Button::COUNT] = [$($(#[cfg($cfg)])* Button::$name,)*];
+
+            /// Get the string representation of the button
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $($(#[cfg($cfg)])* Button::$name => $str,)*
+                }
+            }
+
+            /// Get the lowercase aliases accepted for this button besides its name
+            pub fn aliases(&self) -> &'static [&'static str] {
+                match self {
+                    $($(#[cfg($cfg)])* Button::$name => &[$($alias),*],)*
+                }
+            }
+        }
+    };
 }
 
+standard_button_table!(define_button);
+
 impl Button {
-    /// Get the string representation of the button
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            Button::Left => "Left",
-            Button::Right => "Right",
-            Button::Middle => "Middle",
-            Button::X1 => "X1",
-            Button::X2 => "X2",
-            Button::Extra3 => "Extra3",
-            Button::Extra4 => "Extra4",
-            Button::Extra5 => "Extra5",
-            Button::Extra6 => "Extra6",
-            Button::Extra7 => "Extra7",
-            Button::Extra8 => "Extra8",
-            #[cfg(feature = "extended")]
-            Button::Extra9 => "Extra9",
-            #[cfg(feature = "extended")]
-            Button::Extra10 => "Extra10",
-        }
+    /// Iterate over all standard buttons without allocating
+    pub fn iter() -> impl Iterator<Item = Button> + Clone {
+        Self::ALL.into_iter()
     }
 
     /// Convert the button to a platform-specific code
+    #[cfg(not(feature = "const-table"))]
     pub fn to_code(&self, platform: Platform) -> usize {
         <Self as CodeMapper>::to_code(self, platform)
     }
 
+    /// Convert the button to a platform-specific code
+    #[cfg(feature = "const-table")]
+    pub const fn to_code(&self, platform: Platform) -> usize {
+        const_impl::platform_code(*self, platform)
+    }
+
     /// Parse a button from a platform-specific code
+    #[cfg(not(feature = "const-table"))]
     pub fn from_code(code: usize, platform: Platform) -> Option<Self> {
         <Self as CodeMapper>::from_code(code, platform)
     }
 
+    /// Parse a button from a platform-specific code
+    #[cfg(feature = "const-table")]
+    pub const fn from_code(code: usize, platform: Platform) -> Option<Self> {
+        const_impl::button_in(code, platform.code_space())
+    }
+
+    /// Convert the button to a platform-specific code without panicking
+    #[cfg(feature = "alloc")]
+    pub fn try_to_code(&self, platform: Platform) -> Result<usize, MouseParseError> {
+        <Self as crate::types::CodeMapper>::try_to_code(self, platform)
+    }
+
+    /// Parse a button from a platform-specific code, reporting unknown codes as an error
+    #[cfg(feature = "alloc")]
+    pub fn try_from_code(code: usize, platform: Platform) -> Result<Self, MouseParseError> {
+        <Self as crate::types::CodeMapper>::try_from_code(code, platform)
+    }
+
     /// Get the code for this button on the current platform
     pub fn to_current_platform_code(&self) -> usize {
         self.to_code(Platform::current())
@@ -97,6 +128,7 @@ impl Button {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl FromStr for Button {
     type Err = MouseParseError;
 
@@ -107,7 +139,7 @@ impl FromStr for Button {
 
 #[cfg(feature = "phf")]
 impl PhfHash for Button {
-    fn phf_hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    fn phf_hash<H: core::hash::Hasher>(&self, state: &mut H) {
         self.as_str().phf_hash(state);
     }
 }
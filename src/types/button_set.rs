@@ -0,0 +1,123 @@
+//! Compact set of mouse buttons
+
+use core::fmt;
+
+use crate::types::{Button, MouseEvent};
+
+/// Set of buttons, such as the buttons held down in a protocol packet
+///
+/// Stored as a bit mask indexed by the button's declaration order, so it is
+/// `Copy` and needs no allocation.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ButtonSet(u32);
+
+// 每个按钮占用一位
+const _: () = assert!(Button::COUNT <= u32::BITS as usize);
+
+impl ButtonSet {
+    /// The empty set
+    pub const EMPTY: ButtonSet = ButtonSet(0);
+
+    /// Create an empty set
+    pub const fn new() -> Self {
+        Self::EMPTY
+    }
+
+    const fn bit(button: Button) -> u32 {
+        1 << button as u32
+    }
+
+    /// Check whether `button` is in the set
+    pub const fn contains(&self, button: Button) -> bool {
+        self.0 & Self::bit(button) != 0
+    }
+
+    /// Get a copy of the set with `button` added
+    pub const fn with(self, button: Button) -> Self {
+        ButtonSet(self.0 | Self::bit(button))
+    }
+
+    /// Get a copy of the set with `button` removed
+    pub const fn without(self, button: Button) -> Self {
+        ButtonSet(self.0 & !Self::bit(button))
+    }
+
+    /// Add or remove `button` depending on `pressed`
+    pub fn set(&mut self, button: Button, pressed: bool) {
+        *self = if pressed {
+            self.with(button)
+        } else {
+            self.without(button)
+        };
+    }
+
+    /// Add `button`, returning whether it was newly added
+    pub fn insert(&mut self, button: Button) -> bool {
+        let added = !self.contains(button);
+        *self = self.with(button);
+        added
+    }
+
+    /// Remove `button`, returning whether it was present
+    pub fn remove(&mut self, button: Button) -> bool {
+        let present = self.contains(button);
+        *self = self.without(button);
+        present
+    }
+
+    /// Check whether the set is empty
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Number of buttons in the set
+    pub const fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Iterate over the buttons in declaration order
+    pub fn iter(self) -> impl Iterator<Item = Button> + Clone {
+        Button::iter().filter(move |button| self.contains(*button))
+    }
+
+    /// Get the press and release events leading from this set to `next`
+    ///
+    /// Events are ordered by button declaration order.
+    pub fn changes(self, next: ButtonSet) -> impl Iterator<Item = MouseEvent> + Clone {
+        Button::iter().filter_map(move |button| {
+            match (self.contains(button), next.contains(button)) {
+                (false, true) => Some(MouseEvent::Press(button)),
+                (true, false) => Some(MouseEvent::Release(button)),
+                _ => None,
+            }
+        })
+    }
+
+    /// Apply a press or release event, ignoring other events
+    pub fn apply(&mut self, event: &MouseEvent) {
+        match *event {
+            MouseEvent::Press(button) => self.set(button, true),
+            MouseEvent::Release(button) => self.set(button, false),
+            _ => {}
+        }
+    }
+}
+
+impl FromIterator<Button> for ButtonSet {
+    fn from_iter<I: IntoIterator<Item = Button>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::EMPTY, ButtonSet::with)
+    }
+}
+
+impl Extend<Button> for ButtonSet {
+    fn extend<I: IntoIterator<Item = Button>>(&mut self, iter: I) {
+        *self = iter.into_iter().fold(*self, ButtonSet::with);
+    }
+}
+
+impl fmt::Debug for ButtonSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
@@ -1,12 +1,32 @@
 use super::Platform;
+#[cfg(feature = "alloc")]
+use crate::error::MouseParseError;
 
 /// Trait for types that can be converted to and from platform-specific mouse codes
 pub trait CodeMapper {
     /// Convert the button to a platform-specific code
+    ///
+    /// Implementations may panic when the button has no code on `platform`;
+    /// use `try_to_code` in input paths that must not panic.
     fn to_code(&self, platform: Platform) -> usize;
 
     /// Parse a button from a platform-specific code
     fn from_code(code: usize, platform: Platform) -> Option<Self>
     where
         Self: Sized;
+
+    /// Convert the button to a platform-specific code without panicking
+    #[cfg(feature = "alloc")]
+    fn try_to_code(&self, platform: Platform) -> Result<usize, MouseParseError> {
+        Ok(self.to_code(platform))
+    }
+
+    /// Parse a button from a platform-specific code, reporting unknown codes as an error
+    #[cfg(feature = "alloc")]
+    fn try_from_code(code: usize, platform: Platform) -> Result<Self, MouseParseError>
+    where
+        Self: Sized,
+    {
+        Self::from_code(code, platform).ok_or(MouseParseError::InvalidButtonCode(code))
+    }
 }
@@ -1,4 +1,5 @@
-use std::fmt;
+use core::fmt;
+use core::time::Duration;
 
 use super::Button;
 
@@ -18,6 +19,35 @@ pub enum MouseEvent {
     RelativeMove(i32, i32),
 }
 
+impl MouseEvent {
+    /// Largest number of detents a `Scroll` is expanded into
+    ///
+    /// Encoders for formats that carry one wheel detent per message emit at
+    /// most this many messages for a single `Scroll`, so that a huge amount
+    /// cannot exhaust memory. The rest of a larger amount is dropped.
+    pub const MAX_SCROLL_DETENTS: i32 = 256;
+}
+
+/// Mouse event with the time at which it occurred
+///
+/// The time is measured from an arbitrary, source-defined origin, such as the
+/// kernel clock for evdev devices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimedEvent {
+    /// Time of the event
+    pub time: Duration,
+    /// The event itself
+    pub event: MouseEvent,
+}
+
+impl TimedEvent {
+    /// Create a timed event
+    pub const fn new(time: Duration, event: MouseEvent) -> Self {
+        Self { time, event }
+    }
+}
+
 /// Scroll direction enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -0,0 +1,171 @@
+//! Input backend detection
+//!
+//! The compilation target alone does not say which button numbering a Linux
+//! process receives: X11 clients see core protocol button numbers, while
+//! Wayland clients and programs reading evdev devices see kernel `BTN_*`
+//! codes. `InputBackend` captures that distinction at runtime.
+
+use core::fmt;
+
+use crate::mapping::CodeSpace;
+use crate::types::Platform;
+
+/// Windowing or input stack delivering mouse events
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InputBackend {
+    /// Win32 window messages
+    Windows,
+    /// Cocoa `NSEvent`s
+    MacOS,
+    /// X11 server, including XWayland
+    X11,
+    /// Native Wayland compositor
+    Wayland,
+    /// Raw evdev devices, e.g. on a headless system or console
+    Evdev,
+}
+
+impl InputBackend {
+    /// Get the display name of the backend
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            InputBackend::Windows => "Windows",
+            InputBackend::MacOS => "macOS",
+            InputBackend::X11 => "X11",
+            InputBackend::Wayland => "Wayland",
+            InputBackend::Evdev => "evdev",
+        }
+    }
+
+    /// Get the platform whose button numbering this backend uses
+    ///
+    /// `Platform::Linux` stands for the X11 and kernel numbering, so X11 and
+    /// Wayland sessions on the BSDs report it as well.
+    pub const fn platform(&self) -> Platform {
+        match self {
+            InputBackend::Windows => Platform::Windows,
+            InputBackend::MacOS => Platform::MacOS,
+            InputBackend::X11 | InputBackend::Wayland | InputBackend::Evdev => Platform::Linux,
+        }
+    }
+
+    /// Get the code space in which this backend reports buttons
+    pub const fn code_space(&self) -> CodeSpace {
+        match self {
+            InputBackend::Windows => CodeSpace::Windows,
+            InputBackend::MacOS => CodeSpace::MacOS,
+            InputBackend::X11 => CodeSpace::Linux,
+            // Wayland 的 wl_pointer.button 直接转发内核 BTN_* 代码
+            InputBackend::Wayland | InputBackend::Evdev => CodeSpace::Evdev,
+        }
+    }
+
+    /// Select the backend of a Unix session from its display variables
+    ///
+    /// `wayland_display` and `display` tell whether `WAYLAND_DISPLAY` and
+    /// `DISPLAY` are set. Wayland wins when both are, and a session with
+    /// neither is treated as headless.
+    pub const fn from_session(wayland_display: bool, display: bool) -> Self {
+        match (wayland_display, display) {
+            (true, _) => InputBackend::Wayland,
+            (false, true) => InputBackend::X11,
+            (false, false) => InputBackend::Evdev,
+        }
+    }
+
+    /// Detect the backend of the running process
+    ///
+    /// Returns `None` on targets without a known input stack, and on headless
+    /// NetBSD and OpenBSD, whose wscons consoles provide no evdev devices.
+    #[cfg(feature = "std")]
+    pub fn detect() -> Option<Self> {
+        #[cfg(target_os = "windows")]
+        return Some(InputBackend::Windows);
+
+        #[cfg(target_os = "macos")]
+        return Some(InputBackend::MacOS);
+
+        #[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "dragonfly"))]
+        return Some(Self::from_session(
+            env_is_set("WAYLAND_DISPLAY"),
+            env_is_set("DISPLAY"),
+        ));
+
+        #[cfg(any(target_os = "netbsd", target_os = "openbsd"))]
+        return match Self::from_session(env_is_set("WAYLAND_DISPLAY"), env_is_set("DISPLAY")) {
+            // wscons 控制台没有 evdev 设备
+            InputBackend::Evdev => None,
+            backend => Some(backend),
+        };
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "macos",
+            target_os = "linux",
+            target_os = "freebsd",
+            target_os = "dragonfly",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        )))]
+        None
+    }
+}
+
+#[cfg(all(
+    feature = "std",
+    any(
+        target_os = "linux",
+        target_os = "freebsd",
+        target_os = "dragonfly",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )
+))]
+fn env_is_set(name: &str) -> bool {
+    std::env::var_os(name).is_some_and(|value| !value.is_empty())
+}
+
+impl fmt::Display for InputBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_variables_select_the_backend() {
+        assert_eq!(
+            InputBackend::from_session(true, true),
+            InputBackend::Wayland
+        );
+        assert_eq!(
+            InputBackend::from_session(true, false),
+            InputBackend::Wayland
+        );
+        assert_eq!(InputBackend::from_session(false, true), InputBackend::X11);
+        assert_eq!(
+            InputBackend::from_session(false, false),
+            InputBackend::Evdev
+        );
+    }
+
+    #[test]
+    fn backends_select_their_code_space() {
+        assert_eq!(InputBackend::X11.code_space(), CodeSpace::Linux);
+        assert_eq!(InputBackend::Wayland.code_space(), CodeSpace::Evdev);
+        assert_eq!(InputBackend::Evdev.code_space(), CodeSpace::Evdev);
+        assert_eq!(InputBackend::Windows.code_space(), CodeSpace::Windows);
+        assert_eq!(InputBackend::MacOS.platform(), Platform::MacOS);
+        assert_eq!(InputBackend::Wayland.platform(), Platform::Linux);
+    }
+
+    #[cfg(all(feature = "std", target_os = "linux"))]
+    #[test]
+    fn linux_always_detects_a_backend() {
+        assert!(InputBackend::detect().is_some());
+    }
+}
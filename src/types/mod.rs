@@ -5,14 +5,23 @@
 
 /// Mouse button enumeration and related functionality
 pub mod button;
+/// Compact bit set of mouse buttons
+pub mod button_set;
 /// Trait definition for code mapping between buttons and platform-specific codes
 pub mod code_mapper;
 /// Mouse event types and scroll direction definitions
 pub mod event;
+/// Runtime detection of the windowing or input stack
+pub mod input_backend;
 /// Platform identifiers for cross-platform compatibility
 pub mod platform;
+/// Semantic button roles and handedness
+pub mod role;
 
 pub use button::Button;
+pub use button_set::ButtonSet;
 pub use code_mapper::CodeMapper;
-pub use event::{MouseEvent, ScrollDirection};
+pub use event::{MouseEvent, ScrollDirection, TimedEvent};
+pub use input_backend::InputBackend;
 pub use platform::Platform;
+pub use role::{ButtonRole, Handedness};
@@ -1,7 +1,8 @@
 //! Platform enumeration for cross-platform support
 
-use std::fmt;
+use core::fmt;
 
+use crate::mapping::CodeSpace;
 use crate::types::Button;
 
 /// Supported operating systems/platforms
@@ -17,19 +18,53 @@ pub enum Platform {
 }
 
 impl Platform {
+    /// All built-in platforms
+    pub const ALL: [Platform; 3] = [Platform::Windows, Platform::Linux, Platform::MacOS];
+
+    /// Get the display name of the platform
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Platform::Windows => "Windows",
+            Platform::Linux => "Linux",
+            Platform::MacOS => "macOS",
+        }
+    }
+
+    /// Get the code space holding this platform's standard codes
+    ///
+    /// For Linux this is the X11 numbering; use `InputBackend` to pick evdev
+    /// codes on Wayland or headless systems.
+    pub const fn code_space(&self) -> CodeSpace {
+        match self {
+            Platform::Windows => CodeSpace::Windows,
+            Platform::Linux => CodeSpace::Linux,
+            Platform::MacOS => CodeSpace::MacOS,
+        }
+    }
+
     /// Get the current platform based on compilation target
+    ///
+    /// # Panics
+    ///
+    /// Panics on targets other than Windows, Linux and macOS; use `try_current`
+    /// where the crate may run elsewhere.
     pub fn current() -> Self {
+        Self::try_current().expect("Unsupported platform")
+    }
+
+    /// Get the current platform based on compilation target, if it is supported
+    pub const fn try_current() -> Option<Self> {
         #[cfg(target_os = "windows")]
-        return Platform::Windows;
+        return Some(Platform::Windows);
 
         #[cfg(target_os = "linux")]
-        return Platform::Linux;
+        return Some(Platform::Linux);
 
         #[cfg(target_os = "macos")]
-        return Platform::MacOS;
+        return Some(Platform::MacOS);
 
         #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
-        panic!("Unsupported platform");
+        None
     }
 
     /// Get code for button on this platform
@@ -50,23 +85,15 @@ impl Platform {
 
 impl fmt::Display for Platform {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Platform::Windows => write!(f, "Windows"),
-            Platform::Linux => write!(f, "Linux"),
-            Platform::MacOS => write!(f, "macOS"),
-        }
+        write!(f, "{}", self.as_str())
     }
 }
 
-impl std::str::FromStr for Platform {
+#[cfg(feature = "alloc")]
+impl core::str::FromStr for Platform {
     type Err = crate::error::MouseParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "windows" => Ok(Platform::Windows),
-            "linux" => Ok(Platform::Linux),
-            "macos" | "osx" => Ok(Platform::MacOS),
-            _ => Err(crate::error::MouseParseError::UnknownPlatform),
-        }
+        crate::parser::borrowed::parse_platform(s).map_err(Into::into)
     }
 }
@@ -0,0 +1,234 @@
+//! Semantic button roles
+//!
+//! Applications that bind to `Primary` and `Secondary` rather than `Left`
+//! and `Right` work for left-handed users without special cases: the roles
+//! are resolved against a `Handedness` setting into physical buttons. The
+//! numbering of the DOM `MouseEvent.button` property already follows roles
+//! (main, auxiliary, secondary, back, forward), since browsers report the
+//! buttons after the system has applied its handedness setting.
+
+use core::fmt;
+
+use super::Button;
+
+/// Which hand the mouse is set up for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Handedness {
+    /// Primary on the left button
+    #[default]
+    Right,
+    /// Primary on the right button
+    Left,
+}
+
+impl Handedness {
+    /// Get the other handedness
+    pub const fn flipped(&self) -> Self {
+        match self {
+            Handedness::Right => Handedness::Left,
+            Handedness::Left => Handedness::Right,
+        }
+    }
+}
+
+/// Semantic role of a button
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ButtonRole {
+    /// Main button: select, activate
+    Primary,
+    /// Context menu button
+    Secondary,
+    /// Wheel button
+    Auxiliary,
+    /// Navigate back
+    Back,
+    /// Navigate forward
+    Forward,
+}
+
+impl ButtonRole {
+    /// All roles, in DOM `button` order
+    pub const ALL: [ButtonRole; 5] = [
+        ButtonRole::Primary,
+        ButtonRole::Auxiliary,
+        ButtonRole::Secondary,
+        ButtonRole::Back,
+        ButtonRole::Forward,
+    ];
+
+    /// Get the name of the role
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            ButtonRole::Primary => "Primary",
+            ButtonRole::Secondary => "Secondary",
+            ButtonRole::Auxiliary => "Auxiliary",
+            ButtonRole::Back => "Back",
+            ButtonRole::Forward => "Forward",
+        }
+    }
+
+    /// Get the lowercase aliases accepted for this role besides its name
+    pub const fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            ButtonRole::Primary => &["main"],
+            ButtonRole::Secondary => &["context"],
+            ButtonRole::Auxiliary => &["aux"],
+            ButtonRole::Back => &[],
+            ButtonRole::Forward => &[],
+        }
+    }
+
+    /// Get the physical button playing this role
+    pub const fn button(&self, handedness: Handedness) -> Button {
+        match (self, handedness) {
+            (ButtonRole::Primary, Handedness::Right) => Button::Left,
+            (ButtonRole::Primary, Handedness::Left) => Button::Right,
+            (ButtonRole::Secondary, Handedness::Right) => Button::Right,
+            (ButtonRole::Secondary, Handedness::Left) => Button::Left,
+            (ButtonRole::Auxiliary, _) => Button::Middle,
+            (ButtonRole::Back, _) => Button::X1,
+            (ButtonRole::Forward, _) => Button::X2,
+        }
+    }
+
+    /// Get the role a physical button plays, if any
+    pub const fn of(button: Button, handedness: Handedness) -> Option<Self> {
+        match (button, handedness) {
+            (Button::Left, Handedness::Right) | (Button::Right, Handedness::Left) => {
+                Some(ButtonRole::Primary)
+            }
+            (Button::Right, Handedness::Right) | (Button::Left, Handedness::Left) => {
+                Some(ButtonRole::Secondary)
+            }
+            (Button::Middle, _) => Some(ButtonRole::Auxiliary),
+            (Button::X1, _) => Some(ButtonRole::Back),
+            (Button::X2, _) => Some(ButtonRole::Forward),
+            _ => None,
+        }
+    }
+
+    /// Get the DOM `MouseEvent.button` value
+    pub const fn dom_button(&self) -> u8 {
+        match self {
+            ButtonRole::Primary => 0,
+            ButtonRole::Auxiliary => 1,
+            ButtonRole::Secondary => 2,
+            ButtonRole::Back => 3,
+            ButtonRole::Forward => 4,
+        }
+    }
+
+    /// Get the role of a DOM `MouseEvent.button` value
+    pub const fn from_dom_button(button: u8) -> Option<Self> {
+        match button {
+            0 => Some(ButtonRole::Primary),
+            1 => Some(ButtonRole::Auxiliary),
+            2 => Some(ButtonRole::Secondary),
+            3 => Some(ButtonRole::Back),
+            4 => Some(ButtonRole::Forward),
+            _ => None,
+        }
+    }
+
+    /// Get the bit of this role in the DOM `MouseEvent.buttons` mask
+    pub const fn dom_buttons_bit(&self) -> u16 {
+        match self {
+            ButtonRole::Primary => 1,
+            ButtonRole::Secondary => 2,
+            ButtonRole::Auxiliary => 4,
+            ButtonRole::Back => 8,
+            ButtonRole::Forward => 16,
+        }
+    }
+}
+
+impl fmt::Display for ButtonRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::str::FromStr for ButtonRole {
+    type Err = crate::error::MouseParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        crate::parser::parse_button_role(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roles_resolve_against_handedness() {
+        for handedness in [Handedness::Right, Handedness::Left] {
+            for role in ButtonRole::ALL {
+                let button = role.button(handedness);
+                assert_eq!(ButtonRole::of(button, handedness), Some(role));
+            }
+        }
+        assert_eq!(ButtonRole::Primary.button(Handedness::Right), Button::Left);
+        assert_eq!(ButtonRole::Primary.button(Handedness::Left), Button::Right);
+        assert_eq!(
+            ButtonRole::Secondary.button(Handedness::Right.flipped()),
+            Button::Left
+        );
+        assert_eq!(
+            ButtonRole::Auxiliary.button(Handedness::Left),
+            Button::Middle
+        );
+        assert_eq!(ButtonRole::of(Button::Extra3, Handedness::Right), None);
+        assert_eq!(Handedness::default(), Handedness::Right);
+    }
+
+    #[test]
+    fn dom_values_follow_roles() {
+        // button 的编号为 主、辅助、次，buttons 掩码中次键与辅助键的位置相反
+        for (value, role) in ButtonRole::ALL.into_iter().enumerate() {
+            assert_eq!(role.dom_button(), value as u8);
+            assert_eq!(ButtonRole::from_dom_button(value as u8), Some(role));
+        }
+        assert_eq!(ButtonRole::from_dom_button(5), None);
+        assert_eq!(ButtonRole::Secondary.dom_buttons_bit(), 2);
+        assert_eq!(ButtonRole::Auxiliary.dom_buttons_bit(), 4);
+        let mask = ButtonRole::ALL
+            .iter()
+            .fold(0, |mask, role| mask | role.dom_buttons_bit());
+        assert_eq!(mask, 0b1_1111);
+    }
+
+    #[test]
+    fn role_names_are_not_button_aliases() {
+        use crate::parser::borrowed::{
+            parse_button_for, parse_button_role, parse_button_with_aliases,
+        };
+
+        for name in [
+            "primary",
+            "Secondary",
+            "auxiliary",
+            "main",
+            "context",
+            "aux",
+        ] {
+            assert!(parse_button_with_aliases(name).is_err(), "{}", name);
+            assert!(parse_button_role(name).is_ok(), "{}", name);
+        }
+        assert_eq!(parse_button_role("MAIN"), Ok(ButtonRole::Primary));
+        assert_eq!(parse_button_role("aux"), Ok(ButtonRole::Auxiliary));
+        assert_eq!(
+            parse_button_for("primary", Handedness::Left),
+            Ok(Button::Right)
+        );
+        assert_eq!(
+            parse_button_for("context", Handedness::Left),
+            Ok(Button::Left)
+        );
+        assert_eq!(parse_button_for("back", Handedness::Left), Ok(Button::X1));
+        assert_eq!(parse_button_for("rmb", Handedness::Left), Ok(Button::Right));
+    }
+}